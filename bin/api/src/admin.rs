@@ -0,0 +1,46 @@
+//! Admin authentication — endpoints that change server-side state require
+//! `Authorization: Bearer <ADMIN_TOKEN>`.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+use crate::{ApiError, AppState};
+
+/// Extractor that only succeeds for requests carrying the admin token.
+pub struct Admin;
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for Admin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(expected) = state.admin_token.as_deref() else {
+            return Err(ApiError::Unauthorized(
+                "Admin endpoints are disabled (ADMIN_TOKEN is not set)".into(),
+            ));
+        };
+
+        let provided = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or_default();
+
+        if bool::from(provided.as_bytes().ct_eq(expected.as_bytes())) {
+            Ok(Admin)
+        } else {
+            Err(ApiError::Unauthorized(
+                "Invalid or missing admin token".into(),
+            ))
+        }
+    }
+}
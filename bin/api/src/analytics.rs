@@ -0,0 +1,652 @@
+//! Research analytics: holder distribution, velocity, issuance and entity flows.
+
+use axum::{extract::State, response::IntoResponse};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tempulse_core::address::TokenAddress;
+use tempulse_storage::{
+    self as storage,
+    models::{
+        CohortTurnover, HolderSnapshot, IssuanceInterval, IssuanceParty, IssuancePeriod,
+        TokenIssuance, VelocityDay, VelocitySummary,
+    },
+    repos::HOLDER_BUCKETS,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    AmountFormat, ApiError, ApiResponse, AppState, ErrorBody, FormatParams, PaginationParams, Path,
+    Query, json_ok,
+    network::{ChainId, NetworkParams},
+};
+
+/// Entries in the top minter / redeemer lists.
+const TOP_ISSUANCE_PARTIES: i64 = 10;
+
+/// Links in a flow graph when `?limit=` is omitted.
+const DEFAULT_FLOW_LINKS: i64 = 100;
+
+/// Largest accepted `?days=` window.
+const MAX_WINDOW_DAYS: i64 = 366;
+
+/// `?days=` — how many days, ending today, an analytics window covers.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WindowParams {
+    days: Option<i64>,
+}
+
+/// Resolve a `?days=` parameter, rejecting values outside `1..=MAX_WINDOW_DAYS`.
+fn window_days(days: Option<i64>, default: i32) -> Result<i32, ApiError> {
+    match days {
+        None => Ok(default),
+        Some(d) if (1..=MAX_WINDOW_DAYS).contains(&d) => Ok(d as i32),
+        Some(d) => Err(ApiError::InvalidParameter(format!(
+            "days must be between 1 and {MAX_WINDOW_DAYS}, got {d}"
+        ))),
+    }
+}
+
+// ─── Holder Distribution ────────────────────────────────────────────────────
+
+/// One day of a token's holder distribution.
+#[derive(Serialize, ToSchema)]
+pub struct DistributionEntry {
+    date: String,
+    holder_count: i64,
+    /// Sum of positive balances.
+    total_balance: String,
+    /// Gini coefficient of balances: 0 (equal) to 1 (one holder owns everything).
+    gini: String,
+    /// Fewest holders that together own more than half of `total_balance`.
+    nakamoto_coefficient: i64,
+    /// Fraction of `total_balance` held by the 10 largest holders.
+    top10_share: String,
+    /// Fraction of `total_balance` held by the 100 largest holders.
+    top100_share: String,
+    /// Holders that were not holders in the previous snapshot.
+    new_holders: i64,
+    /// Previous snapshot's holders whose balance is now zero.
+    churned_holders: i64,
+    histogram: Vec<BalanceBucket>,
+}
+
+/// Holders whose balance is in `[min, max)` whole tokens; the last bucket has no `max`.
+#[derive(Serialize, ToSchema)]
+pub struct BalanceBucket {
+    min: String,
+    max: Option<String>,
+    holders: i64,
+}
+
+/// Bounds of bucket `i` in whole tokens, matching [`HOLDER_BUCKETS`].
+fn bucket_bounds(i: usize) -> (String, Option<String>) {
+    let min = if i == 0 {
+        "0".to_string()
+    } else {
+        10u64.pow(i as u32 - 1).to_string()
+    };
+    let max = (i + 1 < HOLDER_BUCKETS).then(|| 10u64.pow(i as u32).to_string());
+    (min, max)
+}
+
+impl DistributionEntry {
+    fn new(s: HolderSnapshot, format: AmountFormat, decimals: i16) -> Self {
+        let histogram = s
+            .balance_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, &holders)| {
+                let (min, max) = bucket_bounds(i);
+                BalanceBucket { min, max, holders }
+            })
+            .collect();
+        Self {
+            date: s.day.to_string(),
+            holder_count: s.holder_count,
+            total_balance: format.render(s.total_balance, decimals),
+            gini: s.gini,
+            nakamoto_coefficient: s.nakamoto,
+            top10_share: s.top10_share,
+            top100_share: s.top100_share,
+            new_holders: s.new_holders,
+            churned_holders: s.churned_holders,
+            histogram,
+        }
+    }
+}
+
+/// GET /api/v1/tokens/:address/distribution — daily holder distribution snapshots, newest first.
+///
+/// Snapshots are taken hourly by the indexer from current balances; a past day
+/// reports the last snapshot taken that day.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}/distribution",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), PaginationParams, NetworkParams),
+    responses(
+        (status = 200, description = "Holder distribution per day", body = ApiResponse<Vec<DistributionEntry>>),
+        (status = 404, description = "Token not found", body = ErrorBody),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_distribution(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
+    Query(params): Query<PaginationParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 30)?;
+    let token = storage::repos::get_token(&state.pool, chain.0, &address)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Token not found".into()))?;
+    let snapshots =
+        storage::repos::get_holder_snapshots(&state.pool, chain.0, &address, limit).await?;
+
+    let entries: Vec<DistributionEntry> = snapshots
+        .into_iter()
+        .map(|s| DistributionEntry::new(s, params.format, token.decimals))
+        .collect();
+    Ok(json_ok(entries))
+}
+
+// ─── Velocity ───────────────────────────────────────────────────────────────
+
+#[derive(Serialize, ToSchema)]
+pub struct VelocityResponse {
+    token_address: String,
+    window_days: i32,
+    summary: VelocitySummary,
+    /// One entry per calendar day of the window, newest first.
+    daily: Vec<VelocityDay>,
+    /// Turnover by holder cohort.
+    cohorts: Vec<CohortTurnover>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CurrencyVelocityResponse {
+    window_days: i32,
+    /// One entry per currency; amounts of different currencies are never summed.
+    summaries: Vec<VelocitySummary>,
+    /// One entry per currency and calendar day of the window, grouped by
+    /// currency and newest first.
+    daily: Vec<VelocityDay>,
+}
+
+/// GET /api/v1/tokens/:address/velocity — velocity, holding time and cohort turnover.
+///
+/// Supply over time is the token's current total supply less the indexed mints
+/// and burns since.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}/velocity",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), WindowParams, FormatParams, NetworkParams),
+    responses(
+        (status = 200, description = "Token velocity", body = ApiResponse<VelocityResponse>),
+        (status = 404, description = "Token not found", body = ErrorBody),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_token_velocity(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
+    Query(window): Query<WindowParams>,
+    Query(params): Query<FormatParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let days = window_days(window.days, 30)?;
+    let token = storage::repos::get_token(&state.pool, chain.0, &address)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Token not found".into()))?;
+
+    let mut summary =
+        storage::repos::get_velocity_summaries(&state.pool, chain.0, Some(&address), days)
+            .await?
+            .pop()
+            .ok_or_else(|| ApiError::NotFound("Token not found".into()))?;
+    let mut daily =
+        storage::repos::get_velocity_days(&state.pool, chain.0, Some(&address), days).await?;
+    let mut cohorts =
+        storage::repos::get_cohort_turnover(&state.pool, chain.0, &address, days).await?;
+
+    if params.format == AmountFormat::Decimal {
+        let render = |raw: &mut String| {
+            *raw = params.format.render(std::mem::take(raw), token.decimals);
+        };
+        render(&mut summary.transfer_volume);
+        render(&mut summary.average_supply);
+        for d in &mut daily {
+            render(&mut d.transfer_volume);
+            render(&mut d.average_supply);
+        }
+        for c in &mut cohorts {
+            render(&mut c.balance);
+            render(&mut c.outgoing_volume);
+        }
+    }
+
+    Ok(json_ok(VelocityResponse {
+        token_address: token.address,
+        window_days: days,
+        summary,
+        daily,
+        cohorts,
+    }))
+}
+
+/// GET /api/v1/stats/velocity — velocity and holding time across the tokens of each currency.
+///
+/// Amounts are scaled by each token's `decimals` before summing, so they are
+/// always decimal strings.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/velocity",
+    tag = "stats",
+    params(WindowParams, NetworkParams),
+    responses(
+        (status = 200, description = "Cross-token velocity per currency", body = ApiResponse<CurrencyVelocityResponse>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_velocity(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Query(window): Query<WindowParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let days = window_days(window.days, 30)?;
+    let summaries =
+        storage::repos::get_velocity_summaries(&state.pool, chain.0, None, days).await?;
+    let daily = storage::repos::get_velocity_days(&state.pool, chain.0, None, days).await?;
+
+    Ok(json_ok(CurrencyVelocityResponse {
+        window_days: days,
+        summaries,
+        daily,
+    }))
+}
+
+// ─── Issuance ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IssuanceParams {
+    /// `day` (default) or `week`.
+    #[serde(default)]
+    interval: IssuanceInterval,
+    /// Number of periods, ending with the current one (default 30 days or 12 weeks).
+    limit: Option<i64>,
+    #[serde(default)]
+    format: AmountFormat,
+}
+
+impl IssuanceParams {
+    /// First day covered by the requested periods.
+    fn since(&self, state: &AppState) -> Result<chrono::NaiveDate, ApiError> {
+        let default = match self.interval {
+            IssuanceInterval::Day => 30,
+            IssuanceInterval::Week => 12,
+        };
+        let periods = state.page_limit(self.limit, default)?;
+        Ok(self
+            .interval
+            .window_start(chrono::Utc::now().date_naive(), periods))
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TokenIssuanceResponse {
+    token_address: String,
+    interval: IssuanceInterval,
+    /// First day of the window; the top lists cover the same window as `periods`.
+    since: String,
+    /// Periods with mint or burn activity, newest first.
+    periods: Vec<IssuancePeriod>,
+    /// Largest recipients of newly minted tokens.
+    top_minters: Vec<IssuanceParty>,
+    /// Holders whose tokens were burned, by volume.
+    top_redeemers: Vec<IssuanceParty>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct IssuanceResponse {
+    interval: IssuanceInterval,
+    since: String,
+    /// Periods with mint or burn activity across all tokens, newest first.
+    periods: Vec<IssuancePeriod>,
+    /// Per-token totals over the window, largest net issuance first.
+    tokens: Vec<TokenIssuance>,
+}
+
+/// GET /api/v1/tokens/:address/issuance — mint/burn volume and net issuance per day or week.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}/issuance",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), IssuanceParams, NetworkParams),
+    responses(
+        (status = 200, description = "Token issuance", body = ApiResponse<TokenIssuanceResponse>),
+        (status = 404, description = "Token not found", body = ErrorBody),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_token_issuance(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
+    Query(params): Query<IssuanceParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let since = params.since(&state)?;
+    let token = storage::repos::get_token(&state.pool, chain.0, &address)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Token not found".into()))?;
+
+    let mut periods = storage::repos::get_issuance_periods(
+        &state.pool,
+        chain.0,
+        Some(&address),
+        params.interval,
+        since,
+    )
+    .await?;
+    let mut top_minters = storage::repos::get_top_issuance_parties(
+        &state.pool,
+        chain.0,
+        &address,
+        "mint",
+        since,
+        TOP_ISSUANCE_PARTIES,
+    )
+    .await?;
+    let mut top_redeemers = storage::repos::get_top_issuance_parties(
+        &state.pool,
+        chain.0,
+        &address,
+        "burn",
+        since,
+        TOP_ISSUANCE_PARTIES,
+    )
+    .await?;
+
+    if params.format == AmountFormat::Decimal {
+        let render = |raw: &mut String| {
+            *raw = params.format.render(std::mem::take(raw), token.decimals);
+        };
+        for p in &mut periods {
+            render(&mut p.mint_volume);
+            render(&mut p.burn_volume);
+            render(&mut p.net_issuance);
+        }
+        for p in top_minters.iter_mut().chain(&mut top_redeemers) {
+            render(&mut p.volume);
+        }
+    }
+
+    Ok(json_ok(TokenIssuanceResponse {
+        token_address: token.address,
+        interval: params.interval,
+        since: since.to_string(),
+        periods,
+        top_minters,
+        top_redeemers,
+    }))
+}
+
+/// GET /api/v1/stats/issuance — mint/burn flows across all tokens.
+///
+/// Amounts are scaled by each token's `decimals` before summing, so they are
+/// always decimal strings.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/issuance",
+    tag = "stats",
+    params(IssuanceParams, NetworkParams),
+    responses(
+        (status = 200, description = "Cross-token issuance", body = ApiResponse<IssuanceResponse>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_issuance(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Query(params): Query<IssuanceParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let since = params.since(&state)?;
+    let periods =
+        storage::repos::get_issuance_periods(&state.pool, chain.0, None, params.interval, since)
+            .await?;
+    let tokens = storage::repos::get_token_issuance_totals(&state.pool, chain.0, since).await?;
+
+    Ok(json_ok(IssuanceResponse {
+        interval: params.interval,
+        since: since.to_string(),
+        periods,
+        tokens,
+    }))
+}
+
+// ─── Entity Flows ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FlowParams {
+    /// Window length in days, ending with `to` (default 30). Not allowed with `from`.
+    days: Option<i64>,
+    /// First day of the window (`YYYY-MM-DD`), inclusive.
+    from: Option<NaiveDate>,
+    /// Last day of the window (`YYYY-MM-DD`), inclusive; defaults to today.
+    to: Option<NaiveDate>,
+    /// Comma-separated token addresses; all tokens when omitted.
+    tokens: Option<String>,
+    /// Maximum number of links, largest first (default 100).
+    limit: Option<i64>,
+}
+
+/// Which column of the Sankey diagram a node sits in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowSide {
+    Source,
+    Target,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FlowNode {
+    name: String,
+    side: FlowSide,
+}
+
+/// Volume sent from `nodes[source]` to `nodes[target]`.
+#[derive(Serialize, ToSchema)]
+pub struct FlowLink {
+    source: usize,
+    target: usize,
+    /// Decimal units, summed across tokens.
+    value: String,
+    transfer_count: i64,
+}
+
+/// A two-column Sankey graph: every entity that sends appears once as a
+/// source node and every entity that receives once as a target node, so the
+/// graph is acyclic even when entities send to each other or to themselves.
+#[derive(Serialize, ToSchema)]
+pub struct FlowGraph {
+    /// First day of the window.
+    since: String,
+    /// Last day of the window.
+    until: String,
+    /// Tokens the flows are limited to; `null` means all tokens.
+    tokens: Option<Vec<String>>,
+    nodes: Vec<FlowNode>,
+    links: Vec<FlowLink>,
+}
+
+/// GET /api/v1/stats/flows — token flows between entities as a Sankey graph.
+///
+/// Addresses outside any entity are grouped as `(unknown)` and the zero
+/// address of mints and burns as `(mint/burn)`; flows from one unknown
+/// address to another are left out.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/flows",
+    tag = "stats",
+    params(FlowParams, NetworkParams),
+    responses(
+        (status = 200, description = "Entity flow graph", body = ApiResponse<FlowGraph>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_flows(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Query(params): Query<FlowParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (since, until) = flow_window(&params, chrono::Utc::now().date_naive())?;
+    let limit = state.page_limit(params.limit, DEFAULT_FLOW_LINKS)?;
+    let tokens = params
+        .tokens
+        .as_deref()
+        .map(|list| {
+            list.split(',')
+                .map(|t| {
+                    t.trim()
+                        .parse::<TokenAddress>()
+                        .map(String::from)
+                        .map_err(|e| ApiError::InvalidParameter(e.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    let flows = storage::repos::get_entity_flows(
+        &state.pool,
+        chain.0,
+        since,
+        until,
+        tokens.as_deref(),
+        limit,
+    )
+    .await?;
+
+    let mut nodes = Vec::new();
+    let mut index: HashMap<(String, FlowSide), usize> = HashMap::new();
+    let mut node = |name: &str, side: FlowSide| {
+        *index.entry((name.to_string(), side)).or_insert_with(|| {
+            nodes.push(FlowNode {
+                name: name.to_string(),
+                side,
+            });
+            nodes.len() - 1
+        })
+    };
+    let links = flows
+        .into_iter()
+        .map(|f| FlowLink {
+            source: node(&f.source_entity, FlowSide::Source),
+            target: node(&f.target_entity, FlowSide::Target),
+            value: f.volume,
+            transfer_count: f.transfer_count,
+        })
+        .collect();
+
+    Ok(json_ok(FlowGraph {
+        since: since.to_string(),
+        until: until.to_string(),
+        tokens,
+        nodes,
+        links,
+    }))
+}
+
+/// Resolve a flow window to its first and last day: `from..=to`, or `days`
+/// days ending with `to`. `to` defaults to `today`.
+fn flow_window(params: &FlowParams, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), ApiError> {
+    let until = params.to.unwrap_or(today);
+    let since = match (params.from, params.days) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::InvalidParameter(
+                "days and from cannot be combined".into(),
+            ));
+        }
+        (Some(from), None) => from,
+        (None, days) => until - chrono::Days::new(window_days(days, 30)? as u64 - 1),
+    };
+    if since > until {
+        return Err(ApiError::InvalidParameter(format!(
+            "from ({since}) is after to ({until})"
+        )));
+    }
+    if (until - since).num_days() >= MAX_WINDOW_DAYS {
+        return Err(ApiError::InvalidParameter(format!(
+            "the window may span at most {MAX_WINDOW_DAYS} days"
+        )));
+    }
+    Ok((since, until))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn params(days: Option<i64>, from: Option<&str>, to: Option<&str>) -> FlowParams {
+        FlowParams {
+            days,
+            from: from.map(day),
+            to: to.map(day),
+            tokens: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn flow_window_defaults_to_thirty_days_ending_today() {
+        let today = day("2025-03-31");
+        assert_eq!(
+            flow_window(&params(None, None, None), today).unwrap(),
+            (day("2025-03-02"), today)
+        );
+        assert_eq!(
+            flow_window(&params(Some(1), None, None), today).unwrap(),
+            (today, today)
+        );
+    }
+
+    #[test]
+    fn flow_window_takes_an_explicit_range() {
+        let today = day("2025-03-31");
+        assert_eq!(
+            flow_window(&params(None, Some("2024-12-01"), Some("2024-12-31")), today).unwrap(),
+            (day("2024-12-01"), day("2024-12-31"))
+        );
+        assert_eq!(
+            flow_window(&params(None, Some("2025-03-01"), None), today).unwrap(),
+            (day("2025-03-01"), today)
+        );
+        assert_eq!(
+            flow_window(&params(Some(7), None, Some("2025-01-07")), today).unwrap(),
+            (day("2025-01-01"), day("2025-01-07"))
+        );
+    }
+
+    #[test]
+    fn flow_window_rejects_bad_ranges() {
+        let today = day("2025-03-31");
+        for bad in [
+            params(Some(7), Some("2025-03-01"), None),
+            params(None, Some("2025-03-02"), Some("2025-03-01")),
+            params(None, Some("2023-01-01"), Some("2025-01-01")),
+            params(Some(0), None, None),
+        ] {
+            assert!(matches!(
+                flow_window(&bad, today),
+                Err(ApiError::InvalidParameter(_))
+            ));
+        }
+    }
+}
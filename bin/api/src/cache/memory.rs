@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use lru::LruCache;
+use std::{
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::{CacheBackend, CachedResponse};
+
+/// In-process LRU cache; entries also expire after their TTL.
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, (Instant, CachedResponse)>>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        match entries.get(key) {
+            Some((expires, value)) if *expires > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn put(&self, key: &str, value: CachedResponse, ttl: Duration) {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        entries.put(key.to_string(), (Instant::now() + ttl, value));
+    }
+}
@@ -0,0 +1,354 @@
+//! Response cache for the read-only REST endpoints.
+//!
+//! Cache keys include the `last_indexed_block` of the network a request reads, so
+//! every batch committed on that network implicitly invalidates its entries (and
+//! only its entries), plus its token re-index and label write counts, for the
+//! writes that do not move the cursor; per-route TTLs bound staleness for
+//! data that changes independently of blocks (e.g. FX rates). Cached responses
+//! carry an `ETag` and honor `If-None-Match`.
+
+mod memory;
+#[cfg(feature = "redis")]
+mod redis;
+
+use async_trait::async_trait;
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{MatchedPath, Query, Request, State},
+    http::{
+        HeaderValue, Method, StatusCode,
+        header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tempulse_core::{AppError, Settings};
+use tempulse_storage as storage;
+
+pub use memory::MemoryCache;
+#[cfg(feature = "redis")]
+pub use redis::RedisCache;
+
+/// How long each cacheable route may be served from cache.
+/// Routes not listed here are never cached.
+const ROUTE_TTLS: &[(&str, Duration)] = &[
+    ("/api/v1/tokens", Duration::from_secs(60)),
+    ("/api/v1/tokens/:address", Duration::from_secs(30)),
+    ("/api/v1/tokens/:address/holders", Duration::from_secs(30)),
+    ("/api/v1/tokens/:address/transfers", Duration::from_secs(15)),
+    (
+        "/api/v1/tokens/:address/volume/daily",
+        Duration::from_secs(60),
+    ),
+    (
+        "/api/v1/tokens/:address/distribution",
+        Duration::from_secs(300),
+    ),
+    ("/api/v1/tokens/:address/velocity", Duration::from_secs(300)),
+    ("/api/v1/stats/volume", Duration::from_secs(60)),
+    ("/api/v1/tokens/:address/issuance", Duration::from_secs(300)),
+    ("/api/v1/stats/velocity", Duration::from_secs(300)),
+    ("/api/v1/stats/issuance", Duration::from_secs(300)),
+    ("/api/v1/stats/flows", Duration::from_secs(300)),
+    ("/api/v1/stats/overview", Duration::from_secs(60)),
+    ("/api/v1/stats/daily", Duration::from_secs(300)),
+    ("/api/v1/stats/monthly", Duration::from_secs(300)),
+    ("/api/v1/activity/recent", Duration::from_secs(5)),
+    ("/api/v1/fx/rates", Duration::from_secs(300)),
+];
+
+/// Responses larger than this are passed through without caching.
+const MAX_CACHED_BODY: usize = 8 * 1024 * 1024;
+
+/// How often the indexer cursors are re-read.
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn ttl_for(route: &str) -> Option<Duration> {
+    ROUTE_TTLS
+        .iter()
+        .find(|(path, _)| *path == route)
+        .map(|(_, ttl)| *ttl)
+}
+
+/// A cached `200 OK` JSON response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: String,
+}
+
+/// Storage for cached responses.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Look up a live entry. Backend failures count as a miss.
+    async fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store an entry for `ttl`. Backend failures are logged and ignored.
+    async fn put(&self, key: &str, value: CachedResponse, ttl: Duration);
+}
+
+/// A cache backend plus the indexer progress its keys are scoped to.
+pub struct ResponseCache {
+    backend: Box<dyn CacheBackend>,
+    /// Network read by requests without `?network=`.
+    default_network: String,
+    /// Each registered network's `last_indexed_block`, re-index count and label
+    /// write count, by name.
+    cursors: RwLock<HashMap<String, (i64, i64, i64)>>,
+}
+
+impl ResponseCache {
+    pub fn new(backend: Box<dyn CacheBackend>, default_network: String) -> Self {
+        Self {
+            backend,
+            default_network,
+            cursors: RwLock::default(),
+        }
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Keep the indexer progress current; a new block on a network changes
+    /// the cache keys of the requests reading it.
+    pub async fn watch_indexer(self: Arc<Self>, pool: sqlx::PgPool) {
+        let mut interval = tokio::time::interval(BLOCK_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            match storage::repos::get_network_cursors(&pool).await {
+                Ok(cursors) => {
+                    *self.cursors.write().expect("cursors lock") = cursors
+                        .into_iter()
+                        .map(|(name, block, reindexes, labels)| (name, (block, reindexes, labels)))
+                        .collect();
+                }
+                Err(e) => tracing::debug!(error = %e, "Could not read indexer cursors"),
+            }
+        }
+    }
+
+    /// Key of `request`, scoped to the cursor of the network it reads. `None`
+    /// for a network not registered (as of the last poll), which is not cached.
+    fn key(&self, request: &Request) -> Option<String> {
+        let Query(params) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+        let network = params
+            .get("network")
+            .map_or(self.default_network.as_str(), String::as_str);
+        let (block, reindexes, labels) =
+            *self.cursors.read().expect("cursors lock").get(network)?;
+        Some(format!(
+            "tempulse:response:{network}:{block}.{reindexes}.{labels}:{}",
+            request.uri()
+        ))
+    }
+}
+
+/// Build the backend selected by the settings: Redis when `REDIS_URL` is set
+/// (and the `redis` feature is compiled in), otherwise an in-process LRU.
+/// Returns `None` when caching is disabled.
+pub async fn from_settings(settings: &Settings) -> Result<Option<ResponseCache>, AppError> {
+    if let Some(url) = &settings.api.redis_url {
+        #[cfg(feature = "redis")]
+        {
+            let backend = RedisCache::connect(url)
+                .await
+                .map_err(|e| AppError::Config(format!("cannot connect to REDIS_URL: {e}")))?;
+            return Ok(Some(ResponseCache::new(
+                Box::new(backend),
+                settings.network.clone(),
+            )));
+        }
+        #[cfg(not(feature = "redis"))]
+        {
+            let _ = url;
+            return Err(AppError::Config(
+                "REDIS_URL is set but the api was built without the `redis` feature".into(),
+            ));
+        }
+    }
+
+    Ok(
+        std::num::NonZeroUsize::new(settings.api.cache_capacity).map(|capacity| {
+            ResponseCache::new(
+                Box::new(MemoryCache::new(capacity)),
+                settings.network.clone(),
+            )
+        }),
+    )
+}
+
+/// Strong ETag derived from the response body.
+fn etag_for(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+fn etag_matches(request_etags: Option<&HeaderValue>, etag: &str) -> bool {
+    request_etags
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',')
+                .map(str::trim)
+                .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+        })
+}
+
+fn respond(
+    cached: CachedResponse,
+    ttl: Duration,
+    if_none_match: Option<&HeaderValue>,
+    status: &'static str,
+) -> Response {
+    let mut response = if etag_matches(if_none_match, &cached.etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let mut response = Response::new(Body::from(cached.body));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    };
+
+    let headers = response.headers_mut();
+    if let Ok(etag) = HeaderValue::from_str(&cached.etag) {
+        headers.insert(ETAG, etag);
+    }
+    if let Ok(cache_control) = HeaderValue::from_str(&format!("public, max-age={}", ttl.as_secs()))
+    {
+        headers.insert(CACHE_CONTROL, cache_control);
+    }
+    headers.insert("x-cache", HeaderValue::from_static(status));
+    response
+}
+
+/// Middleware serving cacheable `GET` routes from the [`ResponseCache`].
+pub async fn middleware(
+    State(cache): State<Arc<ResponseCache>>,
+    matched: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ttl = matched.as_ref().and_then(|m| ttl_for(m.as_str()));
+    let Some(ttl) = ttl.filter(|_| request.method() == Method::GET) else {
+        return next.run(request).await;
+    };
+
+    let Some(key) = cache.key(&request) else {
+        return next.run(request).await;
+    };
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+
+    if let Some(cached) = cache.backend.get(&key).await {
+        return respond(cached, ttl, if_none_match.as_ref(), "HIT");
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let declared_len = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<usize>().ok());
+    if declared_len.is_some_and(|len| len > MAX_CACHED_BODY) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match buffer_up_to(body, MAX_CACHED_BODY).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(too_large)) => return Response::from_parts(parts, too_large),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to buffer response for caching");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let body = match String::from_utf8(body.to_vec()) {
+        Ok(body) => body,
+        Err(_) => return Response::from_parts(parts, Body::from(body)),
+    };
+
+    let cached = CachedResponse {
+        etag: etag_for(body.as_bytes()),
+        body,
+    };
+    cache.backend.put(&key, cached.clone(), ttl).await;
+    respond(cached, ttl, if_none_match.as_ref(), "MISS")
+}
+
+/// Read `body` into memory if it is at most `limit` bytes. A longer body comes
+/// back as `Err`: a body that replays what was read and streams the rest.
+async fn buffer_up_to(mut body: Body, limit: usize) -> Result<Result<Bytes, Body>, axum::Error> {
+    let mut buffered = Vec::new();
+    while let Some(data) = next_data(&mut body).await {
+        buffered.extend_from_slice(&data?);
+        if buffered.len() > limit {
+            let rest = async_stream::stream! {
+                yield Ok(Bytes::from(buffered));
+                while let Some(data) = next_data(&mut body).await {
+                    yield data;
+                }
+            };
+            return Ok(Err(Body::from_stream(rest)));
+        }
+    }
+    Ok(Ok(Bytes::from(buffered)))
+}
+
+/// The next data frame of `body`; trailers are skipped.
+async fn next_data(body: &mut Body) -> Option<Result<Bytes, axum::Error>> {
+    loop {
+        let frame = std::future::poll_fn(|cx| Pin::new(&mut *body).poll_frame(cx)).await?;
+        match frame.map(|frame| frame.into_data()) {
+            Ok(Ok(data)) => return Some(Ok(data)),
+            Ok(Err(_trailers)) => continue,
+            Err(e) => return Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+
+    fn chunked(chunks: &[&'static str]) -> Body {
+        let chunks: Vec<Result<Bytes, std::io::Error>> = chunks
+            .iter()
+            .map(|c| Ok(Bytes::from_static(c.as_bytes())))
+            .collect();
+        Body::from_stream(async_stream::stream! {
+            for chunk in chunks {
+                yield chunk;
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn buffers_bodies_within_the_limit() {
+        let body = buffer_up_to(chunked(&["{\"a\":", "1}"]), 7).await.unwrap();
+        assert_eq!(body.unwrap(), Bytes::from_static(b"{\"a\":1}"));
+    }
+
+    #[tokio::test]
+    async fn passes_longer_bodies_through_whole() {
+        let body = buffer_up_to(chunked(&["{\"a\":", "1,", "\"b\":2}"]), 7)
+            .await
+            .unwrap()
+            .expect_err("over the limit");
+        let replayed = to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(replayed, Bytes::from_static(b"{\"a\":1,\"b\":2}"));
+    }
+}
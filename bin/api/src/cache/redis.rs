@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use redis::{AsyncCommands, aio::ConnectionManager};
+use std::time::Duration;
+
+use super::{CacheBackend, CachedResponse};
+
+/// Shared cache in Redis, so all API replicas serve the same entries.
+pub struct RedisCache {
+    connection: ConnectionManager,
+}
+
+impl RedisCache {
+    pub async fn connect(url: &str) -> redis::RedisResult<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = ConnectionManager::new(client).await?;
+        Ok(Self { connection })
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisCache {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut connection = self.connection.clone();
+        let raw: Option<String> = match connection.get(key).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                tracing::warn!(error = %e, "Redis cache read failed");
+                return None;
+            }
+        };
+        raw.and_then(|raw| serde_json::from_str(&raw).ok())
+    }
+
+    async fn put(&self, key: &str, value: CachedResponse, ttl: Duration) {
+        let Ok(raw) = serde_json::to_string(&value) else {
+            return;
+        };
+        let mut connection = self.connection.clone();
+        let result: redis::RedisResult<()> =
+            connection.set_ex(key, raw, ttl.as_secs().max(1)).await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Redis cache write failed");
+        }
+    }
+}
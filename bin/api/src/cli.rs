@@ -0,0 +1,64 @@
+//! Command-line interface: `api [--config <file>] [--print-config]`.
+
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "api",
+    version,
+    about = "Tempulse API server for TIP-20 stablecoin analytics"
+)]
+pub struct Cli {
+    /// TOML file to load instead of the default.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Print the effective settings and exit.
+    #[arg(long)]
+    pub print_config: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::error::ErrorKind;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("api").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn flags_are_optional() {
+        let cli = parse(&[]).unwrap();
+        assert_eq!(cli.config, None);
+        assert!(!cli.print_config);
+    }
+
+    #[test]
+    fn config_takes_a_path_in_either_form() {
+        for args in [
+            &["--config", "prod.toml"][..],
+            &["--config=prod.toml"],
+            &["--print-config", "--config", "prod.toml"],
+        ] {
+            let cli = parse(args).unwrap();
+            assert_eq!(cli.config, Some(PathBuf::from("prod.toml")), "{args:?}");
+        }
+        assert!(parse(&["--print-config"]).unwrap().print_config);
+    }
+
+    #[test]
+    fn config_is_given_once() {
+        let err = parse(&["--config", "a.toml", "--config", "b.toml"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let kind = |args: &[&str]| parse(args).unwrap_err().kind();
+        assert_eq!(kind(&["--config"]), ErrorKind::InvalidValue);
+        assert_eq!(kind(&["--port", "3000"]), ErrorKind::UnknownArgument);
+        assert_eq!(kind(&["serve"]), ErrorKind::UnknownArgument);
+    }
+}
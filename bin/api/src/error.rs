@@ -0,0 +1,175 @@
+//! API error model — maps failures to HTTP status codes and stable error codes.
+
+use axum::{
+    Json,
+    extract::{
+        FromRequestParts,
+        rejection::{PathRejection, QueryRejection},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tempulse_core::{AppError, address::AddressError};
+use utoipa::ToSchema;
+
+/// Error returned by every API handler.
+///
+/// Client errors carry a message that is safe to show; server-side failures
+/// wrap an [`AppError`] whose details are logged but never sent to the client.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// Malformed request (unparseable query string or path segment).
+    #[error("{0}")]
+    BadRequest(String),
+
+    /// Missing or wrong credentials for an admin endpoint.
+    #[error("{0}")]
+    Unauthorized(String),
+
+    /// The requested resource does not exist.
+    #[error("{0}")]
+    NotFound(String),
+
+    /// Well-formed request with an invalid parameter value.
+    #[error("{0}")]
+    InvalidParameter(String),
+
+    /// Too many requests in flight; the client should back off and retry.
+    #[error("Too many requests")]
+    RateLimited,
+
+    /// A dependency (database, RPC) is temporarily unreachable.
+    #[error("Service temporarily unavailable")]
+    Unavailable(#[source] AppError),
+
+    /// Any other server-side failure.
+    #[error("Internal server error")]
+    Internal(#[source] AppError),
+}
+
+impl ApiError {
+    /// HTTP status code for this error.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::InvalidParameter(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Stable machine-readable error code. Clients should match on this, not the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::InvalidParameter(_) => "invalid_parameter",
+            ApiError::RateLimited => "rate_limited",
+            ApiError::Unavailable(_) => "service_unavailable",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Log server-side failures; their details never reach the client.
+    fn log(&self) {
+        match self {
+            ApiError::Unavailable(e) => tracing::warn!(error = %e, "Dependency unavailable"),
+            ApiError::Internal(e) => tracing::error!(error = %e, "Internal error"),
+            _ => {}
+        }
+    }
+
+    /// Convert into a GraphQL field error carrying the same `code` in its extensions.
+    pub fn into_graphql(self) -> async_graphql::Error {
+        use async_graphql::ErrorExtensions;
+
+        self.log();
+        let code = self.code();
+        async_graphql::Error::new(self.to_string()).extend_with(|_, ext| ext.set("code", code))
+    }
+}
+
+/// JSON body of every error response.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    success: bool,
+    error: ErrorDetail,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorDetail {
+    /// Stable error code, e.g. `not_found` or `invalid_parameter`.
+    code: &'static str,
+    message: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        self.log();
+        let body = ErrorBody {
+            success: false,
+            error: ErrorDetail {
+                code: self.code(),
+                message: self.to_string(),
+            },
+        };
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::Rpc(_) => ApiError::Unavailable(e),
+            _ => ApiError::Internal(e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        let unavailable = matches!(
+            e,
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_)
+        );
+        let e = AppError::Database(e.to_string());
+        if unavailable {
+            ApiError::Unavailable(e)
+        } else {
+            ApiError::Internal(e)
+        }
+    }
+}
+
+impl From<AddressError> for ApiError {
+    fn from(e: AddressError) -> Self {
+        ApiError::BadRequest(e.to_string())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+/// [`axum::extract::Query`] that rejects with an [`ApiError`].
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+pub struct Query<T>(pub T);
+
+/// [`axum::extract::Path`] that rejects with an [`ApiError`].
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(ApiError))]
+pub struct Path<T>(pub T);
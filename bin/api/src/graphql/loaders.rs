@@ -0,0 +1,165 @@
+//! DataLoaders that batch the per-node lookups of a GraphQL query into one SQL query each.
+
+use async_graphql::dataloader::Loader;
+use std::collections::HashMap;
+use tempulse_storage::{
+    self as storage,
+    models::{Account, Token, Transfer},
+};
+
+use super::ResultExt;
+use crate::labels::{self, Label};
+
+/// A token on one network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenKey {
+    pub chain_id: i64,
+    pub address: String,
+}
+
+/// Tokens by address — resolves `Transfer.token`, `Account.token` and decimals lookups.
+pub struct TokenLoader(pub sqlx::PgPool);
+
+impl Loader<TokenKey> for TokenLoader {
+    type Value = Token;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[TokenKey]) -> Result<HashMap<TokenKey, Token>, Self::Error> {
+        // One query per network; in practice a query reads a single one.
+        let mut by_chain: HashMap<i64, Vec<String>> = HashMap::new();
+        for key in keys {
+            by_chain
+                .entry(key.chain_id)
+                .or_default()
+                .push(key.address.clone());
+        }
+
+        let mut result = HashMap::new();
+        for (chain_id, addresses) in by_chain {
+            let tokens = storage::repos::get_tokens_by_address(&self.0, chain_id, &addresses)
+                .await
+                .gql()?;
+            result.extend(tokens.into_iter().map(|t| {
+                let key = TokenKey {
+                    chain_id,
+                    address: t.address.clone(),
+                };
+                (key, t)
+            }));
+        }
+        Ok(result)
+    }
+}
+
+/// Address labels by address — resolves `Account.label`, `Transfer.fromLabel` and `Transfer.toLabel`.
+pub struct LabelLoader(pub sqlx::PgPool);
+
+impl Loader<String> for LabelLoader {
+    type Value = Label;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Label>, Self::Error> {
+        labels::lookup(&self.0, keys.iter().map(String::as_str))
+            .await
+            .gql()
+    }
+}
+
+/// Top holders of a token, limited to `limit` accounts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HoldersKey {
+    pub chain_id: i64,
+    pub token_address: String,
+    pub limit: i64,
+}
+
+/// Top holders for many tokens at once — resolves `Token.holders` in lists of tokens.
+pub struct HoldersLoader(pub sqlx::PgPool);
+
+impl Loader<HoldersKey> for HoldersLoader {
+    type Value = Vec<Account>;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[HoldersKey],
+    ) -> Result<HashMap<HoldersKey, Vec<Account>>, Self::Error> {
+        // One query per distinct network and limit; in practice a query uses a single one.
+        let mut by_limit: HashMap<(i64, i64), Vec<String>> = HashMap::new();
+        for key in keys {
+            by_limit
+                .entry((key.chain_id, key.limit))
+                .or_default()
+                .push(key.token_address.clone());
+        }
+
+        let mut result: HashMap<HoldersKey, Vec<Account>> =
+            keys.iter().map(|key| (key.clone(), Vec::new())).collect();
+        for ((chain_id, limit), tokens) in by_limit {
+            let holders =
+                storage::repos::get_top_holders_for_tokens(&self.0, chain_id, &tokens, limit)
+                    .await
+                    .gql()?;
+            for account in holders {
+                let key = HoldersKey {
+                    chain_id,
+                    token_address: account.token_address.clone(),
+                    limit,
+                };
+                result.entry(key).or_default().push(account);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// One page of a token's transfers, optionally only those sent or received by `address`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransfersKey {
+    pub chain_id: i64,
+    pub token_address: String,
+    pub address: Option<String>,
+    pub before: Option<(i64, i32)>,
+    pub limit: i64,
+}
+
+/// The network, cursor and page size a group of [`TransfersKey`]s shares.
+type PageKey = (i64, Option<(i64, i32)>, i64);
+
+/// Transfer pages for many tokens or accounts at once — resolves `Token.transfers`
+/// and `Account.transfers` in lists.
+pub struct TransfersLoader(pub sqlx::PgPool);
+
+impl Loader<TransfersKey> for TransfersLoader {
+    type Value = Vec<Transfer>;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[TransfersKey],
+    ) -> Result<HashMap<TransfersKey, Vec<Transfer>>, Self::Error> {
+        // One query per distinct network, cursor and page size; sibling
+        // connections in a query usually share them.
+        let mut by_page: HashMap<PageKey, Vec<&TransfersKey>> = HashMap::new();
+        for key in keys {
+            by_page
+                .entry((key.chain_id, key.before, key.limit))
+                .or_default()
+                .push(key);
+        }
+
+        let mut result = HashMap::new();
+        for ((chain_id, before, limit), group) in by_page {
+            let scopes: Vec<_> = group
+                .iter()
+                .map(|key| (key.token_address.clone(), key.address.clone()))
+                .collect();
+            let pages =
+                storage::repos::get_transfers_pages(&self.0, chain_id, &scopes, before, limit)
+                    .await
+                    .gql()?;
+            result.extend(group.into_iter().cloned().zip(pages));
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,303 @@
+//! GraphQL endpoint — nested queries over the same repos as the REST API.
+//!
+//! Lists are Relay-style connections with opaque cursors. Per-node lookups
+//! (a transfer's token, each token's holders and transfers, address labels) go through DataLoaders so a
+//! nested query costs one SQL query per level rather than one per node.
+//! Queries are rejected up front when they exceed [`MAX_DEPTH`] or
+//! [`MAX_COMPLEXITY`]. A request reads the network named by `?network=` on the
+//! endpoint URL.
+
+mod loaders;
+mod types;
+
+use async_graphql::{
+    BatchRequest, BatchResponse, Context, EmptyMutation, EmptySubscription, Object, Result, Schema,
+    connection::{Connection, CursorType, Edge, OpaqueCursor},
+    dataloader::DataLoader,
+    http::GraphiQLSource,
+};
+use axum::{Json, extract::State, response::Html};
+use std::sync::Arc;
+use tempulse_core::address::{Address, TokenAddress};
+use tempulse_storage as storage;
+
+use crate::{
+    AmountFormat, ApiError, AppState, OverviewResponse, TimeSeriesEntry, TokenVolumeEntry,
+    network::{ChainId, NetworkParams},
+    normalize_currency, use_normalized,
+};
+use loaders::{HoldersLoader, LabelLoader, TokenKey, TokenLoader, TransfersLoader};
+use types::{TokenNode, TransferNode, transfers_connection};
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Maximum nesting depth of a query.
+const MAX_DEPTH: usize = 10;
+
+/// Maximum query complexity. Every field costs 1; list fields multiply the
+/// cost of their children by the requested page size.
+const MAX_COMPLEXITY: usize = 5_000;
+
+/// Page size of connection fields when `first` is omitted.
+const DEFAULT_PAGE_SIZE: i32 = 25;
+
+/// Build the schema with its loaders and limits.
+pub fn schema(pool: sqlx::PgPool) -> ApiSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(DataLoader::new(TokenLoader(pool.clone()), tokio::spawn))
+        .data(DataLoader::new(HoldersLoader(pool.clone()), tokio::spawn))
+        .data(DataLoader::new(LabelLoader(pool.clone()), tokio::spawn))
+        .data(DataLoader::new(TransfersLoader(pool.clone()), tokio::spawn))
+        .data(pool)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// POST /api/v1/graphql — execute a query (or a batch of queries).
+#[utoipa::path(
+    post,
+    path = "/api/v1/graphql",
+    tag = "graphql",
+    params(NetworkParams),
+    request_body(
+        content = Object,
+        description = "`{\"query\", \"variables\", \"operationName\"}`, or an array of them"
+    ),
+    responses(
+        (status = 200, description = "`{\"data\", \"errors\"}` per query", body = Object),
+    )
+)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Json(request): Json<BatchRequest>,
+) -> Json<BatchResponse> {
+    Json(state.schema.execute_batch(request.data(chain)).await)
+}
+
+/// GET /api/v1/graphql — GraphiQL explorer.
+#[utoipa::path(
+    get,
+    path = "/api/v1/graphql",
+    tag = "graphql",
+    responses((status = 200, description = "GraphiQL explorer", content_type = "text/html", body = String))
+)]
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/api/v1/graphql").finish())
+}
+
+/// Map any error the REST API knows how to classify into a GraphQL error,
+/// so server-side details are logged and hidden the same way.
+trait ResultExt<T> {
+    fn gql(self) -> Result<T>;
+}
+
+impl<T, E: Into<ApiError>> ResultExt<T> for std::result::Result<T, E> {
+    fn gql(self) -> Result<T> {
+        self.map_err(|e| e.into().into_graphql())
+    }
+}
+
+/// Chain id of the network the request reads.
+fn chain_id(ctx: &Context<'_>) -> i64 {
+    ctx.data_unchecked::<ChainId>().0
+}
+
+/// `(block_number, log_index)` of a transfer in the newest-first ordering.
+type TransferCursor = OpaqueCursor<(i64, i32)>;
+
+/// Offset into the token list.
+type TokenCursor = OpaqueCursor<usize>;
+
+/// Decode an `after:` argument, rejecting cursors this server did not issue.
+fn decode_cursor<C: CursorType>(cursor: Option<String>) -> Result<Option<C>> {
+    cursor
+        .map(|c| {
+            C::decode_cursor(&c)
+                .map_err(|_| ApiError::InvalidParameter(format!("invalid cursor {c:?}")))
+        })
+        .transpose()
+        .gql()
+}
+
+// ─── Query Root ─────────────────────────────────────────────────────────────
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Tracked tokens, ordered by symbol.
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) as usize * child_complexity")]
+    async fn tokens(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        #[graphql(validator(minimum = 1, maximum = 100))] first: Option<i32>,
+    ) -> Result<Connection<TokenCursor, TokenNode>> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+        let start = decode_cursor::<TokenCursor>(after)?.map_or(0, |c| c.0 + 1);
+
+        let mut tokens =
+            storage::repos::get_tokens_page(pool, chain_id(ctx), first as i64 + 1, start as i64)
+                .await
+                .gql()?;
+        let has_next = tokens.len() > first;
+        tokens.truncate(first);
+
+        let mut connection = Connection::new(start > 0, has_next);
+        connection.edges.extend(
+            tokens
+                .into_iter()
+                .enumerate()
+                .map(|(i, t)| Edge::new(OpaqueCursor(start + i), TokenNode(t))),
+        );
+        Ok(connection)
+    }
+
+    /// A single token by address.
+    async fn token(&self, ctx: &Context<'_>, address: String) -> Result<Option<TokenNode>> {
+        let address: TokenAddress = address.parse().gql()?;
+        let token = ctx
+            .data_unchecked::<DataLoader<TokenLoader>>()
+            .load_one(TokenKey {
+                chain_id: chain_id(ctx),
+                address: address.into(),
+            })
+            .await?;
+        Ok(token.map(TokenNode))
+    }
+
+    /// Transfers across all tokens, newest first, optionally filtered by token
+    /// and by an address on either side.
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) as usize * child_complexity")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        token: Option<String>,
+        address: Option<String>,
+        after: Option<String>,
+        #[graphql(validator(minimum = 1, maximum = 100))] first: Option<i32>,
+    ) -> Result<Connection<TransferCursor, TransferNode>> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let token = token.map(|t| t.parse::<TokenAddress>()).transpose().gql()?;
+        let address = address.map(|a| a.parse::<Address>()).transpose().gql()?;
+        transfers_connection(
+            pool,
+            chain_id(ctx),
+            token.as_ref().map(TokenAddress::as_str),
+            address.as_ref().map(Address::as_str),
+            after,
+            first,
+        )
+        .await
+    }
+
+    /// Total volume and transfer count per token.
+    async fn volume(
+        &self,
+        ctx: &Context<'_>,
+        quote: Option<String>,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<Vec<TokenVolumeEntry>> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let quote = normalize_currency(quote);
+        let rows = if use_normalized(&quote, format) {
+            storage::repos::get_token_volumes_normalized(pool, chain_id(ctx), quote.as_deref())
+                .await
+        } else {
+            storage::repos::get_token_volumes(pool, chain_id(ctx)).await
+        }
+        .gql()?;
+        Ok(rows
+            .into_iter()
+            .map(|(address, symbol, volume, count)| TokenVolumeEntry {
+                token_address: address,
+                symbol,
+                total_volume: volume,
+                transfer_count: count,
+            })
+            .collect())
+    }
+
+    /// Global payment analytics.
+    async fn overview(
+        &self,
+        ctx: &Context<'_>,
+        quote: Option<String>,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<OverviewResponse> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let quote = normalize_currency(quote);
+        let (total_volume, total_txs) = if use_normalized(&quote, format) {
+            storage::repos::get_global_stats_normalized(pool, chain_id(ctx), quote.as_deref()).await
+        } else {
+            storage::repos::get_global_stats(pool, chain_id(ctx)).await
+        }
+        .gql()?;
+        Ok(OverviewResponse {
+            total_value_transferred: total_volume,
+            total_transactions: total_txs,
+            active_addresses: storage::repos::get_active_address_count(pool, chain_id(ctx))
+                .await
+                .gql()?,
+            tracked_tokens: storage::repos::get_token_count(pool, chain_id(ctx))
+                .await
+                .gql()?,
+            quote_currency: quote,
+        })
+    }
+
+    /// Daily transfer volume across all tokens, newest day first.
+    #[graphql(complexity = "limit as usize * child_complexity")]
+    async fn daily_volume(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 90, validator(minimum = 1, maximum = 1000))] limit: i32,
+        quote: Option<String>,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<Vec<TimeSeriesEntry>> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let quote = normalize_currency(quote);
+        let rows = if use_normalized(&quote, format) {
+            storage::repos::get_daily_volume_normalized(
+                pool,
+                chain_id(ctx),
+                quote.as_deref(),
+                limit.into(),
+            )
+            .await
+        } else {
+            storage::repos::get_daily_volume(pool, chain_id(ctx), limit.into()).await
+        }
+        .gql()?;
+        Ok(rows.into_iter().map(TimeSeriesEntry::from).collect())
+    }
+
+    /// Monthly transfer volume across all tokens, newest month first.
+    #[graphql(complexity = "limit as usize * child_complexity")]
+    async fn monthly_volume(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 24, validator(minimum = 1, maximum = 1000))] limit: i32,
+        quote: Option<String>,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<Vec<TimeSeriesEntry>> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let quote = normalize_currency(quote);
+        let rows = if use_normalized(&quote, format) {
+            storage::repos::get_monthly_volume_normalized(
+                pool,
+                chain_id(ctx),
+                quote.as_deref(),
+                limit.into(),
+            )
+            .await
+        } else {
+            storage::repos::get_monthly_volume(pool, chain_id(ctx), limit.into()).await
+        }
+        .gql()?;
+        Ok(rows.into_iter().map(TimeSeriesEntry::from).collect())
+    }
+}
@@ -0,0 +1,358 @@
+//! GraphQL object types wrapping the storage models.
+
+use async_graphql::{
+    Context, Object, Result,
+    connection::{Connection, Edge, OpaqueCursor},
+    dataloader::DataLoader,
+};
+use chrono::NaiveDateTime;
+use tempulse_storage::{
+    self as storage,
+    models::{Account, Token, Transfer},
+};
+
+use super::{
+    DEFAULT_PAGE_SIZE, ResultExt, TransferCursor, chain_id, decode_cursor,
+    loaders::{
+        HoldersKey, HoldersLoader, LabelLoader, TokenKey, TokenLoader, TransfersKey,
+        TransfersLoader,
+    },
+};
+use crate::{AmountFormat, DEFAULT_DECIMALS, TimeSeriesEntry, labels::Label};
+
+/// Label of `address`, batched through the [`LabelLoader`].
+async fn label_of(ctx: &Context<'_>, address: &str) -> Result<Option<Label>> {
+    ctx.data_unchecked::<DataLoader<LabelLoader>>()
+        .load_one(address.to_string())
+        .await
+}
+
+/// The token at `token_address` on the request's network, batched through the [`TokenLoader`].
+async fn token_at(ctx: &Context<'_>, token_address: &str) -> Result<Option<Token>> {
+    ctx.data_unchecked::<DataLoader<TokenLoader>>()
+        .load_one(TokenKey {
+            chain_id: chain_id(ctx),
+            address: token_address.to_string(),
+        })
+        .await
+}
+
+/// Decimals of `token_address`, batched through the [`TokenLoader`].
+async fn decimals_of(ctx: &Context<'_>, token_address: &str) -> Result<i16> {
+    Ok(token_at(ctx, token_address)
+        .await?
+        .map_or(DEFAULT_DECIMALS, |t| t.decimals))
+}
+
+/// Render a raw amount of `token_address`, looking up decimals only when needed.
+async fn render_amount(
+    ctx: &Context<'_>,
+    token_address: &str,
+    raw: &str,
+    format: AmountFormat,
+) -> Result<String> {
+    Ok(match format {
+        AmountFormat::Raw => raw.to_string(),
+        AmountFormat::Decimal => {
+            format.render(raw.to_string(), decimals_of(ctx, token_address).await?)
+        }
+    })
+}
+
+/// One page of transfers (newest first) as a Relay connection.
+pub async fn transfers_connection(
+    pool: &sqlx::PgPool,
+    chain_id: i64,
+    token_address: Option<&str>,
+    address: Option<&str>,
+    after: Option<String>,
+    first: Option<i32>,
+) -> Result<Connection<TransferCursor, TransferNode>> {
+    let first = first.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+    let before = decode_cursor::<TransferCursor>(after)?.map(|c| c.0);
+
+    // Fetch one extra row to learn whether another page follows.
+    let rows = storage::repos::get_transfers_page(
+        pool,
+        chain_id,
+        token_address,
+        address,
+        before,
+        first as i64 + 1,
+    )
+    .await
+    .gql()?;
+    Ok(into_connection(rows, before.is_some(), first))
+}
+
+/// One page of a token's transfers (newest first), batched across sibling
+/// tokens and accounts through the [`TransfersLoader`].
+async fn nested_transfers_connection(
+    ctx: &Context<'_>,
+    token_address: &str,
+    address: Option<&str>,
+    after: Option<String>,
+    first: Option<i32>,
+) -> Result<Connection<TransferCursor, TransferNode>> {
+    let first = first.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+    let before = decode_cursor::<TransferCursor>(after)?.map(|c| c.0);
+
+    // Fetch one extra row to learn whether another page follows.
+    let key = TransfersKey {
+        chain_id: chain_id(ctx),
+        token_address: token_address.to_string(),
+        address: address.map(str::to_string),
+        before,
+        limit: first as i64 + 1,
+    };
+    let rows = ctx
+        .data_unchecked::<DataLoader<TransfersLoader>>()
+        .load_one(key)
+        .await?
+        .unwrap_or_default();
+    Ok(into_connection(rows, before.is_some(), first))
+}
+
+/// The first `first` of `rows` as a connection, noting whether more followed.
+fn into_connection(
+    mut rows: Vec<Transfer>,
+    has_previous: bool,
+    first: usize,
+) -> Connection<TransferCursor, TransferNode> {
+    let has_next = rows.len() > first;
+    rows.truncate(first);
+
+    let mut connection = Connection::new(has_previous, has_next);
+    connection.edges.extend(
+        rows.into_iter()
+            .map(|t| Edge::new(OpaqueCursor((t.block_number, t.log_index)), TransferNode(t))),
+    );
+    connection
+}
+
+// ─── Token ──────────────────────────────────────────────────────────────────
+
+/// A tracked TIP-20 stablecoin.
+pub struct TokenNode(pub Token);
+
+#[Object(name = "Token")]
+impl TokenNode {
+    async fn address(&self) -> &str {
+        &self.0.address
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn symbol(&self) -> &str {
+        &self.0.symbol
+    }
+
+    async fn decimals(&self) -> i16 {
+        self.0.decimals
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn total_supply(&self, #[graphql(default)] format: AmountFormat) -> String {
+        format.render(self.0.total_supply.clone(), self.0.decimals)
+    }
+
+    async fn created_at_block(&self) -> i64 {
+        self.0.created_at_block
+    }
+
+    async fn created_at_tx(&self) -> &str {
+        &self.0.created_at_tx
+    }
+
+    /// Largest holders, by balance descending. Batched across tokens.
+    #[graphql(complexity = "first as usize * child_complexity")]
+    async fn holders(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10, validator(minimum = 1, maximum = 100))] first: i32,
+    ) -> Result<Vec<AccountNode>> {
+        let key = HoldersKey {
+            chain_id: chain_id(ctx),
+            token_address: self.0.address.clone(),
+            limit: first.into(),
+        };
+        let holders = ctx
+            .data_unchecked::<DataLoader<HoldersLoader>>()
+            .load_one(key)
+            .await?
+            .unwrap_or_default();
+        Ok(holders.into_iter().map(AccountNode).collect())
+    }
+
+    /// Transfers of this token, newest first. Batched across tokens.
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) as usize * child_complexity")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        #[graphql(validator(minimum = 1, maximum = 100))] first: Option<i32>,
+    ) -> Result<Connection<TransferCursor, TransferNode>> {
+        nested_transfers_connection(ctx, &self.0.address, None, after, first).await
+    }
+
+    /// Daily transfer volume of this token, newest day first.
+    #[graphql(complexity = "limit as usize * child_complexity")]
+    async fn daily_volume(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 30, validator(minimum = 1, maximum = 365))] limit: i32,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<Vec<TimeSeriesEntry>> {
+        let pool = ctx.data_unchecked::<sqlx::PgPool>();
+        let address = self.0.address.parse().gql()?;
+        let rows =
+            storage::repos::get_token_daily_volume(pool, chain_id(ctx), &address, limit.into())
+                .await
+                .gql()?;
+        Ok(rows
+            .into_iter()
+            .map(|(date, volume, count)| TimeSeriesEntry {
+                date,
+                volume: format.render(volume, self.0.decimals),
+                transfer_count: count,
+            })
+            .collect())
+    }
+}
+
+// ─── Account ────────────────────────────────────────────────────────────────
+
+/// The balance of one address in one token.
+pub struct AccountNode(pub Account);
+
+#[Object(name = "Account")]
+impl AccountNode {
+    async fn address(&self) -> &str {
+        &self.0.address
+    }
+
+    async fn token_address(&self) -> &str {
+        &self.0.token_address
+    }
+
+    async fn balance(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<String> {
+        render_amount(ctx, &self.0.token_address, &self.0.balance, format).await
+    }
+
+    async fn updated_at_block(&self) -> i64 {
+        self.0.updated_at_block
+    }
+
+    /// Label of the holder, if the address is a known entity.
+    async fn label(&self, ctx: &Context<'_>) -> Result<Option<Label>> {
+        label_of(ctx, &self.0.address).await
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> Result<Option<TokenNode>> {
+        Ok(token_at(ctx, &self.0.token_address).await?.map(TokenNode))
+    }
+
+    /// Transfers of this token sent or received by this address, newest first.
+    /// Batched across accounts.
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_SIZE) as usize * child_complexity")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        #[graphql(validator(minimum = 1, maximum = 100))] first: Option<i32>,
+    ) -> Result<Connection<TransferCursor, TransferNode>> {
+        nested_transfers_connection(
+            ctx,
+            &self.0.token_address,
+            Some(&self.0.address),
+            after,
+            first,
+        )
+        .await
+    }
+}
+
+// ─── Transfer ───────────────────────────────────────────────────────────────
+
+/// A token movement (transfer, mint, or burn).
+pub struct TransferNode(pub Transfer);
+
+#[Object(name = "Transfer")]
+impl TransferNode {
+    async fn id(&self) -> i64 {
+        self.0.id
+    }
+
+    async fn token_address(&self) -> &str {
+        &self.0.token_address
+    }
+
+    async fn from_address(&self) -> &str {
+        &self.0.from_address
+    }
+
+    async fn to_address(&self) -> &str {
+        &self.0.to_address
+    }
+
+    /// Label of the sender, if the address is a known entity.
+    #[graphql(name = "fromLabel")]
+    async fn sender_label(&self, ctx: &Context<'_>) -> Result<Option<Label>> {
+        label_of(ctx, &self.0.from_address).await
+    }
+
+    /// Label of the receiver, if the address is a known entity.
+    async fn to_label(&self, ctx: &Context<'_>) -> Result<Option<Label>> {
+        label_of(ctx, &self.0.to_address).await
+    }
+
+    async fn amount(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] format: AmountFormat,
+    ) -> Result<String> {
+        render_amount(ctx, &self.0.token_address, &self.0.amount, format).await
+    }
+
+    async fn memo(&self) -> Option<&str> {
+        self.0.memo.as_deref()
+    }
+
+    async fn event_type(&self) -> &str {
+        &self.0.event_type
+    }
+
+    async fn transaction_hash(&self) -> &str {
+        &self.0.transaction_hash
+    }
+
+    async fn block_number(&self) -> i64 {
+        self.0.block_number
+    }
+
+    async fn log_index(&self) -> i32 {
+        self.0.log_index
+    }
+
+    /// Time of the transfer's block.
+    async fn block_time(&self) -> NaiveDateTime {
+        self.0.block_time
+    }
+
+    async fn created_at(&self) -> NaiveDateTime {
+        self.0.created_at
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> Result<Option<TokenNode>> {
+        Ok(token_at(ctx, &self.0.token_address).await?.map(TokenNode))
+    }
+}
@@ -0,0 +1,267 @@
+//! Address labels: lookup, admin management, and attaching labels to holder
+//! and transfer responses.
+
+use async_graphql::SimpleObject;
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tempulse_core::{
+    address::Address,
+    labels::{self, LabelCategory, LabelEntry},
+};
+use tempulse_storage::{
+    self as storage,
+    models::{Account, AddressLabel, Transfer},
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{ApiError, ApiResponse, AppState, ErrorBody, Path, Query, admin::Admin, json_ok};
+
+// ─── Enrichment ─────────────────────────────────────────────────────────────
+
+/// The label of an address, as embedded in other responses.
+#[derive(Debug, Clone, Serialize, ToSchema, SimpleObject)]
+#[graphql(name = "AddressLabel")]
+pub struct Label {
+    pub name: String,
+    pub category: String,
+}
+
+impl From<AddressLabel> for Label {
+    fn from(l: AddressLabel) -> Self {
+        Self {
+            name: l.name,
+            category: l.category,
+        }
+    }
+}
+
+/// Labels keyed by address.
+pub type LabelMap = HashMap<String, Label>;
+
+/// Labels of `addresses`, fetched in a single query.
+pub async fn lookup<'a>(
+    pool: &sqlx::PgPool,
+    addresses: impl IntoIterator<Item = &'a str>,
+) -> Result<LabelMap, sqlx::Error> {
+    let mut addresses: Vec<String> = addresses.into_iter().map(str::to_string).collect();
+    addresses.sort_unstable();
+    addresses.dedup();
+    Ok(storage::repos::get_labels_for_addresses(pool, &addresses)
+        .await?
+        .into_iter()
+        .map(|l| (l.address.clone(), Label::from(l)))
+        .collect())
+}
+
+/// An account balance with the holder's label, if any.
+#[derive(Serialize, ToSchema)]
+pub struct LabeledAccount {
+    #[serde(flatten)]
+    account: Account,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<Label>,
+}
+
+impl LabeledAccount {
+    pub async fn attach(
+        pool: &sqlx::PgPool,
+        accounts: Vec<Account>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let labels = lookup(pool, accounts.iter().map(|a| a.address.as_str())).await?;
+        Ok(accounts
+            .into_iter()
+            .map(|account| Self {
+                label: labels.get(&account.address).cloned(),
+                account,
+            })
+            .collect())
+    }
+}
+
+/// A transfer with the sender's and receiver's labels, if any.
+#[derive(Serialize, ToSchema)]
+pub struct LabeledTransfer {
+    #[serde(flatten)]
+    transfer: Transfer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_label: Option<Label>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_label: Option<Label>,
+}
+
+impl LabeledTransfer {
+    pub async fn attach(
+        pool: &sqlx::PgPool,
+        transfers: Vec<Transfer>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let labels = lookup(
+            pool,
+            transfers
+                .iter()
+                .flat_map(|t| [t.from_address.as_str(), t.to_address.as_str()]),
+        )
+        .await?;
+        Ok(transfers
+            .into_iter()
+            .map(|transfer| Self {
+                from_label: labels.get(&transfer.from_address).cloned(),
+                to_label: labels.get(&transfer.to_address).cloned(),
+                transfer,
+            })
+            .collect())
+    }
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LabelListParams {
+    /// Only labels of this category.
+    #[param(value_type = Option<String>)]
+    category: Option<LabelCategory>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Body of `PUT /api/v1/labels/:address`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LabelRequest {
+    name: String,
+    /// `exchange`, `issuer`, `treasury`, `bridge`, `contract`, `precompile` or `other`.
+    #[schema(value_type = String)]
+    category: LabelCategory,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ImportResult {
+    imported: usize,
+}
+
+/// GET /api/v1/labels — known address labels, ordered by name.
+#[utoipa::path(
+    get,
+    path = "/api/v1/labels",
+    tag = "labels",
+    params(LabelListParams),
+    responses(
+        (status = 200, description = "Address labels", body = ApiResponse<Vec<AddressLabel>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn list_labels(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<LabelListParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 100)?;
+    let offset = params.offset.unwrap_or(0).max(0);
+    let category = params.category.map(LabelCategory::as_str);
+    let labels = storage::repos::get_address_labels(&state.pool, category, limit, offset).await?;
+    Ok(json_ok(labels))
+}
+
+/// PUT /api/v1/labels/:address — create or replace an address's label.
+#[utoipa::path(
+    put,
+    path = "/api/v1/labels/{address}",
+    tag = "labels",
+    security(("admin_token" = [])),
+    params(("address" = String, Path, description = "Labeled address")),
+    request_body = LabelRequest,
+    responses(
+        (status = 200, description = "Stored label", body = ApiResponse<AddressLabel>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn put_label(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<Address>,
+    Json(request): Json<LabelRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::InvalidParameter("name must not be empty".into()));
+    }
+    let entry = LabelEntry {
+        address: address.clone(),
+        name: name.to_string(),
+        category: request.category,
+    };
+    storage::repos::upsert_address_labels(&state.pool, &[entry], "admin").await?;
+
+    let label = storage::repos::get_labels_for_addresses(&state.pool, &[address.into()])
+        .await?
+        .pop()
+        .ok_or(sqlx::Error::RowNotFound)?;
+    Ok(json_ok(label))
+}
+
+/// DELETE /api/v1/labels/:address — remove an address's label.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/labels/{address}",
+    tag = "labels",
+    security(("admin_token" = [])),
+    params(("address" = String, Path, description = "Labeled address")),
+    responses(
+        (status = 204, description = "Label deleted"),
+        (status = 404, description = "No label for the address", body = ErrorBody),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn delete_label(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<Address>,
+) -> Result<impl IntoResponse, ApiError> {
+    if storage::repos::delete_address_label(&state.pool, address.as_str()).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound(format!("No label for {address}")))
+    }
+}
+
+/// POST /api/v1/labels/import — bulk-import labels from a JSON array, or from
+/// CSV (`address,category,name`) when sent as `text/csv`. Existing labels for
+/// the same addresses are replaced.
+#[utoipa::path(
+    post,
+    path = "/api/v1/labels/import",
+    tag = "labels",
+    security(("admin_token" = [])),
+    request_body(content = String, description = "JSON array of labels, or CSV", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Number of labels imported", body = ApiResponse<ImportResult>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn import_labels(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<impl IntoResponse, ApiError> {
+    let is_csv = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/csv"));
+    let entries = if is_csv {
+        labels::parse_csv(&body)
+    } else {
+        labels::parse_json(&body)
+    }
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    storage::repos::upsert_address_labels(&state.pool, &entries, "import").await?;
+    tracing::info!(count = entries.len(), "Address labels imported");
+    Ok(json_ok(ImportResult {
+        imported: entries.len(),
+    }))
+}
@@ -1,31 +1,107 @@
 //! Tempulse API Server — serves stablecoin analytics from the indexed data.
 
+mod admin;
+mod analytics;
+mod cache;
+mod cli;
+mod error;
+mod graphql;
+mod labels;
+mod metrics;
+mod network;
+mod openapi;
+mod status;
+mod stream;
+mod trace;
+mod webhooks;
+
+use async_graphql::{Enum, SimpleObject};
 use axum::{
-    Json, Router,
-    extract::{Path, Query, State},
-    http::StatusCode,
+    BoxError, Json, Router,
+    error_handling::HandleErrorLayer,
+    extract::State,
+    middleware,
     response::IntoResponse,
-    routing::get,
+    routing::{MethodRouter, get, post, put},
 };
+use clap::Parser;
+use error::{ApiError, ErrorBody, Path, Query};
+use labels::{LabeledAccount, LabeledTransfer};
+use network::{ChainId, NetworkParams, Networks};
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, sync::Arc};
-use tempulse_core::{Settings, telemetry};
-use tempulse_storage::{self as storage};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use tempulse_core::{Settings, address::TokenAddress, metrics::PrometheusHandle, telemetry, units};
+use tempulse_storage::{
+    self as storage,
+    models::{FxRate, Token, Transfer},
+};
+use tokio::sync::broadcast;
+use tower::ServiceBuilder;
+use utoipa::{IntoParams, ToSchema};
+
+/// Fallback `decimals` for tokens missing from the registry (TIP-20 tokens always have 6).
+const DEFAULT_DECIMALS: i16 = 6;
 
 /// Shared application state.
 struct AppState {
     pool: sqlx::PgPool,
+    schema: graphql::ApiSchema,
+    /// Newly committed transfers, fed by [`stream::run_listener`].
+    transfers: broadcast::Sender<Arc<Transfer>>,
+    /// Bearer token for admin endpoints; `None` disables them.
+    admin_token: Option<String>,
+    /// Renders the Prometheus recorder for `/metrics`.
+    metrics: PrometheusHandle,
+    /// Lag limits for the `/ready` probe.
+    readiness: status::Thresholds,
+    /// Resolves `?network=` to the chain a request reads.
+    networks: Networks,
+    /// Upper bound for `?limit=` on paginated endpoints (`api.max_page_limit`).
+    max_page_limit: i64,
+}
+
+impl AppState {
+    /// Resolve a `?limit=` parameter, rejecting values outside `1..=max_page_limit`.
+    fn page_limit(&self, limit: Option<i64>, default: i64) -> Result<i64, ApiError> {
+        let max = self.max_page_limit;
+        match limit {
+            None => Ok(default),
+            Some(l) if (1..=max).contains(&l) => Ok(l),
+            Some(l) => Err(ApiError::InvalidParameter(format!(
+                "limit must be between 1 and {max}, got {l}"
+            ))),
+        }
+    }
+}
+
+/// Parse the command line and load settings. Exits after `--print-config`,
+/// or with status 2 when the configuration is invalid.
+fn settings_or_exit() -> Settings {
+    let cli = cli::Cli::parse();
+    let loaded = Settings::load(cli.config.as_deref()).and_then(|settings| {
+        if cli.print_config {
+            print!("{}", settings.to_redacted_toml()?);
+            std::process::exit(0);
+        }
+        Ok(settings)
+    });
+    loaded.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(2);
+    })
 }
 
 #[tokio::main]
 async fn main() {
-    telemetry::init();
-    let settings = Settings::from_env().expect("Failed to load settings");
+    let settings = settings_or_exit();
+    let _telemetry = telemetry::init("tempulse-api", settings.telemetry.otlp_endpoint.as_deref())
+        .expect("Failed to initialise telemetry");
+    let metrics = metrics::install().expect("Failed to install metrics recorder");
 
-    tracing::info!("Starting Tempulse API Server");
+    tracing::info!(network = %settings.network, "Starting Tempulse API Server");
 
     // Connect to database
-    let pool = storage::connect(&settings.database_url)
+    let pool = storage::connect(&settings.database)
         .await
         .expect("Failed to connect to database");
 
@@ -37,51 +113,274 @@ async fn main() {
 
     tracing::info!("Database ready");
 
-    let state = Arc::new(AppState { pool });
+    let schema = graphql::schema(pool.clone());
+    let (transfers, _) = broadcast::channel(stream::STREAM_BUFFER);
+    tokio::spawn(stream::run_listener(pool.clone(), transfers.clone()));
 
-    let app = Router::new()
-        .route("/api/v1/tokens", get(list_tokens))
-        .route("/api/v1/tokens/:address", get(get_token))
-        .route("/api/v1/tokens/:address/holders", get(get_holders))
-        .route(
+    let mut api = api_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, handler)| {
+            router.route(path, handler)
+        });
+
+    // Response cache for the REST routes (streams, GraphQL and docs bypass it)
+    let response_cache = cache::from_settings(&settings)
+        .await
+        .expect("Failed to set up response cache");
+    if let Some(response_cache) = response_cache.map(Arc::new) {
+        tracing::info!(
+            backend = response_cache.backend_name(),
+            "Response cache enabled"
+        );
+        tokio::spawn(response_cache.clone().watch_indexer(pool.clone()));
+        api = api.route_layer(middleware::from_fn_with_state(
+            response_cache,
+            cache::middleware,
+        ));
+    }
+
+    let state = Arc::new(AppState {
+        pool,
+        schema,
+        transfers,
+        admin_token: settings.api.admin_token.clone(),
+        metrics,
+        readiness: status::Thresholds {
+            max_lag_blocks: settings.api.ready_max_lag_blocks,
+            max_lag_secs: settings.api.ready_max_lag_secs,
+        },
+        networks: Networks::new(settings.network.clone()),
+        max_page_limit: settings.api.max_page_limit,
+    });
+
+    let add = |router: Router<Arc<AppState>>, (path, handler)| router.route(path, handler);
+    let app = uncached_routes()
+        .into_iter()
+        .fold(api, add)
+        .route_layer(middleware::from_fn(metrics::track))
+        .route_layer(middleware::from_fn(trace::span));
+    let app = bare_routes()
+        .into_iter()
+        .fold(app, add)
+        .fallback(not_found)
+        .with_state(state)
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_: BoxError| async {
+                    ApiError::RateLimited
+                }))
+                .load_shed()
+                .concurrency_limit(settings.api.max_concurrent_requests),
+        );
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], settings.api.port));
+    tracing::info!("Listening on http://{}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
+
+/// The REST routes, behind the response cache, request metrics and tracing.
+///
+/// With [`uncached_routes`] and [`bare_routes`], these are the lists both the
+/// router and the OpenAPI drift test are built from, so a route cannot be
+/// added without it showing up in the spec check.
+fn api_routes() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
+    vec![
+        ("/api/v1/tokens", get(list_tokens)),
+        ("/api/v1/tokens/:address", get(get_token)),
+        ("/api/v1/tokens/:address/holders", get(get_holders)),
+        (
             "/api/v1/tokens/:address/transfers",
             get(get_token_transfers),
-        )
-        .route("/api/v1/stats/volume", get(get_volume))
-        .route("/api/v1/stats/overview", get(get_overview))
-        .route("/api/v1/stats/daily", get(get_daily_volume))
-        .route("/api/v1/stats/monthly", get(get_monthly_volume))
-        .route(
+        ),
+        ("/api/v1/stats/volume", get(get_volume)),
+        ("/api/v1/stats/overview", get(get_overview)),
+        ("/api/v1/stats/daily", get(get_daily_volume)),
+        ("/api/v1/stats/monthly", get(get_monthly_volume)),
+        (
             "/api/v1/tokens/:address/volume/daily",
             get(get_token_daily_volume),
-        )
-        .route("/api/v1/activity/recent", get(get_recent_activity))
-        .route("/health", get(health))
-        .with_state(state);
+        ),
+        (
+            "/api/v1/tokens/:address/distribution",
+            get(analytics::get_distribution),
+        ),
+        (
+            "/api/v1/tokens/:address/velocity",
+            get(analytics::get_token_velocity),
+        ),
+        ("/api/v1/stats/velocity", get(analytics::get_velocity)),
+        (
+            "/api/v1/tokens/:address/issuance",
+            get(analytics::get_token_issuance),
+        ),
+        ("/api/v1/stats/issuance", get(analytics::get_issuance)),
+        ("/api/v1/stats/flows", get(analytics::get_flows)),
+        ("/api/v1/activity/recent", get(get_recent_activity)),
+        ("/api/v1/fx/rates", get(get_fx_rates)),
+        ("/api/v1/labels", get(labels::list_labels)),
+        ("/api/v1/labels/import", post(labels::import_labels)),
+        (
+            "/api/v1/labels/:address",
+            put(labels::put_label).delete(labels::delete_label),
+        ),
+        (
+            "/api/v1/webhooks",
+            get(webhooks::list_webhooks).post(webhooks::create_webhook),
+        ),
+        (
+            "/api/v1/webhooks/:id",
+            get(webhooks::get_webhook)
+                .put(webhooks::update_webhook)
+                .delete(webhooks::delete_webhook),
+        ),
+        (
+            "/api/v1/webhooks/:id/dead-letters",
+            get(webhooks::list_dead_letters),
+        ),
+        (
+            "/api/v1/webhooks/:id/dead-letters/:letter_id/retry",
+            post(webhooks::retry_dead_letter),
+        ),
+        ("/api/v1/networks", get(network::list_networks)),
+        ("/api/v1/status", get(status::get_status)),
+        ("/health", get(health)),
+        ("/ready", get(status::ready)),
+    ]
+}
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], settings.api_port));
-    tracing::info!("Listening on http://{}", addr);
+/// Routes behind request metrics and tracing but not the response cache:
+/// streams, GraphQL and the docs.
+fn uncached_routes() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
+    vec![
+        ("/api/v1/openapi.json", get(openapi::spec)),
+        ("/api/v1/docs", get(openapi::docs)),
+        ("/api/v1/stream/transfers", get(stream::sse)),
+        ("/api/v1/stream/transfers/ws", get(stream::websocket)),
+        (
+            "/api/v1/graphql",
+            get(graphql::graphiql).post(graphql::handler),
+        ),
+    ]
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+/// Routes behind no middleware: the metrics scrape, so it does not count itself.
+fn bare_routes() -> Vec<(&'static str, MethodRouter<Arc<AppState>>)> {
+    vec![("/metrics", get(metrics::render))]
 }
 
 // ─── Query Params ───────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
+/// `?format=raw|decimal` — how token amounts are rendered in responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema, Enum)]
+#[serde(rename_all = "lowercase")]
+enum AmountFormat {
+    /// Base-unit integer strings, exactly as stored.
+    #[default]
+    Raw,
+    /// Decimal strings scaled by each token's `decimals` (e.g. `"1.5"`).
+    Decimal,
+}
+
+impl AmountFormat {
+    /// Render a raw base-unit amount for a token with the given `decimals`.
+    ///
+    /// Net amounts can be negative, so the sign is kept when scaling.
+    fn render(self, raw: String, decimals: i16) -> String {
+        match self {
+            AmountFormat::Raw => raw,
+            AmountFormat::Decimal => units::format_signed_units(&raw, decimals.max(0) as u32),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FormatParams {
+    #[serde(default)]
+    format: AmountFormat,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct PaginationParams {
     limit: Option<i64>,
+    #[serde(default)]
+    format: AmountFormat,
+}
+
+/// `?quote=USD` — normalize volumes into a quote currency using stored FX rates.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct QuoteParams {
+    quote: Option<String>,
+    #[serde(default)]
+    format: AmountFormat,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct TimeSeriesParams {
+    limit: Option<i64>,
+    quote: Option<String>,
+    #[serde(default)]
+    format: AmountFormat,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FxRateParams {
+    base: Option<String>,
+    quote: Option<String>,
+    limit: Option<i64>,
+}
+
+/// Upper-case a requested currency code so it matches `fx_rates`.
+fn normalize_currency(quote: Option<String>) -> Option<String> {
+    quote
+        .map(|q| q.trim().to_ascii_uppercase())
+        .filter(|q| !q.is_empty())
+}
+
+/// Cross-token aggregates must be summed in decimal units (via the normalized
+/// queries) whenever they are converted or rendered as decimals.
+fn use_normalized(quote: &Option<String>, format: AmountFormat) -> bool {
+    quote.is_some() || format == AmountFormat::Decimal
+}
+
+/// Decimals for every token tracked on `chain`, keyed by address.
+async fn token_decimals(
+    pool: &sqlx::PgPool,
+    chain: ChainId,
+) -> Result<HashMap<String, i16>, sqlx::Error> {
+    Ok(storage::repos::get_token_decimals(pool, chain.0)
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// Decimals for a single token, falling back to [`DEFAULT_DECIMALS`].
+async fn decimals_for(
+    pool: &sqlx::PgPool,
+    chain: ChainId,
+    token_address: &TokenAddress,
+) -> Result<i16, sqlx::Error> {
+    Ok(storage::repos::get_token(pool, chain.0, token_address)
+        .await?
+        .map_or(DEFAULT_DECIMALS, |t| t.decimals))
 }
 
 // ─── Response Types ─────────────────────────────────────────────────────────
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ApiResponse<T: Serialize> {
     success: bool,
     data: T,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema, SimpleObject)]
+#[graphql(name = "TokenVolume")]
 struct TokenVolumeEntry {
     token_address: String,
     symbol: String,
@@ -89,17 +388,24 @@ struct TokenVolumeEntry {
     transfer_count: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct VolumeResponse {
     tokens: Vec<TokenVolumeEntry>,
+    /// Set when volumes are normalized into a quote currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote_currency: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema, SimpleObject)]
+#[graphql(name = "Overview")]
 struct OverviewResponse {
     total_value_transferred: String,
     total_transactions: i64,
     active_addresses: i64,
     tracked_tokens: i64,
+    /// Set when `total_value_transferred` is normalized into a quote currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote_currency: Option<String>,
 }
 
 fn json_ok<T: Serialize>(data: T) -> Json<ApiResponse<T>> {
@@ -109,81 +415,169 @@ fn json_ok<T: Serialize>(data: T) -> Json<ApiResponse<T>> {
     })
 }
 
-fn json_err(msg: &str) -> (StatusCode, Json<ApiResponse<String>>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ApiResponse {
-            success: false,
-            data: msg.to_string(),
-        }),
-    )
-}
-
 // ─── Handlers ───────────────────────────────────────────────────────────────
 
+/// GET /health — liveness check.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "system",
+    responses(
+        (status = 200, description = "Service is up", body = String),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn health() -> &'static str {
     "ok"
 }
 
+/// Fallback for unknown routes.
+async fn not_found() -> ApiError {
+    ApiError::NotFound("Route not found".into())
+}
+
 /// GET /api/v1/tokens — list tracked stablecoins (paginated).
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens",
+    tag = "tokens",
+    params(PaginationParams, NetworkParams),
+    responses(
+        (status = 200, description = "Tracked tokens", body = ApiResponse<Vec<Token>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn list_tokens(
     State(state): State<Arc<AppState>>,
+    chain: ChainId,
     Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(100);
-    let tokens = storage::repos::get_all_tokens(&state.pool, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 100)?;
+    let mut tokens = storage::repos::get_all_tokens(&state.pool, chain.0, limit).await?;
+    for t in &mut tokens {
+        t.total_supply = params
+            .format
+            .render(std::mem::take(&mut t.total_supply), t.decimals);
+    }
     Ok(json_ok(tokens))
 }
 
 /// GET /api/v1/tokens/:address — single token details.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), FormatParams, NetworkParams),
+    responses(
+        (status = 200, description = "Token details", body = ApiResponse<Token>),
+        (status = 404, description = "Token not found", body = ErrorBody),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_token(
     State(state): State<Arc<AppState>>,
-    Path(address): Path<String>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let token = storage::repos::get_token(&state.pool, &address)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
+    Query(params): Query<FormatParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let token = storage::repos::get_token(&state.pool, chain.0, &address).await?;
     match token {
-        Some(t) => Ok(json_ok(t)),
-        None => Err(json_err("Token not found")),
+        Some(mut t) => {
+            t.total_supply = params.format.render(t.total_supply, t.decimals);
+            Ok(json_ok(t))
+        }
+        None => Err(ApiError::NotFound("Token not found".into())),
     }
 }
 
 /// GET /api/v1/tokens/:address/holders — top holders for a token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}/holders",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), PaginationParams, NetworkParams),
+    responses(
+        (status = 200, description = "Top holders", body = ApiResponse<Vec<LabeledAccount>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_holders(
     State(state): State<Arc<AppState>>,
-    Path(address): Path<String>,
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
     Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(50);
-    let holders = storage::repos::get_top_holders(&state.pool, &address, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-    Ok(json_ok(holders))
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 50)?;
+    let mut holders =
+        storage::repos::get_top_holders(&state.pool, chain.0, &address, limit).await?;
+    if params.format == AmountFormat::Decimal {
+        let decimals = decimals_for(&state.pool, chain, &address).await?;
+        for h in &mut holders {
+            h.balance = params
+                .format
+                .render(std::mem::take(&mut h.balance), decimals);
+        }
+    }
+    Ok(json_ok(LabeledAccount::attach(&state.pool, holders).await?))
 }
 
 /// GET /api/v1/tokens/:address/transfers — transfers for a specific token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}/transfers",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), PaginationParams, NetworkParams),
+    responses(
+        (status = 200, description = "Token transfers", body = ApiResponse<Vec<LabeledTransfer>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_token_transfers(
     State(state): State<Arc<AppState>>,
-    Path(address): Path<String>,
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
     Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(50);
-    let transfers = storage::repos::get_token_transfers(&state.pool, &address, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-    Ok(json_ok(transfers))
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 50)?;
+    let mut transfers =
+        storage::repos::get_token_transfers(&state.pool, chain.0, &address, limit).await?;
+    if params.format == AmountFormat::Decimal {
+        let decimals = decimals_for(&state.pool, chain, &address).await?;
+        for t in &mut transfers {
+            t.amount = params
+                .format
+                .render(std::mem::take(&mut t.amount), decimals);
+        }
+    }
+    Ok(json_ok(
+        LabeledTransfer::attach(&state.pool, transfers).await?,
+    ))
 }
 
 /// GET /api/v1/stats/volume — per-token transfer volumes.
+///
+/// With `?quote=USD`, volumes are converted into the quote currency.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/volume",
+    tag = "stats",
+    params(QuoteParams, NetworkParams),
+    responses(
+        (status = 200, description = "Per-token volumes", body = ApiResponse<VolumeResponse>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_volume(
     State(state): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let rows = storage::repos::get_token_volumes(&state.pool)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
+    chain: ChainId,
+    Query(params): Query<QuoteParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let quote = normalize_currency(params.quote);
+    let rows = if use_normalized(&quote, params.format) {
+        storage::repos::get_token_volumes_normalized(&state.pool, chain.0, quote.as_deref()).await
+    } else {
+        storage::repos::get_token_volumes(&state.pool, chain.0).await
+    }?;
 
     let tokens: Vec<TokenVolumeEntry> = rows
         .into_iter()
@@ -195,111 +589,219 @@ async fn get_volume(
         })
         .collect();
 
-    Ok(json_ok(VolumeResponse { tokens }))
+    Ok(json_ok(VolumeResponse {
+        tokens,
+        quote_currency: quote,
+    }))
 }
 
 /// GET /api/v1/stats/overview — global payment analytics.
+///
+/// With `?quote=USD`, the total value is converted into the quote currency.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/overview",
+    tag = "stats",
+    params(QuoteParams, NetworkParams),
+    responses(
+        (status = 200, description = "Global stats", body = ApiResponse<OverviewResponse>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_overview(
     State(state): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let (total_volume, total_txs) = storage::repos::get_global_stats(&state.pool)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-    let active_addrs = storage::repos::get_active_address_count(&state.pool)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-    let token_count = storage::repos::get_token_count(&state.pool)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
+    chain: ChainId,
+    Query(params): Query<QuoteParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let quote = normalize_currency(params.quote);
+    let (total_volume, total_txs) = if use_normalized(&quote, params.format) {
+        storage::repos::get_global_stats_normalized(&state.pool, chain.0, quote.as_deref()).await
+    } else {
+        storage::repos::get_global_stats(&state.pool, chain.0).await
+    }?;
+    let active_addrs = storage::repos::get_active_address_count(&state.pool, chain.0).await?;
+    let token_count = storage::repos::get_token_count(&state.pool, chain.0).await?;
 
     Ok(json_ok(OverviewResponse {
         total_value_transferred: total_volume,
         total_transactions: total_txs,
         active_addresses: active_addrs,
         tracked_tokens: token_count,
+        quote_currency: quote,
     }))
 }
 
+/// GET /api/v1/activity/recent — latest transfers across all tokens.
+#[utoipa::path(
+    get,
+    path = "/api/v1/activity/recent",
+    tag = "activity",
+    params(PaginationParams, NetworkParams),
+    responses(
+        (status = 200, description = "Latest transfers", body = ApiResponse<Vec<LabeledTransfer>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_recent_activity(
     State(state): State<Arc<AppState>>,
+    chain: ChainId,
     Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(50);
-    let transfers = storage::repos::get_recent_transfers(&state.pool, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-    Ok(json_ok(transfers))
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 50)?;
+    let mut transfers = storage::repos::get_recent_transfers(&state.pool, chain.0, limit).await?;
+    if params.format == AmountFormat::Decimal {
+        let decimals = token_decimals(&state.pool, chain).await?;
+        for t in &mut transfers {
+            let d = decimals
+                .get(&t.token_address)
+                .copied()
+                .unwrap_or(DEFAULT_DECIMALS);
+            t.amount = params.format.render(std::mem::take(&mut t.amount), d);
+        }
+    }
+    Ok(json_ok(
+        LabeledTransfer::attach(&state.pool, transfers).await?,
+    ))
 }
 
 // ─── Time-Series Handlers ───────────────────────────────────────────────────
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema, SimpleObject)]
 struct TimeSeriesEntry {
     date: String,
     volume: String,
     transfer_count: i64,
 }
 
+impl From<(String, String, i64)> for TimeSeriesEntry {
+    fn from((date, volume, transfer_count): (String, String, i64)) -> Self {
+        Self {
+            date,
+            volume,
+            transfer_count,
+        }
+    }
+}
+
 /// GET /api/v1/stats/daily — daily transfer volume (global).
+///
+/// With `?quote=USD`, volumes are converted into the quote currency.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/daily",
+    tag = "stats",
+    params(TimeSeriesParams, NetworkParams),
+    responses(
+        (status = 200, description = "Daily volume", body = ApiResponse<Vec<TimeSeriesEntry>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_daily_volume(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(90);
-    let rows = storage::repos::get_daily_volume(&state.pool, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-
-    let entries: Vec<TimeSeriesEntry> = rows
-        .into_iter()
-        .map(|(date, volume, count)| TimeSeriesEntry {
-            date,
-            volume,
-            transfer_count: count,
-        })
-        .collect();
+    chain: ChainId,
+    Query(params): Query<TimeSeriesParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 90)?;
+    let quote = normalize_currency(params.quote);
+    let rows = if use_normalized(&quote, params.format) {
+        storage::repos::get_daily_volume_normalized(&state.pool, chain.0, quote.as_deref(), limit)
+            .await
+    } else {
+        storage::repos::get_daily_volume(&state.pool, chain.0, limit).await
+    }?;
+
+    let entries: Vec<TimeSeriesEntry> = rows.into_iter().map(TimeSeriesEntry::from).collect();
     Ok(json_ok(entries))
 }
 
 /// GET /api/v1/stats/monthly — monthly transfer volume (global).
+///
+/// With `?quote=USD`, volumes are converted into the quote currency.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/monthly",
+    tag = "stats",
+    params(TimeSeriesParams, NetworkParams),
+    responses(
+        (status = 200, description = "Monthly volume", body = ApiResponse<Vec<TimeSeriesEntry>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_monthly_volume(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(24);
-    let rows = storage::repos::get_monthly_volume(&state.pool, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
-
-    let entries: Vec<TimeSeriesEntry> = rows
-        .into_iter()
-        .map(|(date, volume, count)| TimeSeriesEntry {
-            date,
-            volume,
-            transfer_count: count,
-        })
-        .collect();
+    chain: ChainId,
+    Query(params): Query<TimeSeriesParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 24)?;
+    let quote = normalize_currency(params.quote);
+    let rows = if use_normalized(&quote, params.format) {
+        storage::repos::get_monthly_volume_normalized(&state.pool, chain.0, quote.as_deref(), limit)
+            .await
+    } else {
+        storage::repos::get_monthly_volume(&state.pool, chain.0, limit).await
+    }?;
+
+    let entries: Vec<TimeSeriesEntry> = rows.into_iter().map(TimeSeriesEntry::from).collect();
     Ok(json_ok(entries))
 }
 
 /// GET /api/v1/tokens/:address/volume/daily — daily volume for a specific token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{address}/volume/daily",
+    tag = "tokens",
+    params(("address" = String, Path, description = "TIP-20 token address"), PaginationParams, NetworkParams),
+    responses(
+        (status = 200, description = "Daily volume for the token", body = ApiResponse<Vec<TimeSeriesEntry>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
 async fn get_token_daily_volume(
     State(state): State<Arc<AppState>>,
-    Path(address): Path<String>,
+    chain: ChainId,
+    Path(address): Path<TokenAddress>,
     Query(params): Query<PaginationParams>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let limit = params.limit.unwrap_or(90);
-    let rows = storage::repos::get_token_daily_volume(&state.pool, &address, limit)
-        .await
-        .map_err(|e| json_err(&e.to_string()).into())?;
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 90)?;
+    let rows =
+        storage::repos::get_token_daily_volume(&state.pool, chain.0, &address, limit).await?;
+    let decimals = match params.format {
+        AmountFormat::Raw => DEFAULT_DECIMALS,
+        AmountFormat::Decimal => decimals_for(&state.pool, chain, &address).await?,
+    };
 
     let entries: Vec<TimeSeriesEntry> = rows
         .into_iter()
         .map(|(date, volume, count)| TimeSeriesEntry {
             date,
-            volume,
+            volume: params.format.render(volume, decimals),
             transfer_count: count,
         })
         .collect();
     Ok(json_ok(entries))
 }
+
+// ─── FX Handlers ────────────────────────────────────────────────────────────
+
+/// GET /api/v1/fx/rates — stored daily FX rates, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/fx/rates",
+    tag = "fx",
+    params(FxRateParams),
+    responses(
+        (status = 200, description = "Stored FX rates", body = ApiResponse<Vec<FxRate>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+async fn get_fx_rates(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FxRateParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 100)?;
+    let base = normalize_currency(params.base);
+    let quote = normalize_currency(params.quote);
+    let rates =
+        storage::repos::get_fx_rates(&state.pool, base.as_deref(), quote.as_deref(), limit).await?;
+    Ok(json_ok(rates))
+}
@@ -0,0 +1,72 @@
+//! Request metrics and the `/metrics` endpoint that exposes them.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::{sync::Arc, time::Instant};
+use tempulse_core::metrics::{
+    PrometheusHandle, counter, describe_counter, describe_histogram, histogram,
+};
+
+use crate::AppState;
+
+/// Install the recorder and describe every API metric.
+pub fn install() -> eyre::Result<PrometheusHandle> {
+    let handle = tempulse_core::metrics::install(&[])?;
+
+    describe_counter!(
+        "tempulse_http_requests_total",
+        "HTTP requests by route, method and status"
+    );
+    describe_histogram!(
+        "tempulse_http_request_duration_seconds",
+        "HTTP request latency by route, method and status"
+    );
+    tempulse_core::metrics::describe_pool();
+
+    Ok(handle)
+}
+
+/// Middleware recording the count and latency of every routed request.
+///
+/// Requests are labeled with the route pattern (`/api/v1/tokens/:address`),
+/// not the concrete path, so label cardinality stays bounded.
+pub async fn track(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".into());
+    let method = request.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    let labels = [
+        ("route", route),
+        ("method", method),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    counter!("tempulse_http_requests_total", &labels).increment(1);
+    histogram!("tempulse_http_request_duration_seconds", &labels)
+        .record(started.elapsed().as_secs_f64());
+    response
+}
+
+/// GET /metrics — Prometheus text exposition.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses((status = 200, description = "Prometheus metrics", content_type = "text/plain", body = String))
+)]
+pub async fn render(State(state): State<Arc<AppState>>) -> String {
+    tempulse_core::metrics::record_pool(
+        state.pool.size(),
+        state.pool.num_idle(),
+        state.pool.options().get_max_connections(),
+    );
+    state.metrics.render()
+}
@@ -0,0 +1,211 @@
+//! Network selection — `?network=<name>` picks which of the indexed chains a
+//! request reads; requests without it read the server's `network` setting.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, State},
+    http::request::Parts,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use tempulse_storage::{self as storage, models::Network};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{ApiError, ApiResponse, AppState, ErrorBody, Query, json_ok};
+
+/// `?network=mainnet` — the network to read.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NetworkParams {
+    /// Name of a registered network; defaults to the server's network.
+    network: Option<String>,
+}
+
+/// Chain id of the network a request reads, resolved from `?network=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainId(pub i64);
+
+/// How long an unknown network name is answered from the loaded names before
+/// the registered networks are read again.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Network names the indexers have registered, and the one requests default to.
+pub struct Networks {
+    default: String,
+    known: RwLock<Known>,
+}
+
+#[derive(Default)]
+struct Known {
+    chain_ids: HashMap<String, i64>,
+    loaded_at: Option<Instant>,
+}
+
+impl Known {
+    fn fresh(&self) -> bool {
+        self.loaded_at
+            .is_some_and(|at| at.elapsed() < RELOAD_INTERVAL)
+    }
+}
+
+impl Networks {
+    pub fn new(default: String) -> Self {
+        Self {
+            default,
+            known: RwLock::default(),
+        }
+    }
+
+    /// Chain id of `name` (or the default network). Names not seen yet are
+    /// looked up again, since indexers register their network at startup, but
+    /// at most once per [`RELOAD_INTERVAL`], so unknown names cannot load the
+    /// database.
+    async fn resolve(&self, pool: &sqlx::PgPool, name: Option<&str>) -> Result<ChainId, ApiError> {
+        let name = name.unwrap_or(self.default.as_str());
+        let not_found = || {
+            ApiError::NotFound(format!(
+                "Network {name:?} is not indexed by this deployment"
+            ))
+        };
+        if let Some(&id) = self
+            .known
+            .read()
+            .expect("networks lock")
+            .chain_ids
+            .get(name)
+        {
+            return Ok(ChainId(id));
+        }
+        {
+            let mut known = self.known.write().expect("networks lock");
+            if known.fresh() {
+                // Loaded recently, possibly by a concurrent miss.
+                return known
+                    .chain_ids
+                    .get(name)
+                    .copied()
+                    .map(ChainId)
+                    .ok_or_else(not_found);
+            }
+            // Claim the reload, so concurrent misses do not repeat it.
+            known.loaded_at = Some(Instant::now());
+        }
+
+        let registered = storage::repos::get_networks(pool).await?;
+        let mut known = self.known.write().expect("networks lock");
+        known.chain_ids = registered
+            .into_iter()
+            .map(|n| (n.name, n.chain_id))
+            .collect();
+        known
+            .chain_ids
+            .get(name)
+            .copied()
+            .map(ChainId)
+            .ok_or_else(not_found)
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for ChainId {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<NetworkParams>::from_request_parts(parts, state).await?;
+        state
+            .networks
+            .resolve(&state.pool, params.network.as_deref())
+            .await
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct NetworksResponse {
+    /// Network read by requests without `?network=`.
+    default: String,
+    networks: Vec<Network>,
+}
+
+/// GET /api/v1/networks — networks indexed into this deployment's database.
+#[utoipa::path(
+    get,
+    path = "/api/v1/networks",
+    tag = "system",
+    responses(
+        (status = 200, description = "Registered networks", body = ApiResponse<NetworksResponse>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn list_networks(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let networks = storage::repos::get_networks(&state.pool).await?;
+    Ok(json_ok(NetworksResponse {
+        default: state.networks.default.clone(),
+        networks,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pool that fails any query, to prove a lookup never reached it.
+    fn unreachable_pool() -> sqlx::PgPool {
+        sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+            .expect("lazy pool")
+    }
+
+    fn loaded(names: &[(&str, i64)]) -> Networks {
+        let networks = Networks::new("testnet".into());
+        *networks.known.write().unwrap() = Known {
+            chain_ids: names.iter().map(|&(n, id)| (n.to_string(), id)).collect(),
+            loaded_at: Some(Instant::now()),
+        };
+        networks
+    }
+
+    #[tokio::test]
+    async fn resolves_loaded_names_and_the_default() {
+        let networks = loaded(&[("testnet", 42429), ("mainnet", 4217)]);
+        let pool = unreachable_pool();
+        assert_eq!(networks.resolve(&pool, None).await.unwrap(), ChainId(42429));
+        assert_eq!(
+            networks.resolve(&pool, Some("mainnet")).await.unwrap(),
+            ChainId(4217)
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_names_do_not_reload_within_the_interval() {
+        let networks = loaded(&[("testnet", 42429)]);
+        let pool = unreachable_pool();
+        for _ in 0..3 {
+            assert!(matches!(
+                networks.resolve(&pool, Some("nope")).await,
+                Err(ApiError::NotFound(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_names_reload_once_the_interval_passed() {
+        let networks = loaded(&[("testnet", 42429)]);
+        networks.known.write().unwrap().loaded_at = Instant::now().checked_sub(RELOAD_INTERVAL);
+        // The reload reaches the (unreachable) database instead of answering 404.
+        assert!(!matches!(
+            networks.resolve(&unreachable_pool(), Some("nope")).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+//! OpenAPI description of the REST API, served alongside an interactive docs page.
+
+use axum::{Json, response::Html};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+
+use crate::error::{ErrorBody, ErrorDetail};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Tempulse API",
+        description = "Stablecoin analytics for TIP-20 tokens on Tempo."
+    ),
+    paths(
+        crate::list_tokens,
+        crate::get_token,
+        crate::get_holders,
+        crate::get_token_transfers,
+        crate::get_volume,
+        crate::get_overview,
+        crate::get_daily_volume,
+        crate::get_monthly_volume,
+        crate::get_token_daily_volume,
+        crate::analytics::get_distribution,
+        crate::analytics::get_token_velocity,
+        crate::analytics::get_velocity,
+        crate::analytics::get_token_issuance,
+        crate::analytics::get_issuance,
+        crate::analytics::get_flows,
+        crate::get_recent_activity,
+        crate::get_fx_rates,
+        crate::labels::list_labels,
+        crate::labels::put_label,
+        crate::labels::delete_label,
+        crate::labels::import_labels,
+        crate::webhooks::list_webhooks,
+        crate::webhooks::create_webhook,
+        crate::webhooks::get_webhook,
+        crate::webhooks::update_webhook,
+        crate::webhooks::delete_webhook,
+        crate::webhooks::list_dead_letters,
+        crate::webhooks::retry_dead_letter,
+        crate::network::list_networks,
+        crate::status::get_status,
+        crate::health,
+        crate::status::ready,
+        crate::stream::sse,
+        crate::stream::websocket,
+        crate::graphql::handler,
+        crate::graphql::graphiql,
+        spec,
+        docs,
+        crate::metrics::render,
+    ),
+    components(schemas(
+        ErrorBody,
+        ErrorDetail,
+        crate::AmountFormat,
+        tempulse_storage::models::IssuanceInterval
+    )),
+    tags(
+        (name = "tokens", description = "Token metadata, holders and transfers"),
+        (name = "stats", description = "Volume and network aggregates"),
+        (name = "activity", description = "Recent network activity"),
+        (name = "fx", description = "Stored exchange rates"),
+        (name = "labels", description = "Address labels (writes are admin-only)"),
+        (name = "webhooks", description = "Outbound webhook subscriptions (admin)"),
+        (name = "streams", description = "Live transfers over Server-Sent Events and WebSocket"),
+        (name = "graphql", description = "GraphQL endpoint and explorer"),
+        (name = "system", description = "Service health, indexed networks, indexer status, metrics and these docs"),
+    ),
+    modifiers(&AdminToken)
+)]
+pub struct ApiDoc;
+
+/// Registers the `admin_token` bearer scheme used by admin endpoints.
+struct AdminToken;
+
+impl Modify for AdminToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "admin_token",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+    }
+}
+
+/// GET /api/v1/openapi.json — the OpenAPI 3.1 spec for this server.
+#[utoipa::path(
+    get,
+    path = "/api/v1/openapi.json",
+    tag = "system",
+    responses((status = 200, description = "This OpenAPI spec", body = Object))
+)]
+pub async fn spec() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// GET /api/v1/docs — a page rendering the spec above.
+#[utoipa::path(
+    get,
+    path = "/api/v1/docs",
+    tag = "system",
+    responses((status = 200, description = "API reference", content_type = "text/html", body = String))
+)]
+pub async fn docs() -> Html<&'static str> {
+    Html(include_str!("../static/docs.html"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Fails when a route is registered without a `#[utoipa::path]` entry in
+    /// [`ApiDoc`] (or vice versa), so the published spec cannot drift.
+    #[test]
+    fn spec_matches_routes() {
+        let routed: BTreeSet<String> = crate::api_routes()
+            .into_iter()
+            .chain(crate::uncached_routes())
+            .chain(crate::bare_routes())
+            .map(|(path, _)| {
+                path.split('/')
+                    .map(|seg| match seg.strip_prefix(':') {
+                        Some(param) => format!("{{{param}}}"),
+                        None => seg.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        let documented: BTreeSet<String> = ApiDoc::openapi().paths.paths.into_keys().collect();
+
+        let undocumented: Vec<_> = routed.difference(&documented).collect();
+        let unrouted: Vec<_> = documented.difference(&routed).collect();
+        assert!(
+            undocumented.is_empty() && unrouted.is_empty(),
+            "OpenAPI spec out of sync with router: undocumented {undocumented:?}, unrouted {unrouted:?}"
+        );
+    }
+}
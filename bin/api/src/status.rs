@@ -0,0 +1,164 @@
+//! Indexer freshness: the `/api/v1/status` report and the `/ready` probe.
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::{sync::Arc, time::Duration};
+use tempulse_core::AppError;
+use tempulse_storage::{
+    self as storage,
+    models::{IndexerStatus, Reorg},
+};
+use utoipa::ToSchema;
+
+use crate::{
+    ApiError, ApiResponse, AppState, ErrorBody, json_ok,
+    network::{ChainId, NetworkParams},
+};
+
+/// How long the readiness probe waits for the database.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lag limits beyond which the API reports itself not ready.
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    /// Blocks behind the chain head; 0 disables the check.
+    pub max_lag_blocks: u64,
+    /// Age of the newest indexed block in seconds; 0 disables the check.
+    pub max_lag_secs: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct StatusResponse {
+    last_indexed_block: i64,
+    /// Block timestamp (unix seconds) of the newest indexed block.
+    last_indexed_timestamp: Option<i64>,
+    /// Chain head as last read by the indexer.
+    chain_head: Option<i64>,
+    chain_head_seen_at: Option<NaiveDateTime>,
+    /// `chain_head - last_indexed_block`.
+    lag_blocks: Option<i64>,
+    /// Seconds since the newest indexed block was produced.
+    lag_seconds: Option<i64>,
+    last_reorg: Option<Reorg>,
+}
+
+impl From<IndexerStatus> for StatusResponse {
+    fn from(s: IndexerStatus) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            last_indexed_block: s.last_indexed_block,
+            last_indexed_timestamp: s.last_indexed_timestamp,
+            chain_head: s.chain_head,
+            chain_head_seen_at: s.chain_head_seen_at,
+            lag_blocks: s
+                .chain_head
+                .map(|head| (head - s.last_indexed_block).max(0)),
+            lag_seconds: s.last_indexed_timestamp.map(|ts| (now - ts).max(0)),
+            last_reorg: s.last_reorg,
+        }
+    }
+}
+
+impl StatusResponse {
+    /// Why the indexed data is too stale to serve; empty when it is fresh enough.
+    fn stale_reasons(&self, limits: Thresholds) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if limits.max_lag_blocks > 0 {
+            match self.lag_blocks {
+                None => reasons.push("indexer has not reported the chain head yet".into()),
+                Some(lag) if lag as u64 > limits.max_lag_blocks => reasons.push(format!(
+                    "{lag} blocks behind the chain head (limit {})",
+                    limits.max_lag_blocks
+                )),
+                Some(_) => {}
+            }
+        }
+        if limits.max_lag_secs > 0 {
+            match self.lag_seconds {
+                None => reasons.push("no blocks indexed yet".into()),
+                Some(lag) if lag as u64 > limits.max_lag_secs => reasons.push(format!(
+                    "newest indexed block is {lag}s old (limit {}s)",
+                    limits.max_lag_secs
+                )),
+                Some(_) => {}
+            }
+        }
+        reasons
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    ready: bool,
+    /// Why the service is not ready; empty when it is.
+    reasons: Vec<String>,
+    status: StatusResponse,
+}
+
+/// GET /api/v1/status — indexer progress, lag and the latest reorg.
+#[utoipa::path(
+    get,
+    path = "/api/v1/status",
+    tag = "system",
+    params(NetworkParams),
+    responses(
+        (status = 200, description = "Indexer status", body = ApiResponse<StatusResponse>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_status(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+) -> Result<Json<ApiResponse<StatusResponse>>, ApiError> {
+    let status = storage::repos::get_indexer_status(&state.pool, chain.0).await?;
+    Ok(json_ok(status.into()))
+}
+
+/// GET /ready — readiness probe.
+///
+/// Answers 503 when the database is unreachable or the indexed data lags
+/// beyond `READY_MAX_LAG_BLOCKS` / `READY_MAX_LAG_SECS`.
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "system",
+    params(NetworkParams),
+    responses(
+        (status = 200, description = "Ready to serve", body = ApiResponse<ReadinessResponse>),
+        (status = 503, description = "Database unreachable or indexer lagging", body = ApiResponse<ReadinessResponse>),
+    )
+)]
+pub async fn ready(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+) -> Result<impl IntoResponse, ApiError> {
+    let status = tokio::time::timeout(
+        READY_TIMEOUT,
+        storage::repos::get_indexer_status(&state.pool, chain.0),
+    )
+    .await
+    .map_err(|_| AppError::Database("timed out reading indexer status".into()))
+    .and_then(|r| r.map_err(|e| AppError::Database(e.to_string())))
+    .map_err(ApiError::Unavailable)?;
+
+    let status = StatusResponse::from(status);
+    let reasons = status.stale_reasons(state.readiness);
+    let ready = reasons.is_empty();
+    let code = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((
+        code,
+        Json(ApiResponse {
+            success: ready,
+            data: ReadinessResponse {
+                ready,
+                reasons,
+                status,
+            },
+        }),
+    ))
+}
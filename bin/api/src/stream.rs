@@ -0,0 +1,323 @@
+//! Real-time transfer streaming over Server-Sent Events and WebSocket.
+//!
+//! The indexer issues a `NOTIFY` carrying the chain and block range of every
+//! committed batch. A single listener per API process loads those transfers
+//! and fans them out over a broadcast channel; each client connection then
+//! applies its own filters, starting with the network it asked for.
+
+use axum::{
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::HashMap, convert::Infallible, sync::Arc, time::Duration};
+use tempulse_core::address::{Address, TokenAddress};
+use tempulse_storage::{
+    self as storage,
+    models::{Transfer, TransfersCommitted},
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::IntoParams;
+
+use crate::{
+    ApiError, AppState, ErrorBody, Query,
+    network::{ChainId, NetworkParams},
+};
+
+/// Transfers buffered per client before a slow client starts missing events.
+pub const STREAM_BUFFER: usize = 4096;
+
+/// Delay before re-establishing a failed `LISTEN` connection.
+const LISTENER_RETRY: Duration = Duration::from_secs(5);
+
+/// Most blocks of missed notifications replayed before a batch; older gaps are dropped.
+const MAX_CATCH_UP_BLOCKS: i64 = 1_000;
+
+/// Interval of SSE keep-alive comments and WebSocket pings.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Load the transfers of every committed batch and publish them on `tx`.
+///
+/// Runs forever, reconnecting after failures. Each network's transfers are
+/// published in chain order. If notifications were missed (e.g. during a reconnect), the next one
+/// also covers up to [`MAX_CATCH_UP_BLOCKS`] skipped blocks; after a reorg, the replacement transfers of
+/// the re-indexed blocks are published again.
+pub async fn run_listener(pool: sqlx::PgPool, tx: broadcast::Sender<Arc<Transfer>>) {
+    // Last streamed block per chain; networks registered later stream from their next batch.
+    let mut last_streamed: HashMap<i64, i64> = match storage::repos::get_last_indexed_blocks(&pool)
+        .await
+    {
+        Ok(cursors) => cursors.into_iter().collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not read indexer cursors; streaming from next batch");
+            HashMap::new()
+        }
+    };
+
+    loop {
+        if let Err(e) = listen(&pool, &tx, &mut last_streamed).await {
+            tracing::warn!(error = %e, "Transfer listener failed; retrying");
+        }
+        tokio::time::sleep(LISTENER_RETRY).await;
+    }
+}
+
+async fn listen(
+    pool: &sqlx::PgPool,
+    tx: &broadcast::Sender<Arc<Transfer>>,
+    last_streamed: &mut HashMap<i64, i64>,
+) -> Result<(), sqlx::Error> {
+    let mut listener = sqlx::postgres::PgListener::connect_with(pool).await?;
+    listener.listen(storage::repos::TRANSFERS_CHANNEL).await?;
+    tracing::info!(
+        channel = storage::repos::TRANSFERS_CHANNEL,
+        "Listening for committed transfers"
+    );
+
+    loop {
+        let notification = listener.recv().await?;
+        let batch: TransfersCommitted = match serde_json::from_str(notification.payload()) {
+            Ok(batch) => batch,
+            Err(e) => {
+                tracing::warn!(error = %e, payload = notification.payload(), "Bad notification");
+                continue;
+            }
+        };
+
+        let last = last_streamed.entry(batch.chain_id).or_insert(i64::MAX);
+        let from = batch
+            .from_block
+            .min(last.saturating_add(1))
+            .max(batch.from_block - MAX_CATCH_UP_BLOCKS);
+        let transfers =
+            storage::repos::get_transfers_in_blocks(pool, batch.chain_id, from, batch.to_block)
+                .await?;
+        *last = batch.to_block;
+
+        for transfer in transfers {
+            // An error only means nobody is subscribed right now.
+            let _ = tx.send(Arc::new(transfer));
+        }
+    }
+}
+
+// ─── Filters ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventType {
+    Transfer,
+    Mint,
+    Burn,
+}
+
+impl EventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventType::Transfer => "transfer",
+            EventType::Mint => "mint",
+            EventType::Burn => "burn",
+        }
+    }
+}
+
+/// `?token=&address=&min_amount=&event_type=` — server-side stream filters.
+/// The network comes from `?network=`, like every other endpoint.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamParams {
+    #[param(value_type = Option<String>)]
+    token: Option<TokenAddress>,
+    /// Matches either side of the transfer.
+    #[param(value_type = Option<String>)]
+    address: Option<Address>,
+    /// Minimum amount in base units.
+    min_amount: Option<String>,
+    /// `transfer`, `mint` or `burn`.
+    #[param(value_type = Option<String>)]
+    event_type: Option<EventType>,
+}
+
+/// Validated filters applied to each streamed transfer.
+struct TransferFilter {
+    chain: ChainId,
+    token: Option<TokenAddress>,
+    address: Option<Address>,
+    min_amount: Option<String>,
+    event_type: Option<EventType>,
+}
+
+impl TransferFilter {
+    fn new(chain: ChainId, params: StreamParams) -> Result<Self, ApiError> {
+        Ok(Self {
+            chain,
+            token: params.token,
+            address: params.address,
+            min_amount: params.min_amount.map(parse_min_amount).transpose()?,
+            event_type: params.event_type,
+        })
+    }
+
+    fn matches(&self, t: &Transfer) -> bool {
+        t.chain_id == self.chain.0
+            && self
+                .token
+                .as_ref()
+                .is_none_or(|token| t.token_address == token.as_str())
+            && self
+                .address
+                .as_ref()
+                .is_none_or(|a| t.from_address == a.as_str() || t.to_address == a.as_str())
+            && self.event_type.is_none_or(|e| t.event_type == e.as_str())
+            && self
+                .min_amount
+                .as_deref()
+                .is_none_or(|min| cmp_amounts(&t.amount, min) != Ordering::Less)
+    }
+}
+
+/// Validate a `min_amount` filter (base units) and strip leading zeros.
+pub fn parse_min_amount(raw: String) -> Result<String, ApiError> {
+    let digits = raw.trim();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ApiError::InvalidParameter(format!(
+            "min_amount must be a non-negative integer in base units, got {raw:?}"
+        )));
+    }
+    Ok(strip_leading_zeros(digits).to_string())
+}
+
+fn strip_leading_zeros(digits: &str) -> &str {
+    let trimmed = digits.trim_start_matches('0');
+    if trimmed.is_empty() { "0" } else { trimmed }
+}
+
+/// Compare two base-unit integer strings without overflowing.
+fn cmp_amounts(a: &str, b: &str) -> Ordering {
+    let (a, b) = (strip_leading_zeros(a), strip_leading_zeros(b));
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────
+
+/// Message sent to a client that fell too far behind; `skipped` transfers were dropped.
+#[derive(Serialize)]
+struct Lagged {
+    skipped: u64,
+}
+
+/// GET /api/v1/stream/transfers — new transfers as Server-Sent Events.
+///
+/// Each transfer is a `transfer` event with the JSON transfer as data. A client
+/// that cannot keep up receives a `lagged` event and continues from the newest
+/// transfers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stream/transfers",
+    tag = "streams",
+    params(StreamParams, NetworkParams),
+    responses(
+        (status = 200, description = "`transfer` and `lagged` events", content_type = "text/event-stream", body = String),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn sse(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Query(params): Query<StreamParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filter = TransferFilter::new(chain, params)?;
+    let mut rx = state.transfers.subscribe();
+
+    let events = async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(transfer) if filter.matches(&transfer) => {
+                    if let Ok(event) = Event::default().event("transfer").json_data(&*transfer) {
+                        yield Ok::<_, Infallible>(event);
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    if let Ok(event) = Event::default().event("lagged").json_data(Lagged { skipped }) {
+                        yield Ok(event);
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(KEEP_ALIVE)))
+}
+
+/// GET /api/v1/stream/transfers/ws — new transfers over a WebSocket.
+///
+/// Sends one JSON text message per transfer, tagged `{"type": "transfer", ...}`,
+/// and `{"type": "lagged", "skipped": n}` when the client falls behind.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stream/transfers/ws",
+    tag = "streams",
+    params(StreamParams, NetworkParams),
+    responses(
+        (status = 101, description = "Switched to a WebSocket carrying `transfer` and `lagged` messages"),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn websocket(
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Query(params): Query<StreamParams>,
+    upgrade: WebSocketUpgrade,
+) -> Result<impl IntoResponse, ApiError> {
+    let filter = TransferFilter::new(chain, params)?;
+    let rx = state.transfers.subscribe();
+    Ok(upgrade.on_upgrade(move |socket| forward_to_socket(socket, rx, filter)))
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum WsMessage<'a> {
+    Transfer(&'a Transfer),
+    Lagged(Lagged),
+}
+
+async fn forward_to_socket(
+    mut socket: WebSocket,
+    mut rx: broadcast::Receiver<Arc<Transfer>>,
+    filter: TransferFilter,
+) {
+    let mut ping = tokio::time::interval(KEEP_ALIVE);
+    loop {
+        let message = tokio::select! {
+            received = rx.recv() => match received {
+                Ok(transfer) if filter.matches(&transfer) => WsMessage::Transfer(&transfer).to_text(),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => WsMessage::Lagged(Lagged { skipped }).to_text(),
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                // Clients only listen; anything but a close frame is ignored.
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            _ = ping.tick() => Message::Ping(Vec::new()),
+        };
+        if socket.send(message).await.is_err() {
+            break;
+        }
+    }
+}
+
+impl WsMessage<'_> {
+    fn to_text(&self) -> Message {
+        Message::Text(serde_json::to_string(self).expect("stream message serializes"))
+    }
+}
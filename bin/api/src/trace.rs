@@ -0,0 +1,33 @@
+//! Request spans, joined to the caller's trace when it sends `traceparent`.
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+/// Middleware running every routed request inside a `request` span.
+///
+/// The span is named after the route pattern rather than the concrete path,
+/// and carries the response status once the handler returns.
+pub async fn span(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".into());
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        route,
+        status = tracing::field::Empty,
+    );
+    tempulse_core::telemetry::set_remote_parent(&span, |name| {
+        request.headers().get(name).and_then(|v| v.to_str().ok())
+    });
+
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("status", response.status().as_u16());
+    response
+}
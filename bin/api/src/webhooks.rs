@@ -0,0 +1,283 @@
+//! Webhook subscription management (admin only).
+//!
+//! Deliveries themselves are performed by the indexer; see `tempulse-webhooks`.
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tempulse_core::address::{Address, TokenAddress};
+use tempulse_storage::{
+    self as storage,
+    models::{WebhookDeadLetter, WebhookSubscription, WebhookSubscriptionSpec},
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    ApiError, ApiResponse, AppState, ErrorBody, Path, Query,
+    admin::Admin,
+    json_ok,
+    network::{ChainId, NetworkParams},
+    stream::{EventType, parse_min_amount},
+};
+
+/// Body of `POST /api/v1/webhooks` and `PUT /api/v1/webhooks/:id`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WebhookRequest {
+    /// Endpoint receiving `POST`ed JSON payloads.
+    url: String,
+    #[serde(default)]
+    description: String,
+    /// Only transfers of this token.
+    #[schema(value_type = Option<String>)]
+    token_address: Option<TokenAddress>,
+    /// Only transfers sent or received by this address.
+    #[schema(value_type = Option<String>)]
+    address: Option<Address>,
+    /// Only transfers of at least this amount, in base units.
+    min_amount: Option<String>,
+    /// Only `transfer`, `mint` or `burn` events.
+    #[schema(value_type = Option<String>)]
+    event_type: Option<EventType>,
+    #[serde(default = "default_active")]
+    active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl TryFrom<WebhookRequest> for WebhookSubscriptionSpec {
+    type Error = ApiError;
+
+    fn try_from(req: WebhookRequest) -> Result<Self, Self::Error> {
+        let url = req.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(ApiError::InvalidParameter(format!(
+                "url must be an http(s) URL, got {url:?}"
+            )));
+        }
+
+        Ok(Self {
+            url: url.to_string(),
+            description: req.description,
+            token_address: req.token_address.map(String::from),
+            address: req.address.map(String::from),
+            min_amount: req.min_amount.map(parse_min_amount).transpose()?,
+            event_type: req.event_type.map(|e| e.as_str().to_string()),
+            active: req.active,
+        })
+    }
+}
+
+/// A newly created subscription, including its signing secret.
+#[derive(Serialize, ToSchema)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    subscription: WebhookSubscription,
+    /// Key for verifying the `X-Tempulse-Signature` header. Shown only once.
+    secret: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeadLetterParams {
+    limit: Option<i64>,
+}
+
+/// 32 random bytes, hex-encoded.
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn not_found(id: i64) -> ApiError {
+    ApiError::NotFound(format!("Webhook {id} not found"))
+}
+
+/// GET /api/v1/webhooks — list the network's subscriptions.
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(NetworkParams),
+    responses(
+        (status = 200, description = "Subscriptions", body = ApiResponse<Vec<WebhookSubscription>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn list_webhooks(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+) -> Result<impl IntoResponse, ApiError> {
+    let subscriptions = storage::repos::get_webhook_subscriptions(&state.pool, chain.0).await?;
+    Ok(json_ok(subscriptions))
+}
+
+/// POST /api/v1/webhooks — create a subscription to the network's transfers
+/// and return its signing secret.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(NetworkParams),
+    request_body = WebhookRequest,
+    responses(
+        (status = 201, description = "Created subscription", body = ApiResponse<CreatedWebhook>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn create_webhook(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    chain: ChainId,
+    Json(request): Json<WebhookRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let spec = WebhookSubscriptionSpec::try_from(request)?;
+    let secret = generate_secret();
+    let subscription =
+        storage::repos::create_webhook_subscription(&state.pool, chain.0, &spec, &secret).await?;
+    tracing::info!(
+        id = subscription.id,
+        chain_id = chain.0,
+        url = %subscription.url,
+        "Webhook created"
+    );
+    Ok((
+        StatusCode::CREATED,
+        json_ok(CreatedWebhook {
+            subscription,
+            secret,
+        }),
+    ))
+}
+
+/// GET /api/v1/webhooks/:id — a single subscription.
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{id}",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(("id" = i64, Path, description = "Subscription id")),
+    responses(
+        (status = 200, description = "Subscription", body = ApiResponse<WebhookSubscription>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn get_webhook(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    match storage::repos::get_webhook_subscription(&state.pool, id).await? {
+        Some(subscription) => Ok(json_ok(subscription)),
+        None => Err(not_found(id)),
+    }
+}
+
+/// PUT /api/v1/webhooks/:id — replace a subscription's URL and filters (the secret is kept).
+#[utoipa::path(
+    put,
+    path = "/api/v1/webhooks/{id}",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(("id" = i64, Path, description = "Subscription id")),
+    request_body = WebhookRequest,
+    responses(
+        (status = 200, description = "Updated subscription", body = ApiResponse<WebhookSubscription>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn update_webhook(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Json(request): Json<WebhookRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let spec = WebhookSubscriptionSpec::try_from(request)?;
+    match storage::repos::update_webhook_subscription(&state.pool, id, &spec).await? {
+        Some(subscription) => Ok(json_ok(subscription)),
+        None => Err(not_found(id)),
+    }
+}
+
+/// DELETE /api/v1/webhooks/:id — remove a subscription and its queued deliveries.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{id}",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(("id" = i64, Path, description = "Subscription id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn delete_webhook(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    if storage::repos::delete_webhook_subscription(&state.pool, id).await? {
+        tracing::info!(id, "Webhook deleted");
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found(id))
+    }
+}
+
+/// GET /api/v1/webhooks/:id/dead-letters — deliveries that exhausted their retries.
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{id}/dead-letters",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(("id" = i64, Path, description = "Subscription id"), DeadLetterParams),
+    responses(
+        (status = 200, description = "Dead letters, newest first", body = ApiResponse<Vec<WebhookDeadLetter>>),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn list_dead_letters(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(params): Query<DeadLetterParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = state.page_limit(params.limit, 100)?;
+    let letters = storage::repos::get_webhook_dead_letters(&state.pool, id, limit).await?;
+    Ok(json_ok(letters))
+}
+
+/// POST /api/v1/webhooks/:id/dead-letters/:letter_id/retry — queue a dead letter again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/{id}/dead-letters/{letter_id}/retry",
+    tag = "webhooks",
+    security(("admin_token" = [])),
+    params(
+        ("id" = i64, Path, description = "Subscription id"),
+        ("letter_id" = i64, Path, description = "Dead letter id"),
+    ),
+    responses(
+        (status = 202, description = "Queued for delivery"),
+        (status = "default", description = "Error", body = ErrorBody),
+    )
+)]
+pub async fn retry_dead_letter(
+    _: Admin,
+    State(state): State<Arc<AppState>>,
+    Path((id, letter_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, ApiError> {
+    if storage::repos::requeue_webhook_dead_letter(&state.pool, id, letter_id).await? {
+        Ok(StatusCode::ACCEPTED)
+    } else {
+        Err(ApiError::NotFound(format!(
+            "Dead letter {letter_id} of webhook {id} not found"
+        )))
+    }
+}
@@ -0,0 +1,66 @@
+//! The `fin` indexer with one extra event handler, which logs every transfer
+//! at or above a threshold.
+//!
+//! ```sh
+//! WHALE_THRESHOLD=1000000000000 cargo run -p fin --example whale_alerts -- run
+//! ```
+//!
+//! Takes the same subcommands and settings as `fin`. `WHALE_THRESHOLD` is in
+//! token base units (default 10^12, one million of a 6-decimal token).
+
+use sqlx::PgConnection;
+use tempulse_handlers::{Batch, EventHandler, Handlers, async_trait};
+
+struct WhaleAlerts {
+    threshold: u128,
+}
+
+#[async_trait]
+impl EventHandler for WhaleAlerts {
+    fn name(&self) -> &str {
+        "whale_alerts"
+    }
+
+    async fn handle(&self, _conn: &mut PgConnection, batch: &Batch<'_>) -> eyre::Result<()> {
+        for event in batch.events {
+            let t = &event.transfer;
+            if t.amount
+                .parse::<u128>()
+                .is_ok_and(|amount| amount >= self.threshold)
+            {
+                tracing::warn!(
+                    token = %t.token_address,
+                    from = %t.from_address,
+                    to = %t.to_address,
+                    amount = %t.amount,
+                    tx = %t.transaction_hash,
+                    "Whale {}",
+                    t.event_type
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn roll_back(
+        &self,
+        _conn: &mut PgConnection,
+        _chain_id: i64,
+        _block: i64,
+    ) -> eyre::Result<()> {
+        // Nothing stored, so nothing to undo
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let threshold = std::env::var("WHALE_THRESHOLD")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1_000_000_000_000);
+
+    let mut handlers = Handlers::builtin();
+    handlers.register(WhaleAlerts { threshold });
+    fin::run_cli(handlers).await
+}
@@ -0,0 +1,180 @@
+//! Command-line interface: `fin [--config <file>] [--print-config] [<command>]`.
+
+use clap::{Parser, Subcommand};
+use std::{path::PathBuf, str::FromStr};
+use tempulse_core::address::TokenAddress;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "fin",
+    version,
+    about = "Tempulse indexer for TIP-20 stablecoin events"
+)]
+pub struct Cli {
+    /// TOML file to load instead of the default.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Print the effective settings and exit.
+    #[arg(long, global = true)]
+    pub print_config: bool,
+
+    /// What to do; `run` if omitted.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands. Those that move the cursor take the network's writer lock, so
+/// they fail fast while `run` is indexing the same network.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Index new blocks until interrupted.
+    Run,
+
+    /// Index blocks `--from` through `--to` and exit. Blocks up to the cursor
+    /// are fetched again and the derived rows re-derived from there on; blocks
+    /// after it are indexed as `run` would.
+    Backfill {
+        /// First block; defaults to the block after the cursor.
+        #[arg(long)]
+        from: Option<u64>,
+        /// Last block.
+        #[arg(long)]
+        to: u64,
+    },
+
+    /// Re-fetch one token's logs over a block range and rebuild its transfers,
+    /// balances, supply and stats. Safe while `run` is indexing. Refuses to run
+    /// with handlers other than the built-in ones, whose tables it cannot rebuild.
+    ReindexToken {
+        /// TIP-20 token address.
+        address: TokenAddress,
+        /// First block; defaults to the block the token was created at.
+        #[arg(long)]
+        from: Option<u64>,
+        /// Last block; defaults to the cursor.
+        #[arg(long)]
+        to: Option<u64>,
+    },
+
+    /// Delete the derived rows from `--from` on and derive them again from the
+    /// stored logs, without the RPC. Picks up events and metrics added since
+    /// those blocks were processed.
+    Reprocess {
+        /// First block to re-derive.
+        #[arg(long)]
+        from: u64,
+    },
+
+    /// Delete indexed data after a block and move the cursor back to it.
+    Rollback {
+        /// Last block to keep.
+        #[arg(long)]
+        to_block: u64,
+    },
+
+    /// Scan the factory for TokenCreated events and register missing tokens.
+    DiscoverTokens,
+
+    /// Compare stored block hashes with the chain; exits non-zero on a mismatch.
+    /// Hashes are kept for the last block of each batch and for blocks whose
+    /// logs came without a timestamp, so other blocks in the range are skipped.
+    Verify {
+        /// Inclusive range, e.g. `1000..2000`.
+        #[arg(long, value_name = "FROM..TO")]
+        block_range: BlockRange,
+    },
+
+    /// Print the cursors, chain head, lag and latest reorg.
+    Status,
+}
+
+/// An inclusive block range written `FROM..TO`.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockRange {
+    pub from: u64,
+    pub to: u64,
+}
+
+impl FromStr for BlockRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s
+            .split_once("..")
+            .ok_or_else(|| format!("expected FROM..TO, got {s:?}"))?;
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u64>()
+                .map_err(|e| format!("invalid block number {n:?}: {e}"))
+        };
+        let range = Self {
+            from: parse(from)?,
+            to: parse(to)?,
+        };
+        if range.from > range.to {
+            return Err(format!("range {s:?} ends before it starts"));
+        }
+        Ok(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_inclusive_ranges() {
+        let range: BlockRange = "1000..2000".parse().unwrap();
+        assert_eq!((range.from, range.to), (1000, 2000));
+        let range: BlockRange = " 7 .. 7 ".parse().unwrap();
+        assert_eq!((range.from, range.to), (7, 7));
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        for (input, error) in [
+            ("1000", "expected FROM..TO"),
+            ("1000-2000", "expected FROM..TO"),
+            ("..2000", "invalid block number"),
+            ("1000..", "invalid block number"),
+            ("-1..5", "invalid block number"),
+            ("1000..2000..3000", "invalid block number"),
+            ("2000..1000", "ends before it starts"),
+        ] {
+            let err = input.parse::<BlockRange>().unwrap_err();
+            assert!(err.contains(error), "{input:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn verify_takes_a_block_range() {
+        let cli = Cli::try_parse_from(["fin", "verify", "--block-range", "10..20"]).unwrap();
+        match cli.command {
+            Some(Command::Verify { block_range }) => {
+                assert_eq!((block_range.from, block_range.to), (10, 20))
+            }
+            other => panic!("expected verify, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["fin", "verify", "--block-range", "20..10"]).is_err());
+    }
+
+    #[test]
+    fn backfill_takes_an_optional_start() {
+        let cli =
+            Cli::try_parse_from(["fin", "backfill", "--from", "800", "--to", "5000"]).unwrap();
+        match cli.command {
+            Some(Command::Backfill { from, to }) => assert_eq!((from, to), (Some(800), 5000)),
+            other => panic!("expected backfill, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(["fin", "backfill", "--to", "5000"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Backfill {
+                from: None,
+                to: 5000
+            })
+        ));
+        assert!(Cli::try_parse_from(["fin", "backfill", "--from", "800"]).is_err());
+    }
+}
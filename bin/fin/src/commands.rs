@@ -0,0 +1,379 @@
+//! One-shot maintenance subcommands. Each reuses the ingestion, processing and
+//! rollback paths of `run`, so operators never have to edit `indexer_state` by hand.
+
+use alloy::{
+    network::primitives::HeaderResponse, primitives::Address, providers::Provider,
+    rpc::types::Filter, sol_types::SolEvent,
+};
+use eyre::Result;
+use std::time::Instant;
+use tempulse_core::{Settings, address::TokenAddress};
+use tempulse_handlers::{Handlers, transfer_row};
+use tempulse_storage as storage;
+use tempulse_tempo::{TIP20, decoder, provider::TempoProvider};
+
+use crate::{
+    Chain,
+    cli::{BlockRange, Command},
+    connect, discover_tokens, first_block, index_next_batch, ingest, lock_network, metrics,
+    process::{self, Rollback, block_time},
+};
+
+/// Run a subcommand other than `run`.
+pub async fn execute(command: Command, settings: &Settings, handlers: &Handlers) -> Result<()> {
+    let (pool, provider, chain) = connect(settings).await?;
+    match command {
+        Command::Run => unreachable!("`run` is handled by `run_cli`"),
+        Command::Backfill { from, to } => {
+            let _lock = lock_network(&pool, settings, &chain).await?;
+            backfill(&provider, &pool, settings, &chain, handlers, from, to).await
+        }
+        Command::ReindexToken { address, from, to } => {
+            only_builtin_handlers(handlers)?;
+            reindex_token(&provider, &pool, settings, &chain, &address, from, to).await
+        }
+        Command::Reprocess { from } => {
+            let _lock = lock_network(&pool, settings, &chain).await?;
+            reprocess(&pool, settings, &chain, handlers, from as i64).await
+        }
+        Command::Rollback { to_block } => {
+            let _lock = lock_network(&pool, settings, &chain).await?;
+            rollback(&pool, &chain, handlers, to_block as i64).await
+        }
+        Command::DiscoverTokens => {
+            discover_tokens(&provider, &pool, settings, &chain).await?;
+            let count = storage::repos::get_token_count(&pool, chain.id).await?;
+            tracing::info!(count, "Token discovery complete");
+            Ok(())
+        }
+        Command::Verify { block_range } => verify(&provider, &pool, &chain, block_range).await,
+        Command::Status => status(&provider, &pool, settings, &chain).await,
+    }
+}
+
+/// Index blocks `from..=to` (`from` defaulting to the block after the cursor),
+/// then stop. The part of the range up to the cursor is ingested again and
+/// everything from `from` on reprocessed from the stored logs; the part after
+/// it is indexed as `run` would.
+async fn backfill(
+    provider: &TempoProvider,
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+    handlers: &Handlers,
+    from: Option<u64>,
+    to: u64,
+) -> Result<()> {
+    let cursor = storage::repos::get_last_ingested_block(pool, chain.id).await?;
+    let from = from.map_or_else(|| first_block(settings, cursor), |b| b as i64);
+    let to = to as i64;
+    if to < from {
+        eyre::bail!("--to {to} is before --from {from}");
+    }
+    if cursor > 0 && from > cursor + 1 {
+        eyre::bail!(
+            "{} is ingested up to block {cursor}; backfilling from block {from} would skip \
+             blocks {}..={}",
+            settings.network,
+            cursor + 1,
+            from - 1
+        );
+    }
+
+    tracing::info!(from, to, "Backfilling");
+    if cursor > 0 && from <= cursor {
+        let refetch_to = to.min(cursor);
+        let raw_logs_from = storage::repos::get_raw_logs_from_block(pool, chain.id).await?;
+        if raw_logs_from > refetch_to + 1 {
+            eyre::bail!(
+                "Blocks {}..{raw_logs_from} were indexed before raw logs were kept, so \
+                 they cannot be reprocessed; backfill through block {} at least",
+                refetch_to + 1,
+                raw_logs_from - 1
+            );
+        }
+        let logs = ingest::reingest(provider, pool, settings, chain, from, refetch_to).await?;
+        tracing::info!(from, to = refetch_to, logs, "Re-ingested");
+        reprocess(pool, settings, chain, handlers, from).await?;
+    }
+    index_until(
+        provider,
+        pool,
+        settings,
+        chain,
+        handlers,
+        cursor.max(from - 1),
+        to,
+    )
+    .await?;
+    tracing::info!(from, to, "Backfill complete");
+    Ok(())
+}
+
+/// Re-fetch the logs of `address` in `from..=to`, replace the stored ones and
+/// rebuild the token's rows from them. Serializes with the indexer's batches
+/// instead of stopping it. Only the built-in tables are rebuilt.
+async fn reindex_token(
+    provider: &TempoProvider,
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+    address: &TokenAddress,
+    from: Option<u64>,
+    to: Option<u64>,
+) -> Result<()> {
+    let token = storage::repos::get_token(pool, chain.id, address)
+        .await?
+        .ok_or_else(|| eyre::eyre!("Token {address} is not registered on {}", settings.network))?;
+    let cursor = storage::repos::get_last_indexed_block(pool, chain.id).await?;
+    let from = from.map_or(token.created_at_block, |b| b as i64);
+    let to = to.map_or(cursor, |b| b as i64);
+    if to > cursor {
+        eyre::bail!("--to {to} is past the cursor ({cursor}); blocks after it are left to `run`");
+    }
+    if to < from {
+        eyre::bail!("--to {to} is before --from {from}");
+    }
+
+    tracing::info!(token = %address, from, to, "Re-indexing token");
+    let contract: Address = address.as_str().parse()?;
+    let batch = settings.indexer.batch_size as i64;
+    let mut raw_logs = Vec::new();
+    let mut transfers = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = (start + batch - 1).min(to);
+        let filter = Filter::new()
+            .address(contract)
+            .from_block(start as u64)
+            .to_block(end as u64);
+        let mut logs = metrics::rpc("eth_getLogs", provider.get_logs(&filter)).await?;
+        ingest::stamp_logs(provider, &mut logs, &[]).await?;
+        raw_logs.extend(logs.iter().filter_map(ingest::raw_log));
+        for log in logs
+            .iter()
+            .filter(|log| log.topics().first() == Some(&TIP20::Transfer::SIGNATURE_HASH))
+        {
+            if let Some(event) = decoder::decode_tip20_log(log) {
+                transfers.push(transfer_row(&event, block_time(log)?));
+            }
+        }
+        start = end + 1;
+    }
+
+    let started = Instant::now();
+    if !storage::repos::reindex_token(
+        pool,
+        chain.id,
+        address.as_str(),
+        from,
+        to,
+        &raw_logs,
+        &transfers,
+    )
+    .await?
+    {
+        eyre::bail!(
+            "The cursor moved back before block {to} (a reorg?) while re-indexing; run it again"
+        );
+    }
+    metrics::record_transaction("reindex_token", started);
+    tracing::info!(token = %address, transfers = transfers.len(), "Re-index complete");
+    Ok(())
+}
+
+/// Fail if `handlers` has handlers besides the built-in ones: `reindex-token`
+/// rebuilds only the built-in tables, and theirs would drift from the transfers.
+fn only_builtin_handlers(handlers: &Handlers) -> Result<()> {
+    let custom: Vec<&str> = handlers.custom().map(|handler| handler.name()).collect();
+    if !custom.is_empty() {
+        eyre::bail!(
+            "`reindex-token` rebuilds only the built-in tables and would leave those of {} \
+             behind; `backfill --from --to` over the range fetches every token's logs again \
+             and runs all handlers on them",
+            custom.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Re-derive transfers, balances, supply and stats from block `from` on out of
+/// the stored logs, without the RPC.
+async fn reprocess(
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+    handlers: &Handlers,
+    from: i64,
+) -> Result<()> {
+    let earliest = storage::repos::get_raw_logs_from_block(pool, chain.id)
+        .await?
+        .max(1);
+    if from < earliest {
+        eyre::bail!(
+            "Blocks before {earliest} were indexed before raw logs were kept; \
+             use `rollback --to-block` to re-index them from the RPC"
+        );
+    }
+    let cursor = storage::repos::get_last_indexed_block(pool, chain.id).await?;
+    if from > cursor + 1 {
+        eyre::bail!(
+            "--from {from} is past the block after the cursor ({})",
+            cursor + 1
+        );
+    }
+
+    tracing::info!(from, "Reprocessing");
+    process::roll_back(pool, chain.id, handlers, from - 1, Rollback::Derived).await?;
+    let processed = process::process_pending(pool, settings, chain, handlers).await?;
+    tracing::info!(from, to = processed, "Reprocess complete");
+    Ok(())
+}
+
+/// Index from after `last_block` through `to`, failing if the chain head is behind `to`.
+async fn index_until(
+    provider: &TempoProvider,
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+    handlers: &Handlers,
+    mut last_block: i64,
+    to: i64,
+) -> Result<()> {
+    while last_block < to {
+        if !index_next_batch(
+            provider,
+            pool,
+            &mut last_block,
+            settings,
+            chain,
+            handlers,
+            Some(to),
+        )
+        .await?
+        {
+            eyre::bail!("Chain head is behind block {to} (indexed up to {last_block})");
+        }
+    }
+    Ok(())
+}
+
+/// Delete ingested and derived data after `block`.
+async fn rollback(
+    pool: &sqlx::PgPool,
+    chain: &Chain,
+    handlers: &Handlers,
+    block: i64,
+) -> Result<()> {
+    let cursor = storage::repos::get_last_ingested_block(pool, chain.id).await?;
+    if block >= cursor {
+        tracing::info!(cursor, "Nothing indexed after block {block}");
+        return Ok(());
+    }
+    process::roll_back(pool, chain.id, handlers, block, Rollback::Blocks).await?;
+    tracing::info!(block, blocks = cursor - block, "Rolled back");
+    Ok(())
+}
+
+/// Check the stored hashes in `range` against the chain. Only the blocks
+/// ingestion recorded are checked: the last of each batch, and those whose
+/// logs had to be stamped from their header.
+async fn verify(
+    provider: &TempoProvider,
+    pool: &sqlx::PgPool,
+    chain: &Chain,
+    range: BlockRange,
+) -> Result<()> {
+    let blocks =
+        storage::repos::get_indexed_blocks(pool, chain.id, range.from as i64, range.to as i64)
+            .await?;
+
+    let mut first_mismatch = None;
+    for stored in &blocks {
+        let block = metrics::rpc(
+            "eth_getBlockByNumber",
+            provider.get_block_by_number(alloy::eips::BlockNumberOrTag::Number(
+                stored.block_number as u64,
+            )),
+        )
+        .await?;
+        let chain_hash = block.map(|b| format!("{:#x}", b.header.hash()));
+        if chain_hash.as_deref() != Some(stored.block_hash.as_str()) {
+            tracing::warn!(
+                block = stored.block_number,
+                stored = %stored.block_hash,
+                chain = chain_hash.as_deref().unwrap_or("(missing)"),
+                "Block hash mismatch"
+            );
+            first_mismatch.get_or_insert(stored.block_number);
+        }
+    }
+
+    println!(
+        "Checked {} of the {} blocks in {}..{} (hashes are stored for batch ends and \
+         blocks whose logs lacked a timestamp)",
+        blocks.len(),
+        range.to - range.from + 1,
+        range.from,
+        range.to
+    );
+    match first_mismatch {
+        Some(block) => eyre::bail!(
+            "Block {block} no longer matches the chain; `rollback --to-block {}` and re-index",
+            block - 1
+        ),
+        None => {
+            println!("All match");
+            Ok(())
+        }
+    }
+}
+
+/// Print indexing progress for the active network.
+async fn status(
+    provider: &TempoProvider,
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+) -> Result<()> {
+    let head = metrics::rpc("eth_blockNumber", provider.get_block_number()).await? as i64;
+    let status = storage::repos::get_indexer_status(pool, chain.id).await?;
+    let ingested = storage::repos::get_last_ingested_block(pool, chain.id).await?;
+    let raw_logs_from = storage::repos::get_raw_logs_from_block(pool, chain.id).await?;
+    let tokens = storage::repos::get_token_count(pool, chain.id).await?;
+
+    println!("network:      {} (chain {})", settings.network, chain.id);
+    println!("cursor:       {}", status.last_indexed_block);
+    println!("ingested:     {ingested}");
+    println!("raw logs:     from block {}", raw_logs_from.max(1));
+    println!("chain head:   {head}");
+    println!(
+        "lag:          {} blocks",
+        (head - status.last_indexed_block).max(0)
+    );
+    println!("tokens:       {tokens}");
+    match status.last_reorg {
+        Some(reorg) => println!(
+            "last reorg:   fork block {}, depth {}, at {}",
+            reorg.fork_block, reorg.depth, reorg.detected_at
+        ),
+        None => println!("last reorg:   none"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::tests::recorders;
+
+    #[test]
+    fn reindex_token_refuses_handlers_it_cannot_rebuild() {
+        assert!(only_builtin_handlers(&Handlers::builtin()).is_ok());
+
+        let (handlers, calls) = recorders(Handlers::builtin(), false);
+        let err = only_builtin_handlers(&handlers).unwrap_err().to_string();
+        assert!(err.contains("those of first, second behind"), "{err}");
+        assert!(calls.lock().unwrap().is_empty());
+    }
+}
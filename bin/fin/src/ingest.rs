@@ -0,0 +1,398 @@
+//! Ingestion stage — fetch every log emitted by a TIP-20 token or the factory
+//! into the `logs` table, detecting reorgs against `indexed_blocks`.
+//!
+//! Nothing here decodes events; [`crate::process`] derives the other tables
+//! from the stored logs.
+
+use alloy::{
+    consensus::BlockHeader,
+    eips::BlockNumberOrTag,
+    network::primitives::HeaderResponse,
+    primitives::B256,
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    transports::{RpcError, TransportErrorKind},
+};
+use eyre::Result;
+use futures::{StreamExt, TryStreamExt};
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Instant,
+};
+use tempulse_core::Settings;
+use tempulse_handlers::Handlers;
+use tempulse_storage::{self as storage, models::*};
+use tempulse_tempo::{TIP20, provider};
+use tracing::Instrument;
+
+use crate::{
+    Chain, metrics,
+    process::{self, Rollback},
+};
+
+/// Ingest the next batch of blocks, going no further than `until` if given.
+/// Returns `Ok(true)` if work was done, `Ok(false)` if caught up.
+///
+/// 1. **Reorg detection** — checks parent hash of the first block in the batch against
+///    `indexed_blocks`. If mismatch, walks backward to find the fork point, rolls back
+///    ingested and derived data, and re-ingests from there.
+/// 2. **Fetch** — every TIP-20 event and every factory log of the batch, in a
+///    shorter batch if the node rejects the response as too large, plus the
+///    header of the last block and of every block whose logs came without a
+///    timestamp.
+/// 3. **Atomic writes** — the logs, the block records and the ingestion cursor are
+///    written in a single database transaction.
+#[tracing::instrument(name = "ingest_batch", skip_all, fields(from = tracing::field::Empty, to = tracing::field::Empty))]
+pub async fn ingest_next_batch(
+    provider: &provider::TempoProvider,
+    pool: &sqlx::PgPool,
+    last_block: &mut i64,
+    settings: &Settings,
+    chain: &Chain,
+    handlers: &Handlers,
+    until: Option<i64>,
+) -> Result<bool> {
+    let chain_head = metrics::rpc("eth_blockNumber", provider.get_block_number()).await?;
+    let chain_head = chain_head as i64;
+    metrics::record_progress(chain_head, *last_block);
+    storage::repos::set_chain_head(pool, chain.id, chain_head).await?;
+
+    let ceiling = until.map_or(chain_head, |until| until.min(chain_head));
+    if *last_block >= ceiling {
+        return Ok(false); // Caught up
+    }
+
+    let from = *last_block + 1;
+    let mut to = std::cmp::min(from + settings.indexer.batch_size as i64 - 1, ceiling);
+    let started = Instant::now();
+    let span = tracing::Span::current();
+    span.record("from", from);
+    span.record("to", to);
+
+    tracing::info!(from = from, to = to, head = chain_head, "Ingesting batch");
+
+    // ── Reorg Detection ────────────────────────────────────────────────
+    // Check if the parent hash of block `from` matches what we stored for block `from - 1`.
+    if from > 1
+        && let Some(stored_hash) = storage::repos::get_block_hash(pool, chain.id, from - 1).await?
+    {
+        // Fetch the actual block from the chain to compare parent hashes
+        let block = metrics::rpc(
+            "eth_getBlockByNumber",
+            provider.get_block_by_number(alloy::eips::BlockNumberOrTag::Number(from as u64)),
+        )
+        .await?
+        .ok_or_else(|| eyre::eyre!("Block {} not found on chain", from))?;
+        let parent_hash = format!("{:#x}", block.header.parent_hash());
+        if parent_hash != stored_hash {
+            tracing::warn!(
+                block = from,
+                expected = %stored_hash,
+                got = %parent_hash,
+                "Reorg detected! Rolling back…"
+            );
+
+            // Walk backward to find the fork point
+            let mut fork_block = from - 2;
+            while fork_block > 0 {
+                if let Some(stored) =
+                    storage::repos::get_block_hash(pool, chain.id, fork_block).await?
+                {
+                    let chain_block = metrics::rpc(
+                        "eth_getBlockByNumber",
+                        provider.get_block_by_number(alloy::eips::BlockNumberOrTag::Number(
+                            fork_block as u64,
+                        )),
+                    )
+                    .await?
+                    .ok_or_else(|| {
+                        eyre::eyre!(
+                            "Block {} not found on chain during reorg detection",
+                            fork_block
+                        )
+                    })?;
+                    let chain_hash = format!("{:#x}", chain_block.header.hash());
+                    if chain_hash == stored {
+                        break; // Found the fork point
+                    }
+                } else {
+                    break; // No stored hash, can't go further back
+                }
+                fork_block -= 1;
+            }
+
+            tracing::warn!(
+                fork_block = fork_block,
+                "Fork point found, rolling back to block"
+            );
+            let rollback_started = Instant::now();
+            process::roll_back(pool, chain.id, handlers, fork_block, Rollback::Reorg).await?;
+            metrics::record_transaction("reorg_rollback", rollback_started);
+            metrics::record_reorg(*last_block - fork_block);
+            *last_block = fork_block;
+            return Ok(true); // Signal that work was done (rollback), re-ingest next iteration
+        }
+    }
+
+    // ── Fetch the logs, keep those of TIP-20 tokens and the factory ──
+    let (fetched, logs, blocks) = fetch_batch(provider, chain, from, &mut to)
+        .instrument(tracing::info_span!("fetch"))
+        .await?;
+    span.record("to", to);
+    tracing::info!(fetched, kept = logs.len(), to, "Fetched logs");
+
+    // ── Atomic write: logs, block record and cursor ─────────────────
+    let tx_started = Instant::now();
+    let tx = async {
+        let mut tx = pool.begin().await?;
+        storage::repos::lock_batches(&mut tx, chain.id).await?;
+
+        storage::repos::insert_logs(&mut tx, chain.id, &logs).await?;
+
+        for block in &blocks {
+            storage::repos::insert_block(&mut *tx, chain.id, block).await?;
+        }
+
+        storage::repos::set_last_ingested_block(&mut *tx, chain.id, to).await?;
+        Ok::<_, eyre::Report>(tx)
+    }
+    .instrument(tracing::info_span!("persist"))
+    .await?;
+
+    tx.commit()
+        .instrument(tracing::info_span!("commit"))
+        .await?;
+    metrics::record_transaction("ingest", tx_started);
+
+    *last_block = to;
+    metrics::record_progress(chain_head, to);
+    metrics::record_batch(started, logs.len());
+
+    tracing::info!(block = to, logs = logs.len(), "Batch ingested");
+
+    Ok(true)
+}
+
+/// Fetch blocks `from..=to` again, in batches of the configured size, and
+/// replace their stored logs and block records in one transaction. The cursors
+/// stay where they are; reprocess the range to derive from the new logs.
+/// Returns how many logs were stored.
+pub async fn reingest(
+    provider: &provider::TempoProvider,
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+    from: i64,
+    to: i64,
+) -> Result<usize> {
+    let mut logs = Vec::new();
+    let mut blocks = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let mut end = (start + settings.indexer.batch_size as i64 - 1).min(to);
+        let (_, batch_logs, batch_blocks) = fetch_batch(provider, chain, start, &mut end).await?;
+        tracing::info!(
+            from = start,
+            to = end,
+            logs = batch_logs.len(),
+            "Fetched logs"
+        );
+        logs.extend(batch_logs);
+        blocks.extend(batch_blocks);
+        start = end + 1;
+    }
+
+    let started = Instant::now();
+    let mut tx = pool.begin().await?;
+    storage::repos::lock_batches(&mut tx, chain.id).await?;
+    storage::repos::replace_logs(&mut tx, chain.id, from, to, &logs, &blocks).await?;
+    tx.commit().await?;
+    metrics::record_transaction("reingest", started);
+    Ok(logs.len())
+}
+
+/// Fetch the logs of blocks `from..=to` (moving `to` back if the node rejects
+/// the range as too large) and keep those of TIP-20 tokens and the factory:
+/// every TIP-20 event, not only the ones the processor decodes, so event types
+/// it learns later are already stored. Returns how many logs were fetched, the
+/// kept ones, and the headers to record: the last block's (for reorg
+/// detection) and those of blocks whose logs came without a timestamp.
+async fn fetch_batch(
+    provider: &provider::TempoProvider,
+    chain: &Chain,
+    from: i64,
+    to: &mut i64,
+) -> Result<(usize, Vec<RawLog>, Vec<IndexedBlock>)> {
+    let logs = fetch_logs(provider, chain, from, to).await?;
+    let fetched = logs.len();
+    let mut logs: Vec<Log> = logs
+        .into_iter()
+        .filter(|log| {
+            let address = log.address();
+            chain.is_tip20(&address) || address == chain.factory
+        })
+        .collect();
+    let blocks = stamp_logs(provider, &mut logs, &[*to]).await?;
+    Ok((fetched, logs.iter().filter_map(raw_log).collect(), blocks))
+}
+
+/// Headers fetched at once while filling in block timestamps.
+const HEADER_CONCURRENCY: usize = 8;
+
+/// Fetch the logs of blocks `from..=to` that may come from a TIP-20 token or
+/// the factory: every log with a TIP-20 event signature, and every factory log.
+/// While the node rejects the response as too large, the range is halved and
+/// `to` moved back to its new end.
+async fn fetch_logs(
+    provider: &provider::TempoProvider,
+    chain: &Chain,
+    from: i64,
+    to: &mut i64,
+) -> Result<Vec<Log>> {
+    let tip20_events: Vec<B256> = TIP20::TIP20Events::SELECTORS
+        .iter()
+        .copied()
+        .map(B256::from)
+        .collect();
+    loop {
+        let range = |filter: Filter| filter.from_block(from as u64).to_block(*to as u64);
+        let fetched = async {
+            let mut logs = metrics::rpc(
+                "eth_getLogs",
+                provider.get_logs(&range(Filter::new().event_signature(tip20_events.clone()))),
+            )
+            .await?;
+            logs.extend(
+                metrics::rpc(
+                    "eth_getLogs",
+                    provider.get_logs(&range(Filter::new().address(chain.factory))),
+                )
+                .await?,
+            );
+            Ok(logs)
+        }
+        .await;
+
+        match fetched {
+            Ok(mut logs) => {
+                logs.sort_by_key(|log| (log.block_number, log.log_index));
+                logs.dedup_by_key(|log| (log.block_number, log.log_index));
+                return Ok(logs);
+            }
+            Err(e) if *to > from && is_too_large(&e) => {
+                let shorter = from + (*to - from) / 2;
+                tracing::warn!(from, to = *to, shorter, error = %e, "Log response too large, halving the batch");
+                *to = shorter;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Whether the node refused an `eth_getLogs` call for the size of its result
+/// (too many logs or too wide a block range) rather than failing outright.
+fn is_too_large(e: &RpcError<TransportErrorKind>) -> bool {
+    match e {
+        RpcError::ErrorResp(payload) => too_large_response(payload.code, &payload.message),
+        RpcError::Transport(TransportErrorKind::HttpError(http)) => http.status == 413,
+        _ => false,
+    }
+}
+
+/// Whether a JSON-RPC error response refuses a result for its size. Nodes
+/// disagree on the code, so the message is checked too.
+fn too_large_response(code: i64, message: &str) -> bool {
+    let message = message.to_lowercase();
+    code == -32005
+        || ["too large", "too many", "more than", "exceed"]
+            .iter()
+            .any(|hint| message.contains(hint))
+}
+
+/// Fill in the block timestamp of the `logs` the node returned without one,
+/// from their blocks' headers. Fetches those headers plus the ones of blocks
+/// `also`, and returns them all to be recorded.
+pub async fn stamp_logs(
+    provider: &provider::TempoProvider,
+    logs: &mut [Log],
+    also: &[i64],
+) -> Result<Vec<IndexedBlock>> {
+    let numbers: BTreeSet<i64> = logs
+        .iter()
+        .filter(|log| log.block_timestamp.is_none())
+        .filter_map(|log| log.block_number.map(|n| n as i64))
+        .chain(also.iter().copied())
+        .collect();
+
+    let blocks: Vec<IndexedBlock> = futures::stream::iter(numbers)
+        .map(|number| async move {
+            let block = metrics::rpc(
+                "eth_getBlockByNumber",
+                provider.get_block_by_number(BlockNumberOrTag::Number(number as u64)),
+            )
+            .await?
+            .ok_or_else(|| eyre::eyre!("Block {number} not found on chain"))?;
+            Ok::<_, eyre::Report>(IndexedBlock {
+                block_number: number,
+                block_hash: format!("{:#x}", block.header.hash()),
+                parent_hash: format!("{:#x}", block.header.parent_hash()),
+                timestamp: block.header.timestamp() as i64,
+            })
+        })
+        .buffered(HEADER_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let timestamps: HashMap<i64, i64> = blocks
+        .iter()
+        .map(|b| (b.block_number, b.timestamp))
+        .collect();
+    for log in logs.iter_mut().filter(|log| log.block_timestamp.is_none()) {
+        log.block_timestamp = log
+            .block_number
+            .and_then(|n| timestamps.get(&(n as i64)))
+            .map(|&ts| ts as u64);
+    }
+    Ok(blocks)
+}
+
+/// A fetched log in storage form; `None` for pending logs, which have no position yet.
+pub fn raw_log(log: &Log) -> Option<RawLog> {
+    Some(RawLog {
+        block_number: log.block_number? as i64,
+        log_index: log.log_index? as i32,
+        transaction_hash: log
+            .transaction_hash
+            .map(|h| format!("{h:#x}"))
+            .unwrap_or_default(),
+        address: format!("{:#x}", log.address()),
+        topics: log.topics().iter().map(|t| format!("{t:#x}")).collect(),
+        data: format!("{}", log.data().data),
+        block_timestamp: log.block_timestamp.map(|ts| ts as i64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn too_large_responses_are_recognized() {
+        assert!(too_large_response(-32005, "limit exceeded"));
+        assert!(too_large_response(
+            -32602,
+            "query returned more than 10000 results"
+        ));
+        assert!(too_large_response(-32000, "Log response size exceeded"));
+        assert!(too_large_response(-32000, "block range too large"));
+    }
+
+    #[test]
+    fn other_errors_are_not() {
+        assert!(!too_large_response(-32601, "method not found"));
+        assert!(!too_large_response(-32000, "header not found"));
+        assert!(!is_too_large(&RpcError::Transport(
+            TransportErrorKind::BackendGone
+        )));
+    }
+}
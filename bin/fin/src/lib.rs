@@ -0,0 +1,390 @@
+//! Tempulse Indexer — crawls TIP-20 stablecoin events from the Tempo blockchain.
+//!
+//! Flow:
+//! 1. Connect to Tempo RPC & PostgreSQL, register the network (`NETWORK`) by chain id
+//! 2. Discover existing tokens via TIP20Factory TokenCreated events
+//! 3. Ingest: poll blocks in batches and store every TIP-20 and factory log in `logs`
+//! 4. Process: decode the stored Transfer/Mint/Burn events, persist them atomically
+//!    and run the event handlers (account balances, supplies, hourly stats) on them
+//!
+//! That is `fin run`, the default; the other subcommands (`fin --help`) backfill,
+//! roll back, re-index, re-process, verify and report on the same network.
+//!
+//! Production features:
+//! - Reorg detection via parent hash comparison against indexed_blocks
+//! - Raw logs as the source of truth, so derived tables can be rebuilt without the RPC
+//! - Atomic writes per batch (logs + blocks + cursor, then transfers + balances + cursor)
+//! - total_supply tracked on mint/burn
+//! - hourly_stats aggregated in real-time
+//! - Optional FX rate sync (`FX_SOURCE`) for multi-currency normalization
+//! - Hourly holder distribution snapshots
+//! - Entity-to-entity flows over the groups in `ENTITIES_FILE`
+//! - Prometheus metrics on `METRICS_PORT`
+//! - Optional OTLP span export (`OTLP_ENDPOINT`) per batch stage, RPC call and query
+//!
+//! Balances, supplies and hourly stats are kept by the event handlers of
+//! `tempulse-handlers`. The `fin` binary runs the built-in ones; to add your
+//! own, depend on this crate and call [`run_cli`] with them registered (see
+//! `examples/whale_alerts.rs`).
+
+mod cli;
+mod commands;
+mod ingest;
+mod metrics;
+mod process;
+
+use alloy::{primitives::Address, providers::Provider, rpc::types::Filter, sol_types::SolEvent};
+use clap::Parser;
+use cli::{Cli, Command};
+use eyre::Result;
+use std::time::Duration;
+use tempulse_core::{Settings, config::ChainSettings, telemetry};
+use tempulse_handlers::Handlers;
+use tempulse_oracle::PriceSource;
+use tempulse_storage::{self as storage, models::*};
+use tempulse_tempo::{TIP20Factory, decoder, provider};
+use tempulse_webhooks::{Dispatcher, DispatcherConfig};
+
+/// The active network profile in the form the log filters need, plus the
+/// chain id every row is stored under.
+struct Chain {
+    id: i64,
+    /// TIP20Factory precompile address.
+    factory: Address,
+    tip20_prefix: [u8; 12],
+}
+
+impl Chain {
+    /// Resolve the profile against the RPC endpoint, which must report the
+    /// profile's `chain_id` if one is configured.
+    async fn connect(settings: &ChainSettings, provider: &provider::TempoProvider) -> Result<Self> {
+        let reported = metrics::rpc("eth_chainId", provider.get_chain_id()).await?;
+        if let Some(expected) = settings.chain_id
+            && expected != reported
+        {
+            eyre::bail!(
+                "RPC endpoint {} serves chain {reported}, but the network profile expects chain {expected}",
+                settings.rpc_url
+            );
+        }
+        Ok(Self {
+            id: i64::try_from(reported)?,
+            factory: settings.factory_address.as_str().parse()?,
+            tip20_prefix: settings.tip20_prefix_bytes()?,
+        })
+    }
+
+    /// Check if an address is a valid TIP-20 token by its prefix.
+    /// This is an O(1) check that requires zero memory — no need to load token lists.
+    #[inline]
+    fn is_tip20(&self, addr: &Address) -> bool {
+        addr.as_slice()[..12] == self.tip20_prefix
+    }
+}
+
+/// Parse the command line and run it, with `handlers` processing every batch.
+pub async fn run_cli(handlers: Handlers) -> Result<()> {
+    let cli = Cli::parse();
+    let settings = Settings::load(cli.config.as_deref())?;
+    if cli.print_config {
+        print!("{}", settings.to_redacted_toml()?);
+        return Ok(());
+    }
+    let _telemetry = telemetry::init(
+        "tempulse-indexer",
+        settings.telemetry.otlp_endpoint.as_deref(),
+    )?;
+
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run(&settings, &handlers).await,
+        command => commands::execute(command, &settings, &handlers).await,
+    }
+}
+
+/// Connect to the database (applying migrations) and the RPC endpoint, and
+/// register the active network.
+async fn connect(settings: &Settings) -> Result<(sqlx::PgPool, provider::TempoProvider, Chain)> {
+    tracing::info!(
+        network = %settings.network,
+        rpc = %settings.chain().rpc_url,
+        "Starting Tempulse Indexer"
+    );
+
+    // Connect to the database
+    let pool = storage::connect(&settings.database).await?;
+    tracing::info!("Connected to database");
+
+    // Run migrations
+    sqlx::migrate!("../../migrations").run(&pool).await?;
+    tracing::info!("Database migrations applied");
+
+    // Create Tempo RPC provider
+    let provider = provider::create_provider(&settings.chain().rpc_url)?;
+    let chain = Chain::connect(settings.chain(), &provider).await?;
+    tracing::info!(chain_id = chain.id, "Connected to Tempo RPC");
+
+    storage::repos::register_network(&pool, chain.id, &settings.network).await?;
+    tracing::info!(network = %settings.network, chain_id = chain.id, "Network registered");
+
+    Ok((pool, provider, chain))
+}
+
+/// Take the network's writer lock, so two processes never index it at once.
+async fn lock_network(
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+) -> Result<sqlx::PgConnection> {
+    storage::repos::try_lock_network(pool, chain.id)
+        .await?
+        .ok_or_else(|| {
+            eyre::eyre!(
+                "Another indexer process is writing {} (chain {}); stop it first",
+                settings.network,
+                chain.id
+            )
+        })
+}
+
+/// First block to index when the cursor is at `cursor`.
+fn first_block(settings: &Settings, cursor: i64) -> i64 {
+    if cursor == 0 && settings.indexer.start_block > 0 {
+        settings.indexer.start_block as i64
+    } else {
+        cursor + 1
+    }
+}
+
+/// `fin run`: index new blocks, with the background tasks, until interrupted.
+async fn run(settings: &Settings, handlers: &Handlers) -> Result<()> {
+    // ── Initialisation ──────────────────────────────────────────────────
+    let metrics_handle = metrics::install()?;
+    let (pool, provider, chain) = connect(settings).await?;
+    let _lock = lock_network(&pool, settings, &chain).await?;
+
+    if settings.indexer.metrics_port != 0 {
+        tokio::spawn(metrics::serve(
+            settings.indexer.metrics_port,
+            metrics_handle,
+            pool.clone(),
+        ));
+    }
+
+    // ── Token Discovery ─────────────────────────────────────────────────
+    // Fetch all TokenCreated events from the Factory to seed the token registry.
+    // This only scans Factory events (one contract), so it's lightweight.
+    tracing::info!("Discovering TIP-20 tokens from Factory…");
+    discover_tokens(&provider, &pool, settings, &chain).await?;
+
+    let token_count = storage::repos::get_token_count(&pool, chain.id).await?;
+    tracing::info!(count = token_count, "Tracking tokens");
+
+    // ── Address Labels ──────────────────────────────────────────────────
+    if let Some(path) = &settings.indexer.labels_file {
+        let labels = tempulse_core::labels::load_file(path)?;
+        storage::repos::upsert_address_labels(&pool, &labels, "import").await?;
+        tracing::info!(count = labels.len(), path = %path, "Address labels imported");
+    }
+
+    // ── Entities ────────────────────────────────────────────────────────
+    if let Some(path) = &settings.indexer.entities_file {
+        let entities = tempulse_core::entities::load_file(path)?;
+        if storage::repos::replace_entities(&pool, &entities).await? {
+            tracing::info!(count = entities.len(), path = %path, "Entities changed — entity flows rebuilt");
+        }
+    }
+
+    // ── FX Rate Sync ────────────────────────────────────────────────────
+    // Runs in the background so a slow or failing price source never blocks indexing.
+    if let Some(spec) = &settings.indexer.fx_source {
+        let source = tempulse_oracle::from_spec(spec)?;
+        tracing::info!(source = source.name(), "FX rate sync enabled");
+        tokio::spawn(run_fx_sync(
+            source,
+            pool.clone(),
+            settings.indexer.fx_sync_interval(),
+        ));
+    }
+
+    // ── Holder Distribution Snapshots ───────────────────────────────────
+    tokio::spawn(run_holder_snapshots(
+        pool.clone(),
+        chain.id,
+        settings.indexer.holder_snapshot_interval(),
+    ));
+
+    // ── Webhook Dispatch ────────────────────────────────────────────────
+    // Delivers the webhooks queued with each committed batch.
+    if settings.indexer.webhooks_enabled {
+        let dispatcher = Dispatcher::new(pool.clone(), chain.id, DispatcherConfig::default())?;
+        tokio::spawn(dispatcher.run());
+    }
+
+    // ── Main Indexing Loop ──────────────────────────────────────────────
+    let cursor = storage::repos::get_last_ingested_block(&pool, chain.id).await?;
+    let mut last_block = first_block(settings, cursor) - 1;
+
+    tracing::info!(from_block = last_block + 1, "Starting indexing loop");
+
+    let shutdown = tokio::signal::ctrl_c();
+    tokio::pin!(shutdown);
+
+    loop {
+        // Check for shutdown
+        tokio::select! {
+            _ = &mut shutdown => {
+                tracing::info!("Shutting down gracefully…");
+                break;
+            }
+            result = index_next_batch(&provider, &pool, &mut last_block, settings, &chain, handlers, None) => {
+                match result {
+                    Ok(indexed) => {
+                        if !indexed {
+                            // We're caught up — wait before polling again
+                            tokio::time::sleep(settings.indexer.poll_interval()).await;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            error = %e,
+                            retry_in_secs = settings.indexer.retry_interval_secs,
+                            "Indexing error, retrying…"
+                        );
+                        tokio::time::sleep(settings.indexer.retry_interval()).await;
+                    }
+                }
+            }
+        }
+    }
+
+    tracing::info!("Indexer stopped.");
+    Ok(())
+}
+
+/// Periodically pull FX rates from `source` and upsert them into `fx_rates`.
+async fn run_fx_sync(source: Box<dyn PriceSource>, pool: sqlx::PgPool, interval: Duration) {
+    loop {
+        match sync_fx_rates(source.as_ref(), &pool).await {
+            Ok(count) => tracing::info!(count = count, "FX rates synced"),
+            Err(e) => tracing::error!(error = %e, "FX rate sync failed"),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Fetch rates once from `source` and persist them. Returns the number of rates stored.
+async fn sync_fx_rates(source: &dyn PriceSource, pool: &sqlx::PgPool) -> Result<usize> {
+    let rates: Vec<FxRate> = source
+        .fetch_rates()
+        .await?
+        .into_iter()
+        .map(|q| FxRate {
+            base_currency: q.base,
+            quote_currency: q.quote,
+            day: q.day,
+            rate: q.rate,
+            source: source.name().to_string(),
+        })
+        .collect();
+
+    storage::repos::upsert_fx_rates(pool, &rates).await?;
+    Ok(rates.len())
+}
+
+/// Periodically refresh today's holder distribution snapshot for every token.
+async fn run_holder_snapshots(pool: sqlx::PgPool, chain_id: i64, interval: Duration) {
+    loop {
+        match snapshot_holders(&pool, chain_id).await {
+            Ok(count) => tracing::info!(tokens = count, "Holder snapshots refreshed"),
+            Err(e) => tracing::error!(error = %e, "Holder snapshot failed"),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Snapshot every tracked token once. Returns the number of tokens snapshotted.
+async fn snapshot_holders(pool: &sqlx::PgPool, chain_id: i64) -> Result<usize> {
+    let today = chrono::Utc::now().date_naive();
+    let tokens = storage::repos::get_all_tokens(pool, chain_id, i64::MAX).await?;
+    for token in &tokens {
+        storage::repos::snapshot_holder_distribution(pool, chain_id, &token.address, today).await?;
+    }
+    Ok(tokens.len())
+}
+
+/// Discover tokens by querying TIP20Factory TokenCreated events.
+///
+/// This is safe at scale because it only queries ONE contract address
+/// (the Factory), not all tokens.
+async fn discover_tokens(
+    provider: &provider::TempoProvider,
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+) -> Result<()> {
+    let chain_head = metrics::rpc("eth_blockNumber", provider.get_block_number()).await?;
+
+    // Scan from genesis (or start_block) to chain head for factory events
+    let mut from = settings.indexer.start_block;
+    let batch = 10_000u64;
+
+    while from <= chain_head {
+        let to = std::cmp::min(from + batch - 1, chain_head);
+
+        let filter = Filter::new()
+            .address(chain.factory)
+            .event_signature(TIP20Factory::TokenCreated::SIGNATURE_HASH)
+            .from_block(from)
+            .to_block(to);
+
+        let logs = metrics::rpc("eth_getLogs", provider.get_logs(&filter)).await?;
+
+        for log in &logs {
+            if let Some(event) = decoder::decode_factory_log(log) {
+                tracing::info!(
+                    token = %event.token_address,
+                    name = %event.name,
+                    symbol = %event.symbol,
+                    currency = %event.currency,
+                    "Discovered token"
+                );
+
+                let token = Token {
+                    address: format!("{:#x}", event.token_address),
+                    name: event.name,
+                    symbol: event.symbol,
+                    decimals: 6, // TIP-20 tokens always have 6 decimals
+                    currency: event.currency,
+                    total_supply: "0".to_string(),
+                    created_at_block: event.block_number as i64,
+                    created_at_tx: event.transaction_hash,
+                };
+
+                storage::repos::insert_token(pool, chain.id, &token).await?;
+            }
+        }
+
+        from = to + 1;
+    }
+
+    Ok(())
+}
+
+/// Ingest the next batch of blocks (going no further than `until` if given),
+/// then process everything ingested so far. Returns `Ok(true)` if a batch was
+/// ingested, `Ok(false)` if caught up.
+async fn index_next_batch(
+    provider: &provider::TempoProvider,
+    pool: &sqlx::PgPool,
+    last_block: &mut i64,
+    settings: &Settings,
+    chain: &Chain,
+    handlers: &Handlers,
+    until: Option<i64>,
+) -> Result<bool> {
+    let ingested =
+        ingest::ingest_next_batch(provider, pool, last_block, settings, chain, handlers, until)
+            .await?;
+    process::process_pending(pool, settings, chain, handlers).await?;
+    Ok(ingested)
+}
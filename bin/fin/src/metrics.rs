@@ -0,0 +1,125 @@
+//! Indexer metrics and the `/metrics` endpoint that exposes them.
+
+use axum::{Router, extract::State, routing::get};
+use eyre::Result;
+use std::{future::IntoFuture, net::SocketAddr, time::Instant};
+use tempulse_core::metrics::{
+    PrometheusHandle, counter, describe_counter, describe_gauge, describe_histogram, gauge,
+    histogram,
+};
+use tracing::Instrument;
+
+/// Logs fetched per batch (transfer and factory events).
+pub const BATCH_LOGS: &str = "tempulse_indexer_batch_logs";
+
+/// Blocks rolled back per reorg.
+pub const REORG_DEPTH: &str = "tempulse_indexer_reorg_depth_blocks";
+
+/// Install the recorder and describe every indexer metric.
+pub fn install() -> Result<PrometheusHandle> {
+    let handle = tempulse_core::metrics::install(&[BATCH_LOGS, REORG_DEPTH])?;
+
+    describe_gauge!(
+        "tempulse_indexer_head_block",
+        "Latest block number reported by the RPC"
+    );
+    describe_gauge!(
+        "tempulse_indexer_last_indexed_block",
+        "Last block committed by the indexer"
+    );
+    describe_gauge!(
+        "tempulse_indexer_lag_blocks",
+        "Chain head minus the last indexed block"
+    );
+    describe_histogram!(
+        "tempulse_indexer_batch_duration_seconds",
+        "Wall time to fetch, decode and commit one batch"
+    );
+    describe_histogram!(BATCH_LOGS, "Logs fetched per batch");
+    describe_histogram!(
+        "tempulse_rpc_request_duration_seconds",
+        "RPC request latency by method"
+    );
+    describe_counter!("tempulse_rpc_errors_total", "Failed RPC requests by method");
+    describe_counter!(
+        "tempulse_indexer_reorgs_total",
+        "Chain reorganisations handled"
+    );
+    describe_histogram!(REORG_DEPTH, "Blocks rolled back per reorg");
+    describe_histogram!(
+        "tempulse_db_transaction_duration_seconds",
+        "Database transaction time by operation"
+    );
+    tempulse_core::metrics::describe_pool();
+
+    Ok(handle)
+}
+
+/// Serve `GET /metrics` on `port` until the process exits.
+pub async fn serve(port: u16, handle: PrometheusHandle, pool: sqlx::PgPool) {
+    let app = Router::new()
+        .route("/metrics", get(render))
+        .with_state((handle, pool));
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => {
+            tracing::info!("Metrics on http://{}/metrics", addr);
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!(error = %e, "Metrics server stopped");
+            }
+        }
+        Err(e) => tracing::error!(error = %e, %addr, "Failed to bind metrics server"),
+    }
+}
+
+async fn render(State((handle, pool)): State<(PrometheusHandle, sqlx::PgPool)>) -> String {
+    tempulse_core::metrics::record_pool(
+        pool.size(),
+        pool.num_idle(),
+        pool.options().get_max_connections(),
+    );
+    handle.render()
+}
+
+/// Await an RPC call in its own span, recording its latency and whether it failed.
+pub async fn rpc<F, T, E>(method: &'static str, call: F) -> Result<T, E>
+where
+    F: IntoFuture<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = call
+        .into_future()
+        .instrument(tracing::info_span!("rpc", method))
+        .await;
+    histogram!("tempulse_rpc_request_duration_seconds", "method" => method)
+        .record(started.elapsed().as_secs_f64());
+    if result.is_err() {
+        counter!("tempulse_rpc_errors_total", "method" => method).increment(1);
+    }
+    result
+}
+
+/// Record the chain head and indexer cursor.
+pub fn record_progress(head: i64, last_indexed: i64) {
+    gauge!("tempulse_indexer_head_block").set(head as f64);
+    gauge!("tempulse_indexer_last_indexed_block").set(last_indexed as f64);
+    gauge!("tempulse_indexer_lag_blocks").set((head - last_indexed).max(0) as f64);
+}
+
+/// Record one committed batch.
+pub fn record_batch(started: Instant, logs: usize) {
+    histogram!("tempulse_indexer_batch_duration_seconds").record(started.elapsed().as_secs_f64());
+    histogram!(BATCH_LOGS).record(logs as f64);
+}
+
+/// Record a reorg that rolled back `depth` blocks.
+pub fn record_reorg(depth: i64) {
+    counter!("tempulse_indexer_reorgs_total").increment(1);
+    histogram!(REORG_DEPTH).record(depth as f64);
+}
+
+/// Record how long a database transaction took, from begin to commit.
+pub fn record_transaction(operation: &'static str, started: Instant) {
+    histogram!("tempulse_db_transaction_duration_seconds", "operation" => operation)
+        .record(started.elapsed().as_secs_f64());
+}
@@ -0,0 +1,493 @@
+//! Processing stage — decode the logs [`crate::ingest`] stored, persist the
+//! transfers and hand each batch to the event handlers, which keep balances,
+//! supplies, stats and any custom tables. It reads only the database, so the
+//! derived tables can be rebuilt (`fin reprocess`) without going back to the RPC.
+
+use alloy::{
+    primitives::{B256, Bytes, LogData},
+    rpc::types::Log,
+    sol_types::SolEvent,
+};
+use chrono::{DateTime, NaiveDateTime};
+use eyre::Result;
+use std::time::Instant;
+use tempulse_core::Settings;
+use tempulse_handlers::{Batch, BatchEvent, Handlers, transfer_row};
+use tempulse_storage::{self as storage, models::*};
+use tempulse_tempo::{TIP20, TIP20Factory, decoder};
+use tracing::Instrument;
+
+use crate::{Chain, first_block, metrics};
+
+/// Event signatures the processor derives rows from; other stored logs wait
+/// for a processor that understands them.
+fn signatures() -> Vec<String> {
+    vec![
+        format!("{:#x}", TIP20Factory::TokenCreated::SIGNATURE_HASH),
+        format!("{:#x}", TIP20::Transfer::SIGNATURE_HASH),
+    ]
+}
+
+/// Process every ingested block that has not been processed yet, one batch at
+/// a time. Returns the last processed block.
+pub async fn process_pending(
+    pool: &sqlx::PgPool,
+    settings: &Settings,
+    chain: &Chain,
+    handlers: &Handlers,
+) -> Result<i64> {
+    let ingested = storage::repos::get_last_ingested_block(pool, chain.id).await?;
+    let mut processed = storage::repos::get_last_indexed_block(pool, chain.id).await?;
+    if processed == 0 {
+        processed = first_block(settings, 0) - 1;
+    }
+
+    while processed < ingested {
+        let to = std::cmp::min(processed + settings.indexer.batch_size as i64, ingested);
+        process_batch(pool, chain, handlers, processed + 1, to).await?;
+        processed = to;
+    }
+    Ok(processed)
+}
+
+/// Derive the rows of blocks `from..=to` from their stored logs and move the
+/// processing cursor to `to`, all in one database transaction. Balances,
+/// supplies, hourly stats and whatever else `handlers` keep are updated by the
+/// handlers, in that transaction.
+#[tracing::instrument(name = "process_batch", skip_all, fields(from = from, to = to))]
+async fn process_batch(
+    pool: &sqlx::PgPool,
+    chain: &Chain,
+    handlers: &Handlers,
+    from: i64,
+    to: i64,
+) -> Result<()> {
+    let logs = storage::repos::get_logs(pool, chain.id, from, to, &signatures()).await?;
+    let logs = logs.iter().map(rpc_log).collect::<Result<Vec<_>>>()?;
+    let (factory_logs, transfer_logs): (Vec<&Log>, Vec<&Log>) =
+        logs.iter().partition(|log| log.address() == chain.factory);
+
+    // ── Decode: register new tokens, collect the batch's events ─────
+    let mut events: Vec<BatchEvent> = Vec::new();
+    async {
+        for log in &factory_logs {
+            if let Some(event) = decoder::decode_factory_log(log) {
+                tracing::info!(
+                    token = %event.token_address,
+                    symbol = %event.name,
+                    "New token discovered mid-indexing"
+                );
+                let token = Token {
+                    address: format!("{:#x}", event.token_address),
+                    name: event.name,
+                    symbol: event.symbol,
+                    decimals: 6,
+                    currency: event.currency,
+                    total_supply: "0".to_string(),
+                    created_at_block: event.block_number as i64,
+                    created_at_tx: event.transaction_hash,
+                };
+                storage::repos::insert_token(pool, chain.id, &token).await?;
+            }
+        }
+
+        for log in &transfer_logs {
+            let log_address = log.address();
+
+            // ── Prefix check: skip non-TIP-20 contracts ─────────────────
+            if !chain.is_tip20(&log_address) {
+                continue;
+            }
+
+            // ── Ensure this token is registered in the DB ───────────────
+            let token_addr_str = format!("{:#x}", log_address);
+            ensure_token_registered(pool, chain.id, &token_addr_str, from).await?;
+
+            if let Some(event) = decoder::decode_tip20_log(log) {
+                events.push(BatchEvent {
+                    transfer: transfer_row(&event, block_time(log)?),
+                    event,
+                });
+            }
+        }
+        Ok::<_, eyre::Report>(())
+    }
+    .instrument(tracing::info_span!("decode", logs = logs.len()))
+    .await?;
+
+    // ── Atomic write: wrap everything in a transaction ──────────────
+    let tx_started = Instant::now();
+    let tx = async {
+        let mut tx = pool.begin().await?;
+        storage::repos::lock_batches(&mut tx, chain.id).await?;
+
+        // 1. Persist transfers (true batch insert)
+        let new_transfers: Vec<NewTransfer> = events.iter().map(|e| e.transfer.clone()).collect();
+        if !new_transfers.is_empty() {
+            tracing::info!(count = new_transfers.len(), "Persisting transfers");
+            storage::repos::insert_transfers_batch(&mut *tx, chain.id, &new_transfers).await?;
+        }
+
+        // 2. Queue webhook deliveries for them (delivered after commit)
+        if !new_transfers.is_empty() {
+            storage::repos::enqueue_webhook_deliveries(&mut tx, chain.id, from, to, None).await?;
+        }
+
+        // 3. Run the event handlers (balances, supply, hourly stats, custom)
+        let batch = Batch {
+            chain_id: chain.id,
+            from_block: from,
+            to_block: to,
+            events: &events,
+        };
+        for handler in handlers.iter() {
+            handler
+                .handle(&mut tx, &batch)
+                .instrument(tracing::info_span!("handler", handler = handler.name()))
+                .await?;
+        }
+
+        // 4. Roll the batch up into daily/monthly stats, global counters and entity flows.
+        //    These stay out of `handlers`: they aggregate the stored transfers in SQL rather
+        //    than the decoded events, and a rollback rebuilds the rollups from the transfers
+        //    left after the delete, where handlers undo their writes before it.
+        if !new_transfers.is_empty() {
+            storage::repos::apply_rollups(&mut tx, chain.id, from, to).await?;
+            storage::repos::apply_global_counters(&mut tx, chain.id, from, to).await?;
+            storage::repos::apply_entity_flows(&mut tx, chain.id, from, to).await?;
+        }
+
+        // 5. Update cursor
+        storage::repos::set_last_indexed_block(&mut *tx, chain.id, to).await?;
+
+        // 6. Announce the batch to API streams (delivered on commit)
+        storage::repos::notify_transfers_committed(
+            &mut *tx,
+            &TransfersCommitted {
+                chain_id: chain.id,
+                from_block: from,
+                to_block: to,
+            },
+        )
+        .await?;
+        Ok::<_, eyre::Report>(tx)
+    }
+    .instrument(tracing::info_span!("persist"))
+    .await?;
+
+    // ── Commit the transaction ──────────────────────────────────────
+    tx.commit()
+        .instrument(tracing::info_span!("commit"))
+        .await?;
+    metrics::record_transaction("process", tx_started);
+
+    tracing::info!(block = to, transfers = events.len(), "Batch processed");
+
+    Ok(())
+}
+
+/// What a rollback deletes besides the rows derived from the blocks after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rollback {
+    /// A detected reorg: recorded, and the blocks' logs deleted to be re-fetched.
+    Reorg,
+    /// An operator rollback: the blocks' logs are deleted to be re-fetched.
+    Blocks,
+    /// Reprocessing: the blocks' logs are kept to be processed again.
+    Derived,
+}
+
+/// Delete what was derived from the blocks after `block` (and their logs,
+/// unless `kind` is [`Rollback::Derived`]) in one transaction serialized with
+/// the batches. Every handler undoes its part first, while the transfers it
+/// came from are still stored.
+#[tracing::instrument(skip_all, fields(block = block))]
+pub async fn roll_back(
+    pool: &sqlx::PgPool,
+    chain_id: i64,
+    handlers: &Handlers,
+    block: i64,
+    kind: Rollback,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    storage::repos::lock_batches(&mut tx, chain_id).await?;
+    if kind == Rollback::Reorg {
+        // Log the reorg while the cursor still points past the fork
+        storage::repos::record_reorg(&mut tx, chain_id, block).await?;
+    }
+
+    for handler in handlers.iter() {
+        handler
+            .roll_back(&mut tx, chain_id, block)
+            .instrument(tracing::info_span!("handler", handler = handler.name()))
+            .await?;
+    }
+    storage::repos::roll_back_derived(&mut tx, chain_id, block).await?;
+    if kind != Rollback::Derived {
+        storage::repos::roll_back_raw(&mut tx, chain_id, block).await?;
+    }
+    tx.commit().await?;
+
+    tracing::warn!(
+        chain_id,
+        block,
+        ?kind,
+        "Rollback complete — deleted data after block"
+    );
+    Ok(())
+}
+
+/// A stored log in the form the decoder reads.
+fn rpc_log(raw: &RawLog) -> Result<Log> {
+    let topics = raw
+        .topics
+        .iter()
+        .map(|t| t.parse::<B256>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let data: Bytes = raw.data.parse()?;
+    Ok(Log {
+        inner: alloy::primitives::Log {
+            address: raw.address.parse()?,
+            data: LogData::new_unchecked(topics, data),
+        },
+        block_hash: None,
+        block_number: Some(raw.block_number as u64),
+        block_timestamp: raw.block_timestamp.map(|ts| ts as u64),
+        transaction_hash: raw.transaction_hash.parse().ok(),
+        transaction_index: None,
+        log_index: Some(raw.log_index as u64),
+        removed: false,
+    })
+}
+
+/// The time of a log's block. Ingestion stores it with every log, reading the
+/// block header when the RPC did not report it.
+pub fn block_time(log: &Log) -> Result<NaiveDateTime> {
+    log.block_timestamp
+        .and_then(|ts| DateTime::from_timestamp(ts as i64, 0))
+        .map(|time| time.naive_utc())
+        .ok_or_else(|| {
+            let block = log.block_number.unwrap_or_default();
+            eyre::eyre!(
+                "No timestamp recorded for block {block}; \
+                 `fin backfill --from {block} --to {block}` fetches its logs again"
+            )
+        })
+}
+
+/// Ensure a TIP-20 token address is registered in the DB.
+///
+/// If the token was discovered via the Factory, it will already exist.
+/// Otherwise, insert a placeholder with minimal metadata (it can be enriched later).
+/// Uses ON CONFLICT DO NOTHING so it's safe to call on every log.
+async fn ensure_token_registered(
+    pool: &sqlx::PgPool,
+    chain_id: i64,
+    token_address: &str,
+    block_number: i64,
+) -> Result<()> {
+    let token = Token {
+        address: token_address.to_string(),
+        name: String::new(),
+        symbol: String::new(),
+        decimals: 6,
+        currency: String::new(),
+        total_supply: "0".to_string(),
+        created_at_block: block_number,
+        created_at_tx: String::new(),
+    };
+    storage::repos::insert_token(pool, chain_id, &token).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloy::primitives::{Address, U256};
+    use std::sync::{Arc, Mutex};
+    use tempulse_core::address::TIP20_PREFIX;
+    use tempulse_handlers::{EventHandler, async_trait};
+
+    const TOKEN: &str = "0x20c0000000000000000000000000000000000001";
+
+    /// What a [`Recorder`] saw on one call: handler, method, transaction id,
+    /// and how many transfers that transaction could see.
+    type Call = (&'static str, &'static str, i64, i64);
+
+    /// A handler that records its calls, or fails them.
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<Call>>>,
+        fail: bool,
+    }
+
+    impl Recorder {
+        async fn record(&self, conn: &mut sqlx::PgConnection, method: &'static str) -> Result<()> {
+            let (txid, transfers): (i64, i64) =
+                sqlx::query_as("SELECT txid_current(), COUNT(*) FROM transfers")
+                    .fetch_one(conn)
+                    .await?;
+            self.calls
+                .lock()
+                .unwrap()
+                .push((self.name, method, txid, transfers));
+            if self.fail {
+                eyre::bail!("{} failed", self.name);
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl EventHandler for Recorder {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn handle(&self, conn: &mut sqlx::PgConnection, _: &Batch<'_>) -> Result<()> {
+            self.record(conn, "handle").await
+        }
+
+        async fn roll_back(&self, conn: &mut sqlx::PgConnection, _: i64, _: i64) -> Result<()> {
+            self.record(conn, "roll_back").await
+        }
+    }
+
+    fn chain() -> Chain {
+        Chain {
+            id: 1,
+            factory: Address::repeat_byte(0xfa),
+            tip20_prefix: TIP20_PREFIX,
+        }
+    }
+
+    /// `handlers` followed by handlers `first` and `second` (which fails if
+    /// `fail_second`), and the calls those two record.
+    pub(crate) fn recorders(
+        mut handlers: Handlers,
+        fail_second: bool,
+    ) -> (Handlers, Arc<Mutex<Vec<Call>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        handlers
+            .register(Recorder {
+                name: "first",
+                calls: calls.clone(),
+                fail: false,
+            })
+            .register(Recorder {
+                name: "second",
+                calls: calls.clone(),
+                fail: fail_second,
+            });
+        (handlers, calls)
+    }
+
+    /// Ingest one TIP-20 transfer in block 1, with nothing processed yet.
+    async fn ingest_transfer(pool: &sqlx::PgPool) {
+        let topic = |address: Address| format!("{:#x}", address.into_word());
+        let log = RawLog {
+            block_number: 1,
+            log_index: 0,
+            transaction_hash: format!("{:#x}", B256::repeat_byte(0x01)),
+            address: TOKEN.to_string(),
+            topics: vec![
+                format!("{:#x}", TIP20::Transfer::SIGNATURE_HASH),
+                topic(Address::repeat_byte(0x11)),
+                topic(Address::repeat_byte(0x22)),
+            ],
+            data: format!("{:#x}", B256::from(U256::from(1_000_000u64))),
+            block_timestamp: Some(1_700_000_000),
+        };
+        storage::repos::register_network(pool, 1, "test")
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        storage::repos::insert_logs(&mut conn, 1, &[log])
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn block_time_needs_the_stored_timestamp() {
+        let mut log = Log {
+            block_number: Some(7),
+            block_timestamp: Some(1_700_000_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            block_time(&log).unwrap(),
+            DateTime::from_timestamp(1_700_000_000, 0)
+                .unwrap()
+                .naive_utc()
+        );
+
+        log.block_timestamp = None;
+        let err = block_time(&log).unwrap_err().to_string();
+        assert!(err.contains("backfill --from 7 --to 7"), "{err}");
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn handlers_run_in_order_in_the_batch_transaction(pool: sqlx::PgPool) {
+        ingest_transfer(&pool).await;
+        let (handlers, calls) = recorders(Handlers::default(), false);
+
+        process_batch(&pool, &chain(), &handlers, 1, 1)
+            .await
+            .unwrap();
+        let handled = std::mem::take(&mut *calls.lock().unwrap());
+        let txid = handled[0].2;
+        // Both see the transfer the batch has stored but not committed yet.
+        assert_eq!(
+            handled,
+            [("first", "handle", txid, 1), ("second", "handle", txid, 1)]
+        );
+
+        roll_back(&pool, 1, &handlers, 0, Rollback::Derived)
+            .await
+            .unwrap();
+        let rolled_back = std::mem::take(&mut *calls.lock().unwrap());
+        let txid = rolled_back[0].2;
+        // Both run before the transfer is deleted, in the rollback's transaction.
+        assert_eq!(
+            rolled_back,
+            [
+                ("first", "roll_back", txid, 1),
+                ("second", "roll_back", txid, 1)
+            ]
+        );
+        assert_ne!(txid, handled[0].2);
+        assert_eq!(
+            storage::repos::get_last_indexed_block(&pool, 1)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn a_failing_handler_aborts_the_batch(pool: sqlx::PgPool) {
+        ingest_transfer(&pool).await;
+        let (handlers, calls) = recorders(Handlers::default(), true);
+
+        assert!(
+            process_batch(&pool, &chain(), &handlers, 1, 1)
+                .await
+                .is_err()
+        );
+        assert_eq!(calls.lock().unwrap().len(), 2);
+
+        let (transfers,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM transfers")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(transfers, 0);
+        assert_eq!(
+            storage::repos::get_last_indexed_block(&pool, 1)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Local OTLP collector stand-in for trying out trace export.
+//!
+//! ```sh
+//! cargo run -p tempulse-core --example otlp_collector
+//! OTLP_ENDPOINT=http://127.0.0.1:4318 cargo run --bin fin
+//! ```
+//!
+//! Listens on `COLLECTOR_PORT` (default 4318), accepts OTLP/HTTP protobuf
+//! exports on `/v1/traces` and prints one line per span.
+
+use axum::{Router, body::Bytes, http::StatusCode, routing::post};
+use opentelemetry_proto::tonic::{
+    collector::trace::v1::ExportTraceServiceRequest, common::v1::any_value::Value,
+    resource::v1::Resource,
+};
+use prost::Message;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// The `service.name` attribute of a resource, or `unknown`.
+fn service_name(resource: Option<&Resource>) -> String {
+    resource
+        .iter()
+        .flat_map(|r| &r.attributes)
+        .find(|kv| kv.key == "service.name")
+        .and_then(|kv| match kv.value.as_ref()?.value.as_ref()? {
+            Value::StringValue(name) => Some(name.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "unknown".into())
+}
+
+async fn traces(body: Bytes) -> StatusCode {
+    let request = match ExportTraceServiceRequest::decode(body) {
+        Ok(request) => request,
+        Err(e) => {
+            tracing::warn!(error = %e, "Rejected: not an OTLP trace export");
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+    for resource_spans in request.resource_spans {
+        let service = service_name(resource_spans.resource.as_ref());
+        for span in resource_spans.scope_spans.into_iter().flat_map(|s| s.spans) {
+            let micros = span
+                .end_time_unix_nano
+                .saturating_sub(span.start_time_unix_nano)
+                / 1_000;
+            tracing::info!(
+                service = %service,
+                trace = %hex(&span.trace_id),
+                span = %hex(&span.span_id),
+                parent = %hex(&span.parent_span_id),
+                duration_us = micros,
+                "{}",
+                span.name
+            );
+        }
+    }
+    StatusCode::OK
+}
+
+#[tokio::main]
+async fn main() {
+    let _telemetry = tempulse_core::telemetry::init("otlp-collector", None).expect("init logging");
+
+    let port: u16 = std::env::var("COLLECTOR_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(4318);
+    let app = Router::new().route("/v1/traces", post(traces));
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .expect("bind collector port");
+    tracing::info!("Collecting spans on http://127.0.0.1:{port}/v1/traces");
+    axum::serve(listener, app).await.expect("collector server");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_proto::tonic::{
+        common::v1::{AnyValue, KeyValue},
+        trace::v1::{ResourceSpans, ScopeSpans, Span},
+    };
+
+    fn resource(service: &str) -> Resource {
+        Resource {
+            attributes: vec![KeyValue {
+                key: "service.name".into(),
+                value: Some(AnyValue {
+                    value: Some(Value::StringValue(service.into())),
+                }),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn service_name_falls_back_to_unknown() {
+        assert_eq!(service_name(Some(&resource("fin"))), "fin");
+        assert_eq!(service_name(Some(&Resource::default())), "unknown");
+        assert_eq!(service_name(None), "unknown");
+    }
+
+    #[test]
+    fn ids_print_as_lowercase_hex() {
+        assert_eq!(hex(&[0x00, 0xab, 0x7f]), "00ab7f");
+        assert_eq!(hex(&[]), "");
+    }
+
+    #[tokio::test]
+    async fn accepts_trace_exports() {
+        let span = Span {
+            trace_id: vec![1; 16],
+            span_id: vec![2; 8],
+            name: "ingest".into(),
+            start_time_unix_nano: 1_000_000,
+            end_time_unix_nano: 3_000_000,
+            ..Default::default()
+        };
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: Some(resource("fin")),
+                scope_spans: vec![ScopeSpans {
+                    spans: vec![span.clone(), span],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let status = traces(Bytes::from(request.encode_to_vec())).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn rejects_other_bodies() {
+        let status = traces(Bytes::from_static(b"{\"resourceSpans\": []}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
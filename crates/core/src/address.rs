@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// The 12-byte prefix shared by ALL TIP-20 token addresses.
+/// Any address starting with this prefix is a TIP-20 token.
+pub const TIP20_PREFIX: [u8; 12] = [
+    0x20, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Why an address string was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AddressError {
+    #[error("invalid address {0:?}: expected 0x followed by 40 hex characters")]
+    Malformed(String),
+
+    #[error("invalid address {0:?}: mixed-case input fails the EIP-55 checksum")]
+    BadChecksum(String),
+
+    #[error("{0} is not a TIP-20 token address")]
+    NotTip20(String),
+}
+
+/// A validated EVM address in canonical form: `0x` + 40 lowercase hex digits.
+///
+/// This is the `{:#x}` format the indexer writes, so a parsed `Address` can be
+/// compared directly against stored columns. Parsing accepts all-lowercase,
+/// all-uppercase and EIP-55 checksummed input; mixed case that fails the
+/// checksum is rejected since it usually means a typo.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Address(String);
+
+impl Address {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check the address against the TIP-20 prefix rule.
+    pub fn is_tip20(&self) -> bool {
+        self.0.as_bytes()[2..]
+            .chunks_exact(2)
+            .zip(TIP20_PREFIX)
+            .all(|(pair, byte)| hex_byte(pair) == Some(byte))
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let hex = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .filter(|h| h.len() == 40 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| AddressError::Malformed(s.to_string()))?;
+
+        let has_lower = hex.bytes().any(|b| b.is_ascii_lowercase());
+        let has_upper = hex.bytes().any(|b| b.is_ascii_uppercase());
+        if has_lower && has_upper && hex != to_checksum(hex) {
+            return Err(AddressError::BadChecksum(s.to_string()));
+        }
+
+        Ok(Self(format!("0x{}", hex.to_ascii_lowercase())))
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = AddressError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Address> for String {
+    fn from(a: Address) -> Self {
+        a.0
+    }
+}
+
+impl AsRef<str> for Address {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A canonical [`Address`] that also satisfies the TIP-20 prefix rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TokenAddress(Address);
+
+impl TokenAddress {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl FromStr for TokenAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let address: Address = s.parse()?;
+        if !address.is_tip20() {
+            return Err(AddressError::NotTip20(address.0));
+        }
+        Ok(Self(address))
+    }
+}
+
+impl TryFrom<String> for TokenAddress {
+    type Error = AddressError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TokenAddress> for String {
+    fn from(a: TokenAddress) -> Self {
+        a.0.0
+    }
+}
+
+impl From<TokenAddress> for Address {
+    fn from(a: TokenAddress) -> Self {
+        a.0
+    }
+}
+
+impl AsRef<str> for TokenAddress {
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Display for TokenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Decode two hex digits into the byte they spell.
+fn hex_byte(pair: &[u8]) -> Option<u8> {
+    let nibble = |b: u8| (b as char).to_digit(16);
+    Some((nibble(pair[0])? << 4 | nibble(pair[1])?) as u8)
+}
+
+/// Apply EIP-55 mixed-case checksum encoding to 40 hex digits (no `0x`).
+fn to_checksum(hex: &str) -> String {
+    let lower = hex.to_ascii_lowercase();
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if c.is_ascii_alphabetic() && nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference vectors from EIP-55.
+    const EIP55_VECTORS: [&str; 8] = [
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn checksum_matches_eip55_vectors() {
+        for vector in EIP55_VECTORS {
+            let hex = &vector[2..];
+            assert_eq!(to_checksum(hex), hex, "{vector}");
+        }
+    }
+
+    #[test]
+    fn parses_eip55_vectors_to_lowercase() {
+        for vector in EIP55_VECTORS {
+            let address: Address = vector.parse().unwrap();
+            assert_eq!(address.as_str(), vector.to_ascii_lowercase());
+        }
+    }
+
+    #[test]
+    fn rejects_mixed_case_with_a_bad_checksum() {
+        let flipped = "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert_eq!(
+            flipped.parse::<Address>(),
+            Err(AddressError::BadChecksum(flipped.to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        for s in [
+            "",
+            "0x",
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "0x5aaeb6",
+            "0xzzaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+        ] {
+            assert!(
+                matches!(s.parse::<Address>(), Err(AddressError::Malformed(_))),
+                "{s:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn tip20_prefix() {
+        let token: Address = "0x20C0000000000000000000000000000000000001"
+            .parse()
+            .unwrap();
+        assert!(token.is_tip20());
+        assert!(
+            "0x20c0000000000000000000000000000000000001"
+                .parse::<TokenAddress>()
+                .is_ok()
+        );
+
+        let factory: Address = "0x20fc000000000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        assert!(!factory.is_tip20());
+        assert!(matches!(
+            factory.as_str().parse::<TokenAddress>(),
+            Err(AddressError::NotTip20(_))
+        ));
+    }
+}
@@ -0,0 +1,142 @@
+//! Entities — named groups of addresses (an exchange's hot and cold wallets,
+//! a bridge's escrow contracts, …) that token flows are aggregated over.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::address::Address;
+
+/// One entity and the addresses it controls.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Entity {
+    pub name: String,
+    pub addresses: Vec<Address>,
+}
+
+/// Why an entity file was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EntityError {
+    #[error("entity with an empty name")]
+    EmptyName,
+
+    /// Names in parentheses are reserved for addresses outside any entity.
+    #[error("entity name {0:?} is reserved")]
+    ReservedName(String),
+
+    #[error("{address} belongs to both {first:?} and {second:?}")]
+    DuplicateAddress {
+        address: Address,
+        first: String,
+        second: String,
+    },
+
+    #[error("invalid entity JSON: {0}")]
+    Json(String),
+}
+
+/// Parse entities from a JSON array.
+///
+/// ```json
+/// [{ "name": "Acme Exchange", "addresses": ["0x…hot", "0x…cold"] }]
+/// ```
+///
+/// Each address may belong to at most one entity.
+pub fn parse_json(raw: &str) -> Result<Vec<Entity>, EntityError> {
+    let mut entities: Vec<Entity> =
+        serde_json::from_str(raw).map_err(|e| EntityError::Json(e.to_string()))?;
+
+    let mut owners: HashMap<&Address, &str> = HashMap::new();
+    for entity in &mut entities {
+        entity.name = entity.name.trim().to_string();
+        if entity.name.is_empty() {
+            return Err(EntityError::EmptyName);
+        }
+        if entity.name.starts_with('(') {
+            return Err(EntityError::ReservedName(entity.name.clone()));
+        }
+    }
+    for entity in &entities {
+        for address in &entity.addresses {
+            if let Some(first) = owners.insert(address, &entity.name)
+                && first != entity.name
+            {
+                return Err(EntityError::DuplicateAddress {
+                    address: address.clone(),
+                    first: first.to_string(),
+                    second: entity.name.clone(),
+                });
+            }
+        }
+    }
+    Ok(entities)
+}
+
+/// Read and parse an entity file.
+pub fn load_file(path: &str) -> eyre::Result<Vec<Entity>> {
+    let raw = std::fs::read_to_string(path)?;
+    parse_json(&raw).map_err(|e| eyre::eyre!("{path}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOT: &str = "0x1111111111111111111111111111111111111111";
+    const COLD: &str = "0x2222222222222222222222222222222222222222";
+
+    #[test]
+    fn parses_entities() {
+        let raw = format!(r#"[{{"name": " Acme Exchange ", "addresses": ["{HOT}", "{COLD}"]}}]"#);
+        assert_eq!(
+            parse_json(&raw).unwrap(),
+            [Entity {
+                name: "Acme Exchange".into(),
+                addresses: vec![HOT.parse().unwrap(), COLD.parse().unwrap()],
+            }]
+        );
+        assert_eq!(parse_json("[]").unwrap(), []);
+    }
+
+    #[test]
+    fn an_address_may_repeat_within_one_entity() {
+        let raw = format!(r#"[{{"name": "Acme", "addresses": ["{HOT}", "{HOT}"]}}]"#);
+        assert!(parse_json(&raw).is_ok());
+    }
+
+    #[test]
+    fn an_address_may_not_belong_to_two_entities() {
+        let raw = format!(
+            r#"[{{"name": "Acme", "addresses": ["{HOT}"]}}, {{"name": "Bridge", "addresses": ["{HOT}"]}}]"#
+        );
+        assert_eq!(
+            parse_json(&raw),
+            Err(EntityError::DuplicateAddress {
+                address: HOT.parse().unwrap(),
+                first: "Acme".into(),
+                second: "Bridge".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_reserved_names() {
+        assert_eq!(
+            parse_json(r#"[{"name": "  ", "addresses": []}]"#),
+            Err(EntityError::EmptyName)
+        );
+        assert_eq!(
+            parse_json(r#"[{"name": "(unknown)", "addresses": []}]"#),
+            Err(EntityError::ReservedName("(unknown)".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_json_and_addresses() {
+        assert!(matches!(parse_json("{}"), Err(EntityError::Json(_))));
+        assert!(matches!(
+            parse_json(r#"[{"name": "Acme", "addresses": ["0x11"]}]"#),
+            Err(EntityError::Json(_))
+        ));
+    }
+}
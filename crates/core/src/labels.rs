@@ -0,0 +1,246 @@
+//! Address labels — human-readable names for known addresses (exchanges,
+//! issuers, bridges, …), imported from JSON or CSV files.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+use crate::address::Address;
+
+/// What kind of entity a labeled address belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelCategory {
+    Exchange,
+    Issuer,
+    Treasury,
+    Bridge,
+    Contract,
+    Precompile,
+    Other,
+}
+
+impl LabelCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Exchange => "exchange",
+            Self::Issuer => "issuer",
+            Self::Treasury => "treasury",
+            Self::Bridge => "bridge",
+            Self::Contract => "contract",
+            Self::Precompile => "precompile",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl FromStr for LabelCategory {
+    type Err = LabelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "exchange" => Ok(Self::Exchange),
+            "issuer" => Ok(Self::Issuer),
+            "treasury" => Ok(Self::Treasury),
+            "bridge" => Ok(Self::Bridge),
+            "contract" => Ok(Self::Contract),
+            "precompile" => Ok(Self::Precompile),
+            "other" => Ok(Self::Other),
+            _ => Err(LabelError::UnknownCategory(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for LabelCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A name and category assigned to one address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelEntry {
+    pub address: Address,
+    pub name: String,
+    pub category: LabelCategory,
+}
+
+/// Why a label file was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LabelError {
+    #[error("unknown label category {0:?}")]
+    UnknownCategory(String),
+
+    #[error("label for {0} has an empty name")]
+    EmptyName(String),
+
+    #[error("line {line}: {message}")]
+    Csv { line: usize, message: String },
+
+    #[error("invalid label JSON: {0}")]
+    Json(String),
+}
+
+/// Parse labels from a JSON array.
+///
+/// ```json
+/// [{ "address": "0x…", "name": "Acme Exchange hot wallet", "category": "exchange" }]
+/// ```
+pub fn parse_json(raw: &str) -> Result<Vec<LabelEntry>, LabelError> {
+    let entries: Vec<LabelEntry> =
+        serde_json::from_str(raw).map_err(|e| LabelError::Json(e.to_string()))?;
+    entries.into_iter().map(validated).collect()
+}
+
+/// Parse labels from CSV with an `address,category,name` header.
+///
+/// ```text
+/// address,category,name
+/// 0x20fc000000000000000000000000000000000000,precompile,TIP-20 Factory
+/// ```
+///
+/// The name is the last column, so it may itself contain commas. Blank lines
+/// and lines starting with `#` are ignored.
+pub fn parse_csv(raw: &str) -> Result<Vec<LabelEntry>, LabelError> {
+    let lines = raw
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+    let mut entries = Vec::new();
+    for (line, text) in lines {
+        let fields: Vec<&str> = text.splitn(3, ',').map(str::trim).collect();
+        if fields.first() == Some(&"address") {
+            continue; // Header row
+        }
+        let [address, category, name] = fields[..] else {
+            return Err(LabelError::Csv {
+                line,
+                message: "expected 3 columns (address,category,name)".into(),
+            });
+        };
+        let csv_err = |message: String| LabelError::Csv { line, message };
+        entries.push(validated(LabelEntry {
+            address: address.parse().map_err(|e| csv_err(format!("{e}")))?,
+            category: category.parse().map_err(|e| csv_err(format!("{e}")))?,
+            name: name.to_string(),
+        })?);
+    }
+    Ok(entries)
+}
+
+/// Read a label file, as CSV if its name ends in `.csv` and as JSON otherwise.
+pub fn load_file(path: &str) -> eyre::Result<Vec<LabelEntry>> {
+    let raw = std::fs::read_to_string(path)?;
+    let entries = if path.to_ascii_lowercase().ends_with(".csv") {
+        parse_csv(&raw)
+    } else {
+        parse_json(&raw)
+    };
+    entries.map_err(|e| eyre::eyre!("{path}: {e}"))
+}
+
+fn validated(mut entry: LabelEntry) -> Result<LabelEntry, LabelError> {
+    entry.name = entry.name.trim().to_string();
+    if entry.name.is_empty() {
+        return Err(LabelError::EmptyName(entry.address.to_string()));
+    }
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FACTORY: &str = "0x20fc000000000000000000000000000000000000";
+
+    #[test]
+    fn parses_csv_with_header_comments_and_commas_in_names() {
+        let raw = "address,category,name\n\n# precompiles\n0x20FC000000000000000000000000000000000000, Precompile ,TIP-20 Factory\n0x1111111111111111111111111111111111111111,exchange,Acme, Inc. hot wallet\n";
+        let entries = parse_csv(raw).unwrap();
+        assert_eq!(
+            entries,
+            [
+                LabelEntry {
+                    address: FACTORY.parse().unwrap(),
+                    name: "TIP-20 Factory".into(),
+                    category: LabelCategory::Precompile,
+                },
+                LabelEntry {
+                    address: "0x1111111111111111111111111111111111111111"
+                        .parse()
+                        .unwrap(),
+                    name: "Acme, Inc. hot wallet".into(),
+                    category: LabelCategory::Exchange,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_errors_carry_the_line_number() {
+        let missing =
+            parse_csv("address,category,name\n0x1111111111111111111111111111111111111111,exchange");
+        assert!(matches!(missing, Err(LabelError::Csv { line: 2, .. })));
+
+        let bad_address = parse_csv("0x11,exchange,Acme");
+        assert!(matches!(bad_address, Err(LabelError::Csv { line: 1, .. })));
+
+        let bad_category = parse_csv(&format!("\n{FACTORY},casino,Acme"));
+        assert!(matches!(bad_category, Err(LabelError::Csv { line: 2, .. })));
+    }
+
+    #[test]
+    fn csv_rejects_empty_names() {
+        assert_eq!(
+            parse_csv(&format!("{FACTORY},precompile,  ")),
+            Err(LabelError::EmptyName(FACTORY.into()))
+        );
+    }
+
+    #[test]
+    fn parses_json() {
+        let raw = format!(
+            r#"[{{"address":"{FACTORY}","name":" TIP-20 Factory ","category":"precompile"}}]"#
+        );
+        assert_eq!(
+            parse_json(&raw).unwrap(),
+            [LabelEntry {
+                address: FACTORY.parse().unwrap(),
+                name: "TIP-20 Factory".into(),
+                category: LabelCategory::Precompile,
+            }]
+        );
+    }
+
+    #[test]
+    fn json_rejects_bad_entries() {
+        assert!(matches!(parse_json("{}"), Err(LabelError::Json(_))));
+        assert!(matches!(
+            parse_json(r#"[{"address":"0x11","name":"x","category":"other"}]"#),
+            Err(LabelError::Json(_))
+        ));
+        assert!(matches!(
+            parse_json(&format!(
+                r#"[{{"address":"{FACTORY}","name":"x","category":"casino"}}]"#
+            )),
+            Err(LabelError::Json(_))
+        ));
+        assert_eq!(
+            parse_json(&format!(
+                r#"[{{"address":"{FACTORY}","name":"","category":"other"}}]"#
+            )),
+            Err(LabelError::EmptyName(FACTORY.into()))
+        );
+    }
+
+    #[test]
+    fn categories_parse_case_insensitively() {
+        assert_eq!(" Bridge ".parse(), Ok(LabelCategory::Bridge));
+        assert_eq!(
+            "casino".parse::<LabelCategory>(),
+            Err(LabelError::UnknownCategory("casino".into()))
+        );
+    }
+}
@@ -1,6 +1,11 @@
+pub mod address;
 pub mod config;
+pub mod entities;
 pub mod error;
+pub mod labels;
+pub mod metrics;
 pub mod telemetry;
+pub mod units;
 
 pub use config::Settings;
 pub use error::AppError;
@@ -0,0 +1,67 @@
+//! Prometheus metrics shared by the indexer and the API.
+//!
+//! Both binaries install one global recorder at startup and render it on
+//! `GET /metrics`. Metrics are recorded through the `metrics` macros; names
+//! ending in `_seconds` are histograms with latency buckets.
+
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+use std::time::Duration;
+
+pub use metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+pub use metrics_exporter_prometheus::PrometheusHandle;
+
+/// How often buffered histogram samples are folded into their buckets.
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Buckets for `*_seconds` histograms: 1ms to 60s.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+/// Buckets for histograms that count things (logs per batch, blocks per reorg).
+const COUNT_BUCKETS: &[f64] = &[
+    0.0, 1.0, 2.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1_000.0, 5_000.0, 10_000.0, 50_000.0,
+];
+
+/// Install the global Prometheus recorder. Histograms named in `count_histograms`
+/// get count buckets instead of latency buckets.
+pub fn install(count_histograms: &[&str]) -> eyre::Result<PrometheusHandle> {
+    let mut builder = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".into()), LATENCY_BUCKETS)?;
+    for name in count_histograms {
+        builder = builder.set_buckets_for_metric(Matcher::Full(name.to_string()), COUNT_BUCKETS)?;
+    }
+    let handle = builder.install_recorder()?;
+
+    // Without the exporter's own HTTP listener, upkeep is up to us.
+    let upkeep = handle.clone();
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(UPKEEP_INTERVAL);
+            upkeep.run_upkeep();
+        }
+    });
+    Ok(handle)
+}
+
+/// Record the utilisation of a database connection pool.
+pub fn record_pool(size: u32, idle: usize, max: u32) {
+    gauge!("tempulse_db_pool_connections", "state" => "idle").set(idle as f64);
+    gauge!("tempulse_db_pool_connections", "state" => "in_use")
+        .set(size.saturating_sub(idle as u32) as f64);
+    gauge!("tempulse_db_pool_max_connections").set(max as f64);
+}
+
+/// Describe the metrics recorded by [`record_pool`].
+pub fn describe_pool() {
+    describe_gauge!(
+        "tempulse_db_pool_connections",
+        "Open database connections by state (idle, in_use)"
+    );
+    describe_gauge!(
+        "tempulse_db_pool_max_connections",
+        "Maximum size of the database connection pool"
+    );
+}
@@ -1,10 +1,81 @@
-use tracing_subscriber::{fmt, EnvFilter};
+//! Logging and distributed tracing.
+//!
+//! Logs always go to stdout. When an OTLP endpoint is configured, spans are
+//! also exported to a collector over OTLP/HTTP (protobuf), and W3C trace
+//! context (`traceparent`) links them to spans of the calling service.
+
+use opentelemetry::{global, propagation::Extractor, trace::TracerProvider as _};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{Resource, propagation::TraceContextPropagator, trace::SdkTracerProvider};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Flushes buffered spans when dropped; keep it alive for the life of the process.
+#[must_use = "dropping the guard shuts the span exporter down"]
+pub struct TelemetryGuard(Option<SdkTracerProvider>);
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.0.take()
+            && let Err(e) = provider.shutdown()
+        {
+            tracing::warn!(error = %e, "Failed to flush spans");
+        }
+    }
+}
 
 /// Initialise the global tracing subscriber.
 ///
-/// Respects `RUST_LOG` env var; defaults to `info` level.
-pub fn init() {
+/// Respects `RUST_LOG` env var; defaults to `info` level. With an
+/// `otlp_endpoint` (e.g. `http://localhost:4318`), spans are also exported
+/// under `service_name`.
+pub fn init(
+    service_name: &'static str,
+    otlp_endpoint: Option<&str>,
+) -> eyre::Result<TelemetryGuard> {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(true));
+
+    let Some(endpoint) = otlp_endpoint else {
+        registry.init();
+        return Ok(TelemetryGuard(None));
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    registry
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name)))
+        .init();
+    tracing::info!(endpoint, "Exporting spans over OTLP");
+    Ok(TelemetryGuard(Some(provider)))
+}
+
+/// Make `span` a child of the remote trace whose W3C context (`traceparent`,
+/// `tracestate`) `header` looks up in an incoming request. Does nothing when
+/// OTLP export is disabled or the request carries no context.
+pub fn set_remote_parent<'a>(span: &tracing::Span, header: impl Fn(&str) -> Option<&'a str>) {
+    struct Headers<'f, 'a>(&'f dyn Fn(&str) -> Option<&'a str>);
+
+    impl Extractor for Headers<'_, '_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            (self.0)(key)
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            vec!["traceparent", "tracestate"]
+        }
+    }
 
-    fmt().with_env_filter(filter).with_target(true).init();
+    let parent = global::get_text_map_propagator(|p| p.extract(&Headers(&header)));
+    let _ = span.set_parent(parent);
 }
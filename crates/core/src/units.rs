@@ -0,0 +1,116 @@
+/// Render a raw base-unit integer string as a decimal string.
+///
+/// The decimal point is shifted `decimals` places by string manipulation, so
+/// the result is exact for amounts of any size (no floating point involved).
+/// Trailing fractional zeros are trimmed: `("1500000", 6)` → `"1.5"`.
+/// Input that is not a plain unsigned integer is returned unchanged.
+pub fn format_units(raw: &str, decimals: u32) -> String {
+    if raw.is_empty() || !raw.bytes().all(|b| b.is_ascii_digit()) {
+        return raw.to_string();
+    }
+
+    let digits = raw.trim_start_matches('0');
+    let decimals = decimals as usize;
+
+    let (int, frac) = if digits.len() > decimals {
+        digits.split_at(digits.len() - decimals)
+    } else {
+        ("", digits)
+    };
+
+    let int = if int.is_empty() { "0" } else { int };
+    let frac = format!("{:0>width$}", frac, width = decimals);
+    let frac = frac.trim_end_matches('0');
+
+    if frac.is_empty() {
+        int.to_string()
+    } else {
+        format!("{int}.{frac}")
+    }
+}
+
+/// [`format_units`] for amounts that may be negative, such as net flows.
+///
+/// A leading `-` is set aside, the magnitude formatted, and the sign put back,
+/// so `("-1500000", 6)` → `"-1.5"`. Negative zero renders as `"0"`.
+pub fn format_signed_units(raw: &str, decimals: u32) -> String {
+    let Some(magnitude) = raw.strip_prefix('-') else {
+        return format_units(raw, decimals);
+    };
+    if magnitude.is_empty() || !magnitude.bytes().all(|b| b.is_ascii_digit()) {
+        return raw.to_string();
+    }
+    match format_units(magnitude, decimals) {
+        zero if zero == "0" => zero,
+        formatted => format!("-{formatted}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_signed_units, format_units};
+
+    #[test]
+    fn zero() {
+        assert_eq!(format_units("0", 6), "0");
+        assert_eq!(format_units("000", 6), "0");
+        assert_eq!(format_units("0", 0), "0");
+    }
+
+    #[test]
+    fn whole_and_fractional_amounts() {
+        assert_eq!(format_units("1500000", 6), "1.5");
+        assert_eq!(format_units("1000000", 6), "1");
+        assert_eq!(format_units("1234567", 6), "1.234567");
+        assert_eq!(format_units("42", 0), "42");
+    }
+
+    #[test]
+    fn leading_zeros_are_ignored() {
+        assert_eq!(format_units("0001500000", 6), "1.5");
+        assert_eq!(format_units("00042", 0), "42");
+    }
+
+    #[test]
+    fn fewer_digits_than_decimals() {
+        assert_eq!(format_units("1", 6), "0.000001");
+        assert_eq!(format_units("120", 6), "0.00012");
+        assert_eq!(format_units("999999", 6), "0.999999");
+    }
+
+    #[test]
+    fn amounts_beyond_u128() {
+        let raw = "123456789012345678901234567890123456789012345678901234567890";
+        assert_eq!(
+            format_units(raw, 18),
+            "123456789012345678901234567890123456789012.34567890123456789"
+        );
+    }
+
+    #[test]
+    fn bad_input_is_returned_unchanged() {
+        assert_eq!(format_units("", 6), "");
+        assert_eq!(format_units("1.5", 6), "1.5");
+        assert_eq!(format_units("0x10", 6), "0x10");
+        assert_eq!(format_units(" 1", 6), " 1");
+        assert_eq!(format_units("abc", 6), "abc");
+    }
+
+    #[test]
+    fn signed_amounts() {
+        assert_eq!(format_signed_units("-1500000", 6), "-1.5");
+        assert_eq!(format_signed_units("-1", 6), "-0.000001");
+        assert_eq!(format_signed_units("-42", 0), "-42");
+        assert_eq!(format_signed_units("1500000", 6), "1.5");
+        assert_eq!(format_signed_units("-0", 6), "0");
+        assert_eq!(format_signed_units("-000", 6), "0");
+    }
+
+    #[test]
+    fn bad_signed_input_is_returned_unchanged() {
+        assert_eq!(format_signed_units("-", 6), "-");
+        assert_eq!(format_signed_units("--1", 6), "--1");
+        assert_eq!(format_signed_units("-1.5", 6), "-1.5");
+        assert_eq!(format_signed_units("+1", 6), "+1");
+    }
+}
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use tempulse_storage as storage;
+
+use crate::{Batch, EventHandler};
+
+/// Keeps `accounts` balances: transfers and mints credit the receiver,
+/// transfers and burns debit the sender.
+pub struct AccountBalances;
+
+#[async_trait]
+impl EventHandler for AccountBalances {
+    fn name(&self) -> &str {
+        "account_balances"
+    }
+
+    async fn handle(&self, conn: &mut PgConnection, batch: &Batch<'_>) -> eyre::Result<()> {
+        for event in batch.events {
+            let t = &event.transfer;
+            if t.event_type == "transfer" || t.event_type == "mint" {
+                storage::repos::upsert_account_balance(
+                    &mut *conn,
+                    batch.chain_id,
+                    &t.to_address,
+                    &t.token_address,
+                    &t.amount,
+                    true,
+                    t.block_number,
+                )
+                .await?;
+            }
+            if t.event_type == "transfer" || t.event_type == "burn" {
+                storage::repos::upsert_account_balance(
+                    &mut *conn,
+                    batch.chain_id,
+                    &t.from_address,
+                    &t.token_address,
+                    &t.amount,
+                    false,
+                    t.block_number,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn roll_back(
+        &self,
+        conn: &mut PgConnection,
+        chain_id: i64,
+        block: i64,
+    ) -> eyre::Result<()> {
+        storage::repos::revert_account_balances(conn, chain_id, block).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::PgConnection;
+use tempulse_storage::models::NewTransfer;
+use tempulse_tempo::decoder::{self, Tip20Event};
+
+use crate::{AccountBalances, HourlyStats, TotalSupply};
+
+/// A decoded event of a batch, with the `transfers` row stored for it.
+#[derive(Debug, Clone)]
+pub struct BatchEvent {
+    pub event: Tip20Event,
+    /// The event as stored in `transfers` (see [`transfer_row`]).
+    pub transfer: NewTransfer,
+}
+
+/// The events of blocks `from_block..=to_block`, in chain order.
+#[derive(Debug, Clone, Copy)]
+pub struct Batch<'a> {
+    pub chain_id: i64,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub events: &'a [BatchEvent],
+}
+
+/// A pluggable processor of decoded TIP-20 events.
+///
+/// The indexer calls every registered handler once per batch, inside the
+/// transaction that stores the batch's transfers and moves the processing
+/// cursor. A handler's writes commit with the batch; an error aborts the batch,
+/// which is retried.
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    /// Short name, recorded on the handler's trace span.
+    fn name(&self) -> &str;
+
+    /// Apply a batch's events. The batch's transfers are already inserted.
+    async fn handle(&self, conn: &mut PgConnection, batch: &Batch<'_>) -> eyre::Result<()>;
+
+    /// Undo what [`handle`](Self::handle) did for the blocks after `block`, on
+    /// a reorg, `fin rollback`, `fin reprocess` or `fin backfill`. Runs in the
+    /// rollback's transaction while those blocks' transfers are still stored.
+    /// It may cover blocks the handler never handled, e.g. ones processed
+    /// before it was registered. `fin reindex-token` rebuilds only the built-in
+    /// tables, so it refuses to run while other handlers are registered.
+    async fn roll_back(
+        &self,
+        conn: &mut PgConnection,
+        chain_id: i64,
+        block: i64,
+    ) -> eyre::Result<()>;
+}
+
+/// The handlers the indexer runs, in registration order. The default is
+/// empty; [`Handlers::builtin`] keeps the indexer's own tables.
+#[derive(Default)]
+pub struct Handlers {
+    handlers: Vec<Box<dyn EventHandler>>,
+    /// How many of `handlers`, from the first, [`Handlers::builtin`] registered.
+    builtin: usize,
+}
+
+impl std::fmt::Debug for Handlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(|handler| handler.name()))
+            .finish()
+    }
+}
+
+impl Handlers {
+    /// The built-in handlers: account balances, token supplies and hourly stats.
+    pub fn builtin() -> Self {
+        let mut handlers = Self::default();
+        handlers
+            .register(AccountBalances)
+            .register(TotalSupply)
+            .register(HourlyStats);
+        handlers.builtin = handlers.handlers.len();
+        handlers
+    }
+
+    /// Add `handler`, to run after those already registered.
+    pub fn register(&mut self, handler: impl EventHandler + 'static) -> &mut Self {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn EventHandler> {
+        self.handlers.iter().map(|handler| handler.as_ref())
+    }
+
+    /// The handlers registered besides those of [`Handlers::builtin`].
+    pub fn custom(&self) -> impl Iterator<Item = &dyn EventHandler> {
+        self.iter().skip(self.builtin)
+    }
+}
+
+/// The `transfers` row for a decoded TIP-20 event of a block mined at `block_time`.
+pub fn transfer_row(event: &Tip20Event, block_time: NaiveDateTime) -> NewTransfer {
+    let (token, from, to, amount, event_type, memo, block_number, tx_hash, log_index) = match event
+    {
+        Tip20Event::Transfer {
+            token_address,
+            from,
+            to,
+            amount,
+            block_number,
+            transaction_hash,
+            log_index,
+        } => (
+            token_address,
+            *from,
+            *to,
+            amount,
+            "transfer",
+            None,
+            block_number,
+            transaction_hash,
+            log_index,
+        ),
+        Tip20Event::Mint {
+            token_address,
+            to,
+            amount,
+            block_number,
+            transaction_hash,
+            log_index,
+        } => (
+            token_address,
+            decoder::ZERO_ADDRESS,
+            *to,
+            amount,
+            "mint",
+            None,
+            block_number,
+            transaction_hash,
+            log_index,
+        ),
+        Tip20Event::Burn {
+            token_address,
+            from,
+            amount,
+            block_number,
+            transaction_hash,
+            log_index,
+        } => (
+            token_address,
+            *from,
+            decoder::ZERO_ADDRESS,
+            amount,
+            "burn",
+            None,
+            block_number,
+            transaction_hash,
+            log_index,
+        ),
+        Tip20Event::TransferWithMemo {
+            token_address,
+            from,
+            to,
+            amount,
+            memo,
+            block_number,
+            transaction_hash,
+            log_index,
+        } => (
+            token_address,
+            *from,
+            *to,
+            amount,
+            "transfer",
+            Some(format!("0x{}", hex::encode(memo))),
+            block_number,
+            transaction_hash,
+            log_index,
+        ),
+    };
+    NewTransfer {
+        token_address: format!("{:#x}", token),
+        from_address: format!("{:#x}", from),
+        to_address: format!("{:#x}", to),
+        amount: amount.to_string(),
+        memo,
+        event_type: event_type.to_string(),
+        transaction_hash: tx_hash.clone(),
+        block_number: *block_number as i64,
+        log_index: *log_index as i32,
+        block_time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0x20c0000000000000000000000000000000000001";
+    const ALICE: &str = "0x1111111111111111111111111111111111111111";
+    const BOB: &str = "0x2222222222222222222222222222222222222222";
+    const ZERO: &str = "0x0000000000000000000000000000000000000000";
+    const TX: &str = "0xabc";
+
+    fn time() -> NaiveDateTime {
+        chrono::DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    /// The fields that differ between event types: type, from, to, amount, memo.
+    fn summary(row: &NewTransfer) -> (&str, &str, &str, &str, Option<&str>) {
+        (
+            &row.event_type,
+            &row.from_address,
+            &row.to_address,
+            &row.amount,
+            row.memo.as_deref(),
+        )
+    }
+
+    #[test]
+    fn transfer() {
+        let event = Tip20Event::Transfer {
+            token_address: TOKEN.parse().unwrap(),
+            from: ALICE.parse().unwrap(),
+            to: BOB.parse().unwrap(),
+            amount: "1500000".parse().unwrap(),
+            block_number: 42,
+            transaction_hash: TX.into(),
+            log_index: 3,
+        };
+        let row = transfer_row(&event, time());
+        assert_eq!(summary(&row), ("transfer", ALICE, BOB, "1500000", None));
+        assert_eq!(row.token_address, TOKEN);
+        assert_eq!(
+            (
+                row.block_number,
+                row.log_index,
+                row.transaction_hash.as_str()
+            ),
+            (42, 3, TX)
+        );
+        assert_eq!(row.block_time, time());
+    }
+
+    #[test]
+    fn mint_comes_from_the_zero_address() {
+        let event = Tip20Event::Mint {
+            token_address: TOKEN.parse().unwrap(),
+            to: BOB.parse().unwrap(),
+            amount: "7".parse().unwrap(),
+            block_number: 1,
+            transaction_hash: TX.into(),
+            log_index: 0,
+        };
+        let row = transfer_row(&event, time());
+        assert_eq!(summary(&row), ("mint", ZERO, BOB, "7", None));
+    }
+
+    #[test]
+    fn burn_goes_to_the_zero_address() {
+        let event = Tip20Event::Burn {
+            token_address: TOKEN.parse().unwrap(),
+            from: ALICE.parse().unwrap(),
+            amount: "7".parse().unwrap(),
+            block_number: 1,
+            transaction_hash: TX.into(),
+            log_index: 0,
+        };
+        let row = transfer_row(&event, time());
+        assert_eq!(summary(&row), ("burn", ALICE, ZERO, "7", None));
+    }
+
+    #[test]
+    fn transfer_with_memo_keeps_the_memo_as_hex() {
+        let mut memo = [0u8; 32];
+        memo[0] = 0xde;
+        memo[31] = 0x01;
+        let event = Tip20Event::TransferWithMemo {
+            token_address: TOKEN.parse().unwrap(),
+            from: ALICE.parse().unwrap(),
+            to: BOB.parse().unwrap(),
+            amount: "340282366920938463463374607431768211456".parse().unwrap(),
+            memo,
+            block_number: 1,
+            transaction_hash: TX.into(),
+            log_index: 0,
+        };
+        let row = transfer_row(&event, time());
+        let hex_memo = format!("0xde{}01", "00".repeat(30));
+        assert_eq!(
+            summary(&row),
+            (
+                "transfer",
+                ALICE,
+                BOB,
+                "340282366920938463463374607431768211456",
+                Some(hex_memo.as_str())
+            )
+        );
+    }
+
+    #[test]
+    fn handlers_debug_as_their_names() {
+        assert_eq!(format!("{:?}", Handlers::default()), "[]");
+        assert_eq!(
+            format!("{:?}", Handlers::builtin()),
+            r#"["account_balances", "total_supply", "hourly_stats"]"#
+        );
+    }
+
+    #[test]
+    fn custom_handlers_are_those_registered_after_the_builtin_ones() {
+        let names = |handlers: &Handlers| {
+            handlers
+                .custom()
+                .map(|h| h.name().to_string())
+                .collect::<Vec<_>>()
+        };
+        let mut handlers = Handlers::builtin();
+        assert!(names(&handlers).is_empty());
+        handlers.register(HourlyStats);
+        assert_eq!(names(&handlers), ["hourly_stats"]);
+
+        let mut handlers = Handlers::default();
+        handlers.register(AccountBalances);
+        assert_eq!(names(&handlers), ["account_balances"]);
+    }
+}
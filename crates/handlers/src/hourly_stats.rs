@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Timelike};
+use sqlx::PgConnection;
+use tempulse_storage as storage;
+
+use crate::{Batch, EventHandler};
+
+/// Aggregates transfers, mints and burns into `hourly_stats`, bucketed by the
+/// hour of their block.
+pub struct HourlyStats;
+
+#[async_trait]
+impl EventHandler for HourlyStats {
+    fn name(&self) -> &str {
+        "hourly_stats"
+    }
+
+    async fn handle(&self, conn: &mut PgConnection, batch: &Batch<'_>) -> eyre::Result<()> {
+        for event in batch.events {
+            storage::repos::upsert_hourly_stats(
+                &mut *conn,
+                batch.chain_id,
+                &event.transfer,
+                hour_of(event.transfer.block_time),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn roll_back(
+        &self,
+        conn: &mut PgConnection,
+        chain_id: i64,
+        block: i64,
+    ) -> eyre::Result<()> {
+        storage::repos::revert_hourly_stats(conn, chain_id, block).await?;
+        Ok(())
+    }
+}
+
+/// The start of the hour `time` falls in.
+pub fn hour_of(time: NaiveDateTime) -> NaiveDateTime {
+    time.date()
+        .and_hms_opt(time.time().hour(), 0, 0)
+        .unwrap_or_default()
+}
@@ -0,0 +1,15 @@
+//! Event handlers: turn each processed batch of decoded TIP-20 events into
+//! derived rows. The built-in handlers keep account balances, token supplies
+//! and hourly stats; implement [`EventHandler`] and [`Handlers::register`] it
+//! to keep your own tables (an internal ledger, say) in step with the indexer.
+
+pub mod balances;
+pub mod handler;
+pub mod hourly_stats;
+pub mod supply;
+
+pub use async_trait::async_trait;
+pub use balances::AccountBalances;
+pub use handler::{Batch, BatchEvent, EventHandler, Handlers, transfer_row};
+pub use hourly_stats::HourlyStats;
+pub use supply::TotalSupply;
@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use tempulse_storage as storage;
+
+use crate::{Batch, EventHandler};
+
+/// Keeps each token's `total_supply`: mints add to it, burns subtract from it.
+pub struct TotalSupply;
+
+#[async_trait]
+impl EventHandler for TotalSupply {
+    fn name(&self) -> &str {
+        "total_supply"
+    }
+
+    async fn handle(&self, conn: &mut PgConnection, batch: &Batch<'_>) -> eyre::Result<()> {
+        for event in batch.events {
+            let t = &event.transfer;
+            let is_mint = match t.event_type.as_str() {
+                "mint" => true,
+                "burn" => false,
+                _ => continue,
+            };
+            storage::repos::update_total_supply_on_event(
+                &mut *conn,
+                batch.chain_id,
+                &t.token_address,
+                &t.amount,
+                is_mint,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn roll_back(
+        &self,
+        conn: &mut PgConnection,
+        chain_id: i64,
+        block: i64,
+    ) -> eyre::Result<()> {
+        storage::repos::revert_total_supply(conn, chain_id, block).await?;
+        Ok(())
+    }
+}
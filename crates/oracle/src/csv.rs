@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::source::{FxQuote, PriceSource};
+
+/// Historical rates imported from a CSV file with a `day,base,quote,rate` header.
+///
+/// ```text
+/// day,base,quote,rate
+/// 2025-01-01,EUR,USD,1.0354
+/// 2025-01-02,EUR,USD,1.0298
+/// ```
+///
+/// Fields may be double-quoted (`"EUR"`), with `""` standing for a literal
+/// quote inside a quoted field. Blank lines and lines starting with `#` are
+/// ignored.
+pub struct CsvSource {
+    path: String,
+}
+
+impl CsvSource {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl PriceSource for CsvSource {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    async fn fetch_rates(&self) -> eyre::Result<Vec<FxQuote>> {
+        let raw = std::fs::read_to_string(&self.path)?;
+        parse_rates(&self.path, &raw)
+    }
+}
+
+/// Parse the contents of a rates CSV; `path` is only used in error messages.
+fn parse_rates(path: &str, raw: &str) -> eyre::Result<Vec<FxQuote>> {
+    let mut rates = Vec::new();
+
+    let lines = raw
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+    for (line_no, line) in lines {
+        let fields = split_fields(line)
+            .ok_or_else(|| eyre::eyre!("{}:{}: unterminated quoted field", path, line_no))?;
+        let fields: Vec<&str> = fields.iter().map(|f| f.trim()).collect();
+        if fields.first() == Some(&"day") {
+            continue; // Header row
+        }
+        let [day, base, quote, rate] = fields[..] else {
+            eyre::bail!(
+                "{}:{}: expected 4 columns (day,base,quote,rate)",
+                path,
+                line_no
+            );
+        };
+        let day = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .map_err(|e| eyre::eyre!("{}:{}: invalid day {:?}: {}", path, line_no, day, e))?;
+
+        rates.push(
+            FxQuote {
+                day,
+                base: base.to_string(),
+                quote: quote.to_string(),
+                rate: rate.to_string(),
+            }
+            .normalized()?,
+        );
+    }
+
+    Ok(rates)
+}
+
+/// Split one CSV record into fields, unquoting double-quoted fields.
+///
+/// Returns `None` if a quoted field is never closed.
+fn split_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_plain_and_quoted_fields() {
+        assert_eq!(
+            split_fields("2025-01-01,EUR,USD,1.0354").unwrap(),
+            ["2025-01-01", "EUR", "USD", "1.0354"]
+        );
+        assert_eq!(
+            split_fields(r#""2025-01-01", "EUR",USD,"1.0354""#).unwrap(),
+            ["2025-01-01", "EUR", "USD", "1.0354"]
+        );
+    }
+
+    #[test]
+    fn quoted_fields_keep_commas_and_escaped_quotes() {
+        assert_eq!(
+            split_fields(r#"a,"b,c","say ""hi""",d"#).unwrap(),
+            ["a", "b,c", r#"say "hi""#, "d"]
+        );
+        assert_eq!(split_fields(r#""""#).unwrap(), [""]);
+    }
+
+    #[test]
+    fn unterminated_quote_is_rejected() {
+        assert_eq!(split_fields(r#"a,"b"#), None);
+        let err = parse_rates("rates.csv", "2025-01-01,\"EUR,USD,1.0").unwrap_err();
+        assert!(err.to_string().contains("rates.csv:1"));
+    }
+
+    #[test]
+    fn parses_rates_skipping_header_comments_and_blanks() {
+        let raw = "day,base,quote,rate\n\n# comment\n2025-01-01,eur,usd,1.0354\n\"2025-01-02\",\"EUR\",\"USD\",\"1.0298\"\n";
+        let rates = parse_rates("rates.csv", raw).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].base, "EUR");
+        assert_eq!(rates[0].quote, "USD");
+        assert_eq!(rates[0].rate, "1.0354");
+        assert_eq!(rates[1].day, NaiveDate::from_ymd_opt(2025, 1, 2).unwrap());
+        assert_eq!(rates[1].rate, "1.0298");
+    }
+
+    #[test]
+    fn reports_line_of_bad_rows() {
+        let err = parse_rates("rates.csv", "day,base,quote,rate\n2025-01-01,EUR,USD").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("rates.csv:2: expected 4 columns")
+        );
+
+        let err = parse_rates("rates.csv", "2025-13-01,EUR,USD,1").unwrap_err();
+        assert!(err.to_string().starts_with("rates.csv:1: invalid day"));
+
+        assert!(parse_rates("rates.csv", "2025-01-01,EUR,USD,-1").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::source::{FxQuote, PriceSource};
+
+/// Rates fetched from an HTTP endpoint returning a JSON array:
+///
+/// ```json
+/// [{ "day": "2025-01-01", "base": "EUR", "quote": "USD", "rate": "1.0354" }]
+/// ```
+///
+/// `rate` may be a JSON string or number. This is the shape served by a local
+/// stand-in during development; a real provider sits behind the same contract.
+pub struct HttpSource {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct HttpRate {
+    day: NaiveDate,
+    base: String,
+    quote: String,
+    rate: RateValue,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RateValue {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+impl HttpSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for HttpSource {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn fetch_rates(&self) -> eyre::Result<Vec<FxQuote>> {
+        let entries: Vec<HttpRate> = self
+            .client
+            .get(&self.url)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        entries
+            .into_iter()
+            .map(|e| {
+                let rate = match e.rate {
+                    RateValue::Text(s) => s,
+                    RateValue::Number(n) => n.to_string(),
+                };
+                FxQuote {
+                    day: e.day,
+                    base: e.base,
+                    quote: e.quote,
+                    rate,
+                }
+                .normalized()
+            })
+            .collect()
+    }
+}
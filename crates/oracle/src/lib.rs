@@ -0,0 +1,9 @@
+pub mod csv;
+pub mod http;
+pub mod source;
+pub mod static_file;
+
+pub use csv::CsvSource;
+pub use http::HttpSource;
+pub use source::{FxQuote, PriceSource, from_spec};
+pub use static_file::StaticSource;
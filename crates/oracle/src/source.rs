@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{CsvSource, HttpSource, StaticSource};
+
+/// A daily exchange rate: one unit of `base` is worth `rate` units of `quote`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FxQuote {
+    pub day: NaiveDate,
+    pub base: String,
+    pub quote: String,
+    pub rate: String,
+}
+
+impl FxQuote {
+    /// Validate the quote and normalize currency codes to upper case.
+    ///
+    /// Rates are kept as decimal strings so they can be stored and multiplied
+    /// exactly in PostgreSQL `NUMERIC` arithmetic.
+    pub fn normalized(self) -> eyre::Result<Self> {
+        let base = normalize_currency(&self.base)?;
+        let quote = normalize_currency(&self.quote)?;
+        let rate = self.rate.trim().to_string();
+
+        if !is_positive_decimal(&rate) {
+            eyre::bail!("Invalid FX rate {:?} for {}/{}", self.rate, base, quote);
+        }
+
+        Ok(Self {
+            day: self.day,
+            base,
+            quote,
+            rate,
+        })
+    }
+}
+
+/// A pluggable provider of daily FX rates.
+///
+/// Implementations only fetch rates; persisting them into `fx_rates` is the
+/// caller's job, so a source never needs database access.
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Short human-readable name, recorded alongside each stored rate.
+    fn name(&self) -> &str;
+
+    /// Fetch the rates currently offered by this source.
+    async fn fetch_rates(&self) -> eyre::Result<Vec<FxQuote>>;
+}
+
+/// Build a price source from a spec string:
+///
+/// - `static:<path>` — JSON file of fixed rates (see [`StaticSource`])
+/// - `csv:<path>` — CSV file of historical rates (see [`CsvSource`])
+/// - `http://…` / `https://…` — JSON endpoint (see [`HttpSource`])
+pub fn from_spec(spec: &str) -> eyre::Result<Box<dyn PriceSource>> {
+    if let Some(path) = spec.strip_prefix("static:") {
+        Ok(Box::new(StaticSource::new(path)))
+    } else if let Some(path) = spec.strip_prefix("csv:") {
+        Ok(Box::new(CsvSource::new(path)))
+    } else if spec.starts_with("http://") || spec.starts_with("https://") {
+        Ok(Box::new(HttpSource::new(spec)))
+    } else {
+        eyre::bail!(
+            "Unknown FX source {:?} (expected static:, csv: or an http(s) URL)",
+            spec
+        )
+    }
+}
+
+/// Upper-case a currency code, rejecting anything that is not 1–12 ASCII letters.
+pub(crate) fn normalize_currency(code: &str) -> eyre::Result<String> {
+    let code = code.trim();
+    if code.is_empty() || code.len() > 12 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        eyre::bail!("Invalid currency code {:?}", code);
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+/// Check for a plain positive decimal such as `1`, `1.08` or `0.000125`.
+fn is_positive_decimal(s: &str) -> bool {
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    let digits_ok = !int.is_empty()
+        && int.chars().all(|c| c.is_ascii_digit())
+        && frac.chars().all(|c| c.is_ascii_digit())
+        && !(s.contains('.') && frac.is_empty());
+    digits_ok && s.chars().any(|c| matches!(c, '1'..='9'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positive_decimals() {
+        for s in ["1", "1.08", "0.000125", "10", "007", "0.5"] {
+            assert!(is_positive_decimal(s), "{s:?} should be accepted");
+        }
+    }
+
+    #[test]
+    fn rejects_zero_signed_and_malformed_decimals() {
+        for s in [
+            "", "0", "0.000", "-1", "+1", "1.", ".5", "1.2.3", "1e3", "1,5", " 1", "NaN", "inf",
+        ] {
+            assert!(!is_positive_decimal(s), "{s:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn normalizes_quotes() {
+        let quote = FxQuote {
+            day: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            base: " eur ".into(),
+            quote: "usd".into(),
+            rate: " 1.0354 ".into(),
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!((quote.base.as_str(), quote.quote.as_str()), ("EUR", "USD"));
+        assert_eq!(quote.rate, "1.0354");
+
+        assert!(normalize_currency("US1").is_err());
+        assert!(normalize_currency("").is_err());
+    }
+}
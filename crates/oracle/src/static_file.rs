@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::source::{FxQuote, PriceSource};
+
+/// Fixed rates read from a JSON file.
+///
+/// ```json
+/// [
+///   { "base": "EUR", "quote": "USD", "rate": "1.08" },
+///   { "base": "GBP", "quote": "USD", "rate": "1.27", "day": "2025-01-01" }
+/// ]
+/// ```
+///
+/// Entries without a `day` are stamped with the current UTC date, so a
+/// periodic sync builds up a daily history from a single static file.
+pub struct StaticSource {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct StaticRate {
+    base: String,
+    quote: String,
+    rate: String,
+    day: Option<NaiveDate>,
+}
+
+impl StaticSource {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl PriceSource for StaticSource {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    async fn fetch_rates(&self) -> eyre::Result<Vec<FxQuote>> {
+        let raw = std::fs::read_to_string(&self.path)?;
+        let entries: Vec<StaticRate> = serde_json::from_str(&raw)?;
+        let today = chrono::Utc::now().date_naive();
+
+        entries
+            .into_iter()
+            .map(|e| {
+                FxQuote {
+                    day: e.day.unwrap_or(today),
+                    base: e.base,
+                    quote: e.quote,
+                    rate: e.rate,
+                }
+                .normalized()
+            })
+            .collect()
+    }
+}
@@ -7,14 +7,15 @@ pub use sqlx::postgres::PgPool;
 
 use sqlx::postgres::PgPoolOptions;
 use std::time::Duration;
+use tempulse_core::config::DatabaseSettings;
 
-/// Connect to PostgreSQL with a production-ready connection pool.
-pub async fn connect(database_url: &str) -> Result<PgPool, sqlx::Error> {
+/// Connect to PostgreSQL with a pool sized by `settings`.
+pub async fn connect(settings: &DatabaseSettings) -> Result<PgPool, sqlx::Error> {
     PgPoolOptions::new()
-        .max_connections(20)
-        .min_connections(2)
-        .acquire_timeout(Duration::from_secs(10))
-        .idle_timeout(Duration::from_secs(300))
-        .connect(database_url)
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .acquire_timeout(Duration::from_secs(settings.acquire_timeout_secs))
+        .idle_timeout(Duration::from_secs(settings.idle_timeout_secs))
+        .connect(&settings.url)
         .await
 }
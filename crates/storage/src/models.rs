@@ -1,4 +1,4 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -6,6 +6,7 @@ use sqlx::FromRow;
 
 /// A tracked TIP-20 stablecoin.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Token {
     pub address: String,
     pub name: String,
@@ -21,8 +22,10 @@ pub struct Token {
 
 /// An immutable record of a token movement (transfer, mint, or burn).
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Transfer {
     pub id: i64,
+    pub chain_id: i64,
     pub token_address: String,
     pub from_address: String,
     pub to_address: String,
@@ -32,6 +35,9 @@ pub struct Transfer {
     pub transaction_hash: String,
     pub block_number: i64,
     pub log_index: i32,
+    /// Time of the transfer's block; time series bucket on this.
+    pub block_time: NaiveDateTime,
+    /// When the row was stored.
     pub created_at: NaiveDateTime,
 }
 
@@ -47,12 +53,14 @@ pub struct NewTransfer {
     pub transaction_hash: String,
     pub block_number: i64,
     pub log_index: i32,
+    pub block_time: NaiveDateTime,
 }
 
 // ─── Account ────────────────────────────────────────────────────────────────
 
 /// Current balance for an (address, token) pair.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Account {
     pub address: String,
     pub token_address: String,
@@ -71,6 +79,24 @@ pub struct IndexedBlock {
     pub timestamp: i64,
 }
 
+// ─── RawLog ─────────────────────────────────────────────────────────────────
+
+/// A log emitted by a TIP-20 token or the factory, stored as fetched.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RawLog {
+    pub block_number: i64,
+    pub log_index: i32,
+    pub transaction_hash: String,
+    pub address: String,
+    /// Hex topics, event signature first.
+    pub topics: Vec<String>,
+    /// Hex-encoded data.
+    pub data: String,
+    /// Block timestamp (unix seconds); ingestion reads the block header when
+    /// the RPC did not report it.
+    pub block_timestamp: Option<i64>,
+}
+
 // ─── HourlyStats ────────────────────────────────────────────────────────────
 
 /// Pre-aggregated hourly metrics for a token.
@@ -87,3 +113,285 @@ pub struct HourlyStats {
     pub unique_senders: i64,
     pub unique_receivers: i64,
 }
+
+// ─── FxRate ─────────────────────────────────────────────────────────────────
+
+/// A daily exchange rate: one unit of `base_currency` is worth `rate` units of `quote_currency`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FxRate {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub day: NaiveDate,
+    pub rate: String,
+    pub source: String,
+}
+
+// ─── Notifications ──────────────────────────────────────────────────────────
+
+/// Payload of a [`TRANSFERS_CHANNEL`](crate::repos::TRANSFERS_CHANNEL) notification:
+/// the chain and block range of a committed indexer batch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TransfersCommitted {
+    pub chain_id: i64,
+    pub from_block: i64,
+    pub to_block: i64,
+}
+
+// ─── HolderSnapshot ─────────────────────────────────────────────────────────
+
+/// A daily snapshot of how a token's balances are spread across holders.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct HolderSnapshot {
+    pub token_address: String,
+    pub day: NaiveDate,
+    pub holder_count: i64,
+    /// Sum of positive balances, in base units.
+    pub total_balance: String,
+    /// Gini coefficient of positive balances: 0 (equal) to 1 (one holder owns everything).
+    pub gini: String,
+    /// Fewest holders that together own more than half of `total_balance`.
+    pub nakamoto: i64,
+    pub top10_share: String,
+    pub top100_share: String,
+    pub new_holders: i64,
+    pub churned_holders: i64,
+    /// Holder counts per balance bucket; see [`HOLDER_BUCKETS`](crate::repos::HOLDER_BUCKETS).
+    pub balance_buckets: Vec<i64>,
+    pub taken_at: NaiveDateTime,
+}
+
+// ─── Velocity ───────────────────────────────────────────────────────────────
+
+/// One day of token velocity: transfer volume relative to the supply in circulation.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VelocityDay {
+    /// Currency of the token, or of the tokens summed across.
+    pub currency: String,
+    pub day: NaiveDate,
+    pub transfer_volume: String,
+    /// Mean of the supply at the start and end of the day: the current total
+    /// supply less what mints and burns changed since.
+    pub average_supply: String,
+    /// `transfer_volume / average_supply`; `None` while there is no supply.
+    pub velocity: Option<String>,
+}
+
+/// Velocity over a window of days.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VelocitySummary {
+    pub currency: String,
+    pub transfer_volume: String,
+    pub average_supply: String,
+    pub velocity: Option<String>,
+    /// How long a unit of supply sits between transfers on average, in days
+    /// (`window / velocity`); `None` without any transfer volume.
+    pub average_holding_days: Option<String>,
+}
+
+/// Outgoing transfer volume of a group of holders relative to what they hold.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CohortTurnover {
+    /// `top_10`, `top_11_100`, `rest`, or `exited` for senders now holding nothing.
+    pub cohort: String,
+    pub holders: i64,
+    pub balance: String,
+    pub outgoing_volume: String,
+    /// `outgoing_volume / balance`; `None` for `exited`.
+    pub turnover: Option<String>,
+}
+
+// ─── Issuance ───────────────────────────────────────────────────────────────
+
+/// Granularity of issuance time series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum IssuanceInterval {
+    #[default]
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+}
+
+impl IssuanceInterval {
+    /// The `DATE_TRUNC` field name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+
+    /// First day of the window of `periods` periods ending with the one containing `today`.
+    pub fn window_start(self, today: NaiveDate, periods: i64) -> NaiveDate {
+        let back = periods.max(1) - 1;
+        match self {
+            Self::Day => today - chrono::Days::new(back as u64),
+            Self::Week => {
+                today.week(chrono::Weekday::Mon).first_day() - chrono::Days::new(7 * back as u64)
+            }
+        }
+    }
+}
+
+/// Mint and burn totals of one day or week.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IssuancePeriod {
+    /// First day of the period.
+    pub period: NaiveDate,
+    pub mint_count: i64,
+    pub mint_volume: String,
+    pub burn_count: i64,
+    pub burn_volume: String,
+    /// `mint_volume - burn_volume`; negative when more was redeemed than issued.
+    pub net_issuance: String,
+}
+
+/// An address ranked by the volume minted to it or burned from it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IssuanceParty {
+    pub address: String,
+    pub volume: String,
+    pub count: i64,
+}
+
+/// A token's mint and burn totals over a window, in decimal units.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TokenIssuance {
+    pub token_address: String,
+    pub symbol: String,
+    pub mint_volume: String,
+    pub burn_volume: String,
+    pub net_issuance: String,
+}
+
+// ─── AddressLabel ───────────────────────────────────────────────────────────
+
+/// A human-readable name for a known address.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AddressLabel {
+    pub address: String,
+    pub name: String,
+    /// `exchange`, `issuer`, `treasury`, `bridge`, `contract`, `precompile` or `other`.
+    pub category: String,
+    /// `builtin`, `import` or `admin`.
+    pub source: String,
+    pub updated_at: NaiveDateTime,
+}
+
+// ─── EntityFlow ─────────────────────────────────────────────────────────────
+
+/// Total flow from one entity to another over a window, in decimal units.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EntityFlow {
+    pub source_entity: String,
+    pub target_entity: String,
+    pub volume: String,
+    pub transfer_count: i64,
+}
+
+// ─── Networks ───────────────────────────────────────────────────────────────
+
+/// A chain registered by an indexer writing to this database.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Network {
+    pub chain_id: i64,
+    /// Profile name clients pass as `?network=`.
+    pub name: String,
+    pub registered_at: NaiveDateTime,
+}
+
+// ─── Indexer Status ─────────────────────────────────────────────────────────
+
+/// A chain reorganisation the indexer rolled back.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Reorg {
+    /// The cursor was reset to this block.
+    pub fork_block: i64,
+    /// Blocks rolled back.
+    pub depth: i64,
+    pub detected_at: NaiveDateTime,
+}
+
+/// Indexer progress as recorded in the database.
+#[derive(Debug, Clone)]
+pub struct IndexerStatus {
+    pub last_indexed_block: i64,
+    /// Block timestamp (unix seconds) of the newest indexed block, if any.
+    pub last_indexed_timestamp: Option<i64>,
+    /// Chain head as last read by the indexer; `None` until it first polls.
+    pub chain_head: Option<i64>,
+    pub chain_head_seen_at: Option<NaiveDateTime>,
+    pub last_reorg: Option<Reorg>,
+}
+
+// ─── Webhooks ───────────────────────────────────────────────────────────────
+
+/// An outbound webhook subscription. `None` filters match every transfer.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookSubscription {
+    pub id: i64,
+    /// The network whose transfers it matches.
+    pub chain_id: i64,
+    pub url: String,
+    /// HMAC-SHA256 signing key. Only returned to the client on creation.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub description: String,
+    pub token_address: Option<String>,
+    /// Matches either the sender or the receiver.
+    pub address: Option<String>,
+    /// Minimum amount in base units.
+    pub min_amount: Option<String>,
+    pub event_type: Option<String>,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+}
+
+/// The user-editable fields of a [`WebhookSubscription`].
+#[derive(Debug, Clone)]
+pub struct WebhookSubscriptionSpec {
+    pub url: String,
+    pub description: String,
+    pub token_address: Option<String>,
+    pub address: Option<String>,
+    pub min_amount: Option<String>,
+    pub event_type: Option<String>,
+    pub active: bool,
+}
+
+/// A queued delivery joined with its subscription's endpoint and key.
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingWebhookDelivery {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub transfer_id: i64,
+    pub payload: String,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// A delivery that exhausted its retries.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDeadLetter {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub transfer_id: i64,
+    pub payload: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub failed_at: NaiveDateTime,
+}
@@ -1,17 +1,91 @@
-use chrono::NaiveDateTime;
-use sqlx::{Executor, PgPool, Postgres, QueryBuilder};
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::{
+    Execute, Executor, FromRow, PgConnection, PgPool, Postgres, QueryBuilder, Row,
+    postgres::PgQueryResult,
+};
+use tempulse_core::{address::TokenAddress, entities::Entity, labels::LabelEntry};
 
 use crate::models::*;
 
+// ─── Tracing ────────────────────────────────────────────────────────────────
+//
+// Every query function runs in a span with `db.statement` and `db.rows`
+// fields, filled in by each statement it runs; one that runs several
+// statements ends up with the last one's.
+
+/// Records a query's SQL as `db.statement` on the current span.
+trait Traced: Sized {
+    fn traced(self) -> Self;
+}
+
+impl<'q, Q: Execute<'q, Postgres>> Traced for Q {
+    fn traced(self) -> Self {
+        tracing::Span::current().record("db.statement", self.sql());
+        self
+    }
+}
+
+/// Rows a statement returned or changed.
+trait RowCount {
+    fn row_count(&self) -> u64;
+}
+
+impl<T> RowCount for Vec<T> {
+    fn row_count(&self) -> u64 {
+        self.len() as u64
+    }
+}
+
+impl<T> RowCount for Option<T> {
+    fn row_count(&self) -> u64 {
+        self.is_some().into()
+    }
+}
+
+impl RowCount for PgQueryResult {
+    fn row_count(&self) -> u64 {
+        self.rows_affected()
+    }
+}
+
+/// Records a statement's [`RowCount`] as `db.rows` on the current span.
+trait RecordRows: Sized {
+    fn record_rows(self) -> Self;
+}
+
+impl<T: RowCount> RecordRows for Result<T, sqlx::Error> {
+    fn record_rows(self) -> Self {
+        if let Ok(rows) = &self {
+            tracing::Span::current().record("db.rows", rows.row_count());
+        }
+        self
+    }
+}
+
+/// Records the single row of a `fetch_one` as `db.rows` on the current span.
+trait RecordRow: Sized {
+    fn record_row(self) -> Self;
+}
+
+impl<T> RecordRow for Result<T, sqlx::Error> {
+    fn record_row(self) -> Self {
+        if self.is_ok() {
+            tracing::Span::current().record("db.rows", 1);
+        }
+        self
+    }
+}
+
 // ─── Token Queries ──────────────────────────────────────────────────────────
 
 /// Insert a new token (ignore if already exists).
-pub async fn insert_token(pool: &PgPool, token: &Token) -> Result<(), sqlx::Error> {
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn insert_token(pool: &PgPool, chain_id: i64, token: &Token) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        INSERT INTO tokens (address, name, symbol, decimals, currency, total_supply, created_at_block, created_at_tx)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-        ON CONFLICT (address) DO NOTHING
+        INSERT INTO tokens (chain_id, address, name, symbol, decimals, currency, total_supply, created_at_block, created_at_tx)
+        VALUES ($9, $1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (chain_id, address) DO NOTHING
         "#,
     )
     .bind(&token.address)
@@ -22,55 +96,135 @@ pub async fn insert_token(pool: &PgPool, token: &Token) -> Result<(), sqlx::Erro
     .bind(&token.total_supply)
     .bind(token.created_at_block)
     .bind(&token.created_at_tx)
+    .bind(chain_id)
+    .traced()
     .execute(pool)
-    .await?;
+    .await
+    .record_rows()?;
     Ok(())
 }
 
 /// Get all tracked tokens.
-pub async fn get_all_tokens(pool: &PgPool, limit: i64) -> Result<Vec<Token>, sqlx::Error> {
-    sqlx::query_as::<_, Token>("SELECT * FROM tokens ORDER BY symbol LIMIT $1")
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_all_tokens(
+    pool: &PgPool,
+    chain_id: i64,
+    limit: i64,
+) -> Result<Vec<Token>, sqlx::Error> {
+    sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $2 ORDER BY symbol LIMIT $1")
         .bind(limit)
+        .bind(chain_id)
+        .traced()
         .fetch_all(pool)
         .await
+        .record_rows()
 }
 
 /// Get a single token by address.
-pub async fn get_token(pool: &PgPool, address: &str) -> Result<Option<Token>, sqlx::Error> {
-    sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE address = $1")
-        .bind(address)
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token(
+    pool: &PgPool,
+    chain_id: i64,
+    address: &TokenAddress,
+) -> Result<Option<Token>, sqlx::Error> {
+    sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $1 AND address = $2")
+        .bind(chain_id)
+        .bind(address.as_str())
+        .traced()
         .fetch_optional(pool)
         .await
+        .record_rows()
+}
+
+/// Get one page of tracked tokens ordered by symbol.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_tokens_page(
+    pool: &PgPool,
+    chain_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Token>, sqlx::Error> {
+    sqlx::query_as::<_, Token>(
+        "SELECT * FROM tokens WHERE chain_id = $3 ORDER BY symbol, address LIMIT $1 OFFSET $2",
+    )
+    .bind(limit)
+    .bind(offset)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Get every token whose address is in `addresses` (unknown addresses are skipped).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_tokens_by_address(
+    pool: &PgPool,
+    chain_id: i64,
+    addresses: &[String],
+) -> Result<Vec<Token>, sqlx::Error> {
+    sqlx::query_as::<_, Token>("SELECT * FROM tokens WHERE chain_id = $1 AND address = ANY($2)")
+        .bind(chain_id)
+        .bind(addresses)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
 }
 
 /// Get the count of tracked tokens (no allocation — just a scalar).
-pub async fn get_token_count(pool: &PgPool) -> Result<i64, sqlx::Error> {
-    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tokens")
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token_count(pool: &PgPool, chain_id: i64) -> Result<i64, sqlx::Error> {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tokens WHERE chain_id = $1")
+        .bind(chain_id)
+        .traced()
         .fetch_one(pool)
-        .await?;
+        .await
+        .record_row()?;
     Ok(row.0)
 }
 
+/// Get the `decimals` of every tracked token as (address, decimals) pairs.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token_decimals(
+    pool: &PgPool,
+    chain_id: i64,
+) -> Result<Vec<(String, i16)>, sqlx::Error> {
+    sqlx::query_as("SELECT address, decimals FROM tokens WHERE chain_id = $1")
+        .bind(chain_id)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
 /// Update total supply for a token.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn update_total_supply(
     pool: &PgPool,
+    chain_id: i64,
     token_address: &str,
     new_supply: &str,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query("UPDATE tokens SET total_supply = $1 WHERE address = $2")
+    sqlx::query("UPDATE tokens SET total_supply = $1 WHERE chain_id = $2 AND address = $3")
         .bind(new_supply)
+        .bind(chain_id)
         .bind(token_address)
+        .traced()
         .execute(pool)
-        .await?;
+        .await
+        .record_rows()?;
     Ok(())
 }
 
 // ─── Transfer Queries ───────────────────────────────────────────────────────
 
 /// Insert a batch of transfers using a single multi-value INSERT.
-/// Skips duplicates on (transaction_hash, log_index).
+/// Skips duplicates on (chain_id, transaction_hash, log_index).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn insert_transfers_batch<'e, E>(
     executor: E,
+    chain_id: i64,
     transfers: &[NewTransfer],
 ) -> Result<(), sqlx::Error>
 where
@@ -82,11 +236,12 @@ where
 
     // Build: INSERT INTO transfers (...) VALUES (...), (...), ... ON CONFLICT DO NOTHING
     let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(
-        "INSERT INTO transfers (token_address, from_address, to_address, amount, memo, event_type, transaction_hash, block_number, log_index) ",
+        "INSERT INTO transfers (chain_id, token_address, from_address, to_address, amount, memo, event_type, transaction_hash, block_number, log_index, block_time) ",
     );
 
     qb.push_values(transfers, |mut b, t| {
-        b.push_bind(&t.token_address)
+        b.push_bind(chain_id)
+            .push_bind(&t.token_address)
             .push_bind(&t.from_address)
             .push_bind(&t.to_address)
             .push_bind(&t.amount)
@@ -94,37 +249,89 @@ where
             .push_bind(&t.event_type)
             .push_bind(&t.transaction_hash)
             .push_bind(t.block_number)
-            .push_bind(t.log_index);
+            .push_bind(t.log_index)
+            .push_bind(t.block_time);
     });
 
-    qb.push(" ON CONFLICT (transaction_hash, log_index) DO NOTHING");
-    qb.build().execute(executor).await?;
+    qb.push(" ON CONFLICT (chain_id, transaction_hash, log_index) DO NOTHING");
+    qb.build().traced().execute(executor).await.record_rows()?;
     Ok(())
 }
 
 /// Get the most recent transfers across all tokens.
-pub async fn get_recent_transfers(pool: &PgPool, limit: i64) -> Result<Vec<Transfer>, sqlx::Error> {
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_recent_transfers(
+    pool: &PgPool,
+    chain_id: i64,
+    limit: i64,
+) -> Result<Vec<Transfer>, sqlx::Error> {
     sqlx::query_as::<_, Transfer>(
-        "SELECT * FROM transfers ORDER BY block_number DESC, log_index DESC LIMIT $1",
+        "SELECT * FROM transfers WHERE chain_id = $2 ORDER BY block_number DESC, log_index DESC LIMIT $1",
     )
     .bind(limit)
+    .bind(chain_id)
+    .traced()
     .fetch_all(pool)
     .await
+    .record_rows()
 }
 
 /// Get transfers for a specific token.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn get_token_transfers(
     pool: &PgPool,
-    token_address: &str,
+    chain_id: i64,
+    token_address: &TokenAddress,
+    limit: i64,
+) -> Result<Vec<Transfer>, sqlx::Error> {
+    sqlx::query_as::<_, Transfer>(
+        "SELECT * FROM transfers WHERE chain_id = $3 AND token_address = $1 ORDER BY block_number DESC, log_index DESC LIMIT $2",
+    )
+    .bind(token_address.as_str())
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Get a page of transfers, newest first, using keyset pagination.
+///
+/// `token_address` and `address` (sender or receiver) are optional filters.
+/// `before` is the `(block_number, log_index)` of the last transfer on the
+/// previous page; only strictly older transfers are returned.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_transfers_page(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: Option<&str>,
+    address: Option<&str>,
+    before: Option<(i64, i32)>,
     limit: i64,
 ) -> Result<Vec<Transfer>, sqlx::Error> {
+    let (before_block, before_log) = before.unzip();
     sqlx::query_as::<_, Transfer>(
-        "SELECT * FROM transfers WHERE token_address = $1 ORDER BY block_number DESC, log_index DESC LIMIT $2",
+        r#"
+        SELECT * FROM transfers
+        WHERE chain_id = $6
+          AND ($1::TEXT IS NULL OR token_address = $1)
+          AND ($2::TEXT IS NULL OR from_address = $2 OR to_address = $2)
+          AND ($3::BIGINT IS NULL OR (block_number, log_index) < ($3, $4::INT))
+        ORDER BY block_number DESC, log_index DESC
+        LIMIT $5
+        "#,
     )
     .bind(token_address)
+    .bind(address)
+    .bind(before_block)
+    .bind(before_log)
     .bind(limit)
+    .bind(chain_id)
+    .traced()
     .fetch_all(pool)
     .await
+    .record_rows()
 }
 
 // ─── Total Supply ───────────────────────────────────────────────────────────
@@ -132,8 +339,10 @@ pub async fn get_token_transfers(
 /// Update a token's total_supply in response to mint or burn events.
 /// For mints: `is_mint = true` → supply increases.
 /// For burns: `is_mint = false` → supply decreases (clamped at 0).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn update_total_supply_on_event<'e, E>(
     executor: E,
+    chain_id: i64,
     token_address: &str,
     amount: &str,
     is_mint: bool,
@@ -146,45 +355,83 @@ where
             r#"
             UPDATE tokens
             SET total_supply = (CAST(total_supply AS NUMERIC) + CAST($1 AS NUMERIC))::TEXT
-            WHERE address = $2
+            WHERE chain_id = $3 AND address = $2
             "#,
         )
         .bind(amount)
         .bind(token_address)
+        .bind(chain_id)
+        .traced()
         .execute(executor)
-        .await?;
+        .await
+        .record_rows()?;
     } else {
         sqlx::query(
             r#"
             UPDATE tokens
             SET total_supply = GREATEST(0, CAST(total_supply AS NUMERIC) - CAST($1 AS NUMERIC))::TEXT
-            WHERE address = $2
+            WHERE chain_id = $3 AND address = $2
             "#,
         )
         .bind(amount)
         .bind(token_address)
+        .bind(chain_id)
+        .traced()
         .execute(executor)
-        .await?;
+        .await
+        .record_rows()?;
     }
     Ok(())
 }
 
+/// Subtract the mints and burns after `fork_block` from token supplies.
+/// Must run before those transfers are deleted.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn revert_total_supply(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE tokens tk
+        SET total_supply = GREATEST(0, CAST(tk.total_supply AS NUMERIC) - s.net)::TEXT
+        FROM (
+            SELECT token_address,
+                   SUM(CASE WHEN event_type = 'mint' THEN CAST(amount AS NUMERIC) ELSE -CAST(amount AS NUMERIC) END) AS net
+            FROM transfers
+            WHERE chain_id = $2 AND block_number > $1 AND event_type IN ('mint', 'burn')
+            GROUP BY token_address
+        ) s
+        WHERE tk.chain_id = $2 AND tk.address = s.token_address
+        "#,
+    )
+    .bind(fork_block)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
 // ─── Hourly Stats ───────────────────────────────────────────────────────────
 
-/// Upsert a row into hourly_stats, incrementing counters and volumes.
+/// Upsert a row into hourly_stats, counting `transfer` into `hour`.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn upsert_hourly_stats<'e, E>(
     executor: E,
-    token_address: &str,
+    chain_id: i64,
+    transfer: &NewTransfer,
     hour: NaiveDateTime,
-    event_type: &str,
-    amount: &str,
-    sender: &str,
-    receiver: &str,
 ) -> Result<(), sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
-    let (tc, tv, mc, mv, bc, bv) = match event_type {
+    let amount = transfer.amount.as_str();
+    let sender = transfer.from_address.as_str();
+    let receiver = transfer.to_address.as_str();
+    let (tc, tv, mc, mv, bc, bv) = match transfer.event_type.as_str() {
         "mint" => (0i64, "0", 1i64, amount, 0i64, "0"),
         "burn" => (0, "0", 0, "0", 1, amount),
         _ => (1, amount, 0, "0", 0, "0"),
@@ -205,9 +452,9 @@ where
 
     sqlx::query(
         r#"
-        INSERT INTO hourly_stats (token_address, hour, transfer_count, transfer_volume, mint_count, mint_volume, burn_count, burn_volume, unique_senders, unique_receivers)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-        ON CONFLICT (token_address, hour) DO UPDATE SET
+        INSERT INTO hourly_stats (chain_id, token_address, hour, transfer_count, transfer_volume, mint_count, mint_volume, burn_count, burn_volume, unique_senders, unique_receivers)
+        VALUES ($11, $1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT (chain_id, token_address, hour) DO UPDATE SET
             transfer_count = hourly_stats.transfer_count + $3,
             transfer_volume = (CAST(hourly_stats.transfer_volume AS NUMERIC) + CAST($4 AS NUMERIC))::TEXT,
             mint_count = hourly_stats.mint_count + $5,
@@ -218,7 +465,7 @@ where
             unique_receivers = hourly_stats.unique_receivers + $10
         "#,
     )
-    .bind(token_address)
+    .bind(&transfer.token_address)
     .bind(hour)
     .bind(tc)
     .bind(tv)
@@ -228,19 +475,570 @@ where
     .bind(bv)
     .bind(is_sender)
     .bind(is_receiver)
+    .bind(chain_id)
+    .traced()
     .execute(executor)
-    .await?;
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Take the transfers after `fork_block` back out of the hours they were
+/// counted in. Must run before those transfers are deleted.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn revert_hourly_stats(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    fold_hourly_stats(conn, chain_id, -1, HourlyEvents::StoredAfter(fork_block)).await
+}
+
+// ─── Daily / Monthly Rollups ────────────────────────────────────────────────
+
+/// A rollup table and the membership table behind its `active_addresses`.
+struct Rollup {
+    stats: &'static str,
+    members: &'static str,
+    /// Name of the period column.
+    period: &'static str,
+    /// Maps a transfer `t` onto its period, by the time of its block.
+    bucket: &'static str,
+    /// Maps the date `$2` onto the period containing it.
+    start: &'static str,
+}
+
+const ROLLUPS: [Rollup; 2] = [
+    Rollup {
+        stats: "daily_stats",
+        members: "daily_active_addresses",
+        period: "day",
+        bucket: "DATE(t.block_time)",
+        start: "$2::DATE",
+    },
+    Rollup {
+        stats: "monthly_stats",
+        members: "monthly_active_addresses",
+        period: "month",
+        bucket: "DATE_TRUNC('month', t.block_time)::DATE",
+        start: "DATE_TRUNC('month', $2::DATE)::DATE",
+    },
+];
+
+/// Which transfers of chain `$1` a rollup pass folds in.
+#[derive(Clone, Copy)]
+enum RollupScope<'a> {
+    /// Blocks `$2..=$3`.
+    Blocks(i64, i64),
+    /// Every period starting at the one containing `$2`.
+    Since(NaiveDate),
+    /// Token `$3`, every period starting at the one containing `$2`.
+    TokenSince(&'a str, NaiveDate),
+}
+
+impl RollupScope<'_> {
+    fn filter(&self, rollup: &Rollup) -> String {
+        match self {
+            Self::Blocks(..) => "t.chain_id = $1 AND t.block_number BETWEEN $2 AND $3".to_string(),
+            Self::Since(_) => format!("t.chain_id = $1 AND t.block_time >= {}", rollup.start),
+            Self::TokenSince(..) => format!(
+                "t.chain_id = $1 AND t.block_time >= {} AND t.token_address = $3",
+                rollup.start
+            ),
+        }
+    }
+}
+
+/// Add the transfers in `scope` onto one rollup's counters, volumes and active-address sets.
+async fn fold_rollup(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    rollup: &Rollup,
+    scope: RollupScope<'_>,
+) -> Result<(), sqlx::Error> {
+    let Rollup {
+        stats,
+        members,
+        period,
+        bucket,
+        ..
+    } = rollup;
+    let filter = scope.filter(rollup);
+
+    let totals = format!(
+        r#"
+        INSERT INTO {stats} (chain_id, token_address, {period}, transfer_count, transfer_volume, mint_count, mint_volume, burn_count, burn_volume, net_issuance)
+        SELECT $1, token_address, bucket,
+               transfer_count, transfer_volume::TEXT, mint_count, mint_volume::TEXT,
+               burn_count, burn_volume::TEXT, (mint_volume - burn_volume)::TEXT
+        FROM (
+            SELECT t.token_address, {bucket} AS bucket,
+                   COUNT(*) FILTER (WHERE t.event_type = 'transfer') AS transfer_count,
+                   COALESCE(SUM(CAST(t.amount AS NUMERIC)) FILTER (WHERE t.event_type = 'transfer'), 0) AS transfer_volume,
+                   COUNT(*) FILTER (WHERE t.event_type = 'mint') AS mint_count,
+                   COALESCE(SUM(CAST(t.amount AS NUMERIC)) FILTER (WHERE t.event_type = 'mint'), 0) AS mint_volume,
+                   COUNT(*) FILTER (WHERE t.event_type = 'burn') AS burn_count,
+                   COALESCE(SUM(CAST(t.amount AS NUMERIC)) FILTER (WHERE t.event_type = 'burn'), 0) AS burn_volume
+            FROM transfers t
+            WHERE {filter}
+            GROUP BY t.token_address, bucket
+        ) s
+        ON CONFLICT (chain_id, token_address, {period}) DO UPDATE SET
+            transfer_count = {stats}.transfer_count + EXCLUDED.transfer_count,
+            transfer_volume = (CAST({stats}.transfer_volume AS NUMERIC) + CAST(EXCLUDED.transfer_volume AS NUMERIC))::TEXT,
+            mint_count = {stats}.mint_count + EXCLUDED.mint_count,
+            mint_volume = (CAST({stats}.mint_volume AS NUMERIC) + CAST(EXCLUDED.mint_volume AS NUMERIC))::TEXT,
+            burn_count = {stats}.burn_count + EXCLUDED.burn_count,
+            burn_volume = (CAST({stats}.burn_volume AS NUMERIC) + CAST(EXCLUDED.burn_volume AS NUMERIC))::TEXT,
+            net_issuance = (CAST({stats}.net_issuance AS NUMERIC) + CAST(EXCLUDED.net_issuance AS NUMERIC))::TEXT
+        "#
+    );
+
+    // Only addresses new to their (token, period) set bump `active_addresses`.
+    let active = format!(
+        r#"
+        WITH added AS (
+            INSERT INTO {members} (chain_id, token_address, {period}, address)
+            SELECT DISTINCT $1, t.token_address, {bucket}, a.address
+            FROM transfers t
+            CROSS JOIN LATERAL (VALUES (t.from_address), (t.to_address)) AS a(address)
+            WHERE {filter} AND a.address <> '0x0000000000000000000000000000000000000000'
+            ON CONFLICT DO NOTHING
+            RETURNING token_address, {period}
+        )
+        UPDATE {stats} s SET active_addresses = s.active_addresses + n.added
+        FROM (
+            SELECT token_address, {period}, COUNT(*) AS added
+            FROM added
+            GROUP BY token_address, {period}
+        ) n
+        WHERE s.chain_id = $1 AND s.token_address = n.token_address AND s.{period} = n.{period}
+        "#
+    );
+
+    for sql in [&totals, &active] {
+        let query = sqlx::query(sql).bind(chain_id);
+        let query = match scope {
+            RollupScope::Blocks(from, to) => query.bind(from).bind(to),
+            RollupScope::Since(day) => query.bind(day),
+            RollupScope::TokenSince(token, day) => query.bind(day).bind(token),
+        };
+        query.traced().execute(&mut *conn).await.record_rows()?;
+    }
+    Ok(())
+}
+
+/// Fold the transfers of blocks `from_block..=to_block` into daily_stats and monthly_stats.
+///
+/// Must run in the transaction that inserted those transfers, exactly once per range.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn apply_rollups(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<(), sqlx::Error> {
+    for rollup in &ROLLUPS {
+        fold_rollup(
+            conn,
+            chain_id,
+            rollup,
+            RollupScope::Blocks(from_block, to_block),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Recompute every daily and monthly rollup from the period containing `day` onwards.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn rebuild_rollups_since(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    day: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    for rollup in &ROLLUPS {
+        for table in [rollup.stats, rollup.members] {
+            sqlx::query(&format!(
+                "DELETE FROM {table} WHERE chain_id = $1 AND {} >= {}",
+                rollup.period, rollup.start
+            ))
+            .bind(chain_id)
+            .bind(day)
+            .traced()
+            .execute(&mut *conn)
+            .await
+            .record_rows()?;
+        }
+        fold_rollup(conn, chain_id, rollup, RollupScope::Since(day)).await?;
+    }
     Ok(())
 }
 
+/// Recompute one token's daily and monthly rollups from the period containing `day` onwards.
+async fn rebuild_token_rollups_since(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    token: &str,
+    day: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    for rollup in &ROLLUPS {
+        for table in [rollup.stats, rollup.members] {
+            sqlx::query(&format!(
+                "DELETE FROM {table} WHERE chain_id = $1 AND {} >= {} AND token_address = $3",
+                rollup.period, rollup.start
+            ))
+            .bind(chain_id)
+            .bind(day)
+            .bind(token)
+            .traced()
+            .execute(&mut *conn)
+            .await
+            .record_rows()?;
+        }
+        fold_rollup(conn, chain_id, rollup, RollupScope::TokenSince(token, day)).await?;
+    }
+    Ok(())
+}
+
+// ─── Global Counters ────────────────────────────────────────────────────────
+
+/// Add (`sign = 1`) or subtract (`sign = -1`) the transfers of blocks
+/// `from_block..=to_block` (of one token, if given) to/from token_totals and global_totals.
+async fn fold_totals(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+    sign: i64,
+    token: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO token_totals (chain_id, token_address, total_volume, transfer_count)
+        SELECT $4, token_address, ($3 * SUM(CAST(amount AS NUMERIC)))::TEXT, $3 * COUNT(*)
+        FROM transfers
+        WHERE chain_id = $4 AND block_number BETWEEN $1 AND $2
+          AND ($5::TEXT IS NULL OR token_address = $5)
+        GROUP BY token_address
+        ON CONFLICT (chain_id, token_address) DO UPDATE SET
+            total_volume = (CAST(token_totals.total_volume AS NUMERIC) + CAST(EXCLUDED.total_volume AS NUMERIC))::TEXT,
+            transfer_count = token_totals.transfer_count + EXCLUDED.transfer_count
+        "#,
+    )
+    .bind(from_block)
+    .bind(to_block)
+    .bind(sign)
+    .bind(chain_id)
+    .bind(token)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO global_totals (chain_id, total_volume, total_transfers)
+        SELECT $4, COALESCE($3 * SUM(CAST(amount AS NUMERIC)), 0)::TEXT, $3 * COUNT(*)
+        FROM transfers
+        WHERE chain_id = $4 AND block_number BETWEEN $1 AND $2
+          AND ($5::TEXT IS NULL OR token_address = $5)
+        ON CONFLICT (chain_id) DO UPDATE SET
+            total_volume = (CAST(global_totals.total_volume AS NUMERIC) + CAST(EXCLUDED.total_volume AS NUMERIC))::TEXT,
+            total_transfers = global_totals.total_transfers + EXCLUDED.total_transfers
+        "#,
+    )
+    .bind(from_block)
+    .bind(to_block)
+    .bind(sign)
+    .bind(chain_id)
+    .bind(token)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Record the first-seen block of addresses in the transfers of blocks
+/// `from_block..=to_block`, counting new ones in global_totals.
+async fn add_known_addresses(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        WITH added AS (
+            INSERT INTO known_addresses (chain_id, address, first_seen_block)
+            SELECT $3, a.address, MIN(t.block_number)
+            FROM transfers t
+            CROSS JOIN LATERAL (VALUES (t.from_address), (t.to_address)) AS a(address)
+            WHERE t.chain_id = $3 AND t.block_number BETWEEN $1 AND $2
+              AND a.address <> '0x0000000000000000000000000000000000000000'
+            GROUP BY a.address
+            ON CONFLICT DO NOTHING
+            RETURNING 1
+        )
+        UPDATE global_totals SET active_addresses = active_addresses + (SELECT COUNT(*) FROM added)
+        WHERE chain_id = $3
+        "#,
+    )
+    .bind(from_block)
+    .bind(to_block)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Add the transfers of blocks `from_block..=to_block` to the global counters
+/// and record their first-seen addresses.
+///
+/// Must run in the transaction that inserted those transfers, exactly once per range.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn apply_global_counters(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<(), sqlx::Error> {
+    fold_totals(conn, chain_id, from_block, to_block, 1, None).await?;
+    add_known_addresses(conn, chain_id, from_block, to_block).await
+}
+
+/// Subtract the transfers after `fork_block` from the global counters and forget
+/// addresses first seen there. Must run before those transfers are deleted.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn revert_global_counters(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    fold_totals(conn, chain_id, fork_block + 1, i64::MAX, -1, None).await?;
+
+    sqlx::query(
+        r#"
+        WITH removed AS (
+            DELETE FROM known_addresses WHERE chain_id = $2 AND first_seen_block > $1
+            RETURNING 1
+        )
+        UPDATE global_totals SET active_addresses = active_addresses - (SELECT COUNT(*) FROM removed)
+        WHERE chain_id = $2
+        "#,
+    )
+    .bind(fork_block)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+// ─── Entity Flows ───────────────────────────────────────────────────────────
+
+/// Flow endpoint of addresses that belong to no entity.
+pub const UNKNOWN_ENTITY: &str = "(unknown)";
+
+/// Flow endpoint of the zero address, i.e. the other side of mints and burns.
+pub const MINT_BURN_ENTITY: &str = "(mint/burn)";
+
+/// Add (`sign = 1`) or subtract (`sign = -1`) the transfers of blocks
+/// `from_block..=to_block` (of one token, if given) to/from entity_flows.
+async fn fold_entity_flows(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+    sign: i64,
+    token: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO entity_flows (chain_id, token_address, day, source_entity, target_entity, volume, transfer_count)
+        SELECT $6, t.token_address, DATE(t.block_time), e.source, e.target,
+               ($3 * SUM(CAST(t.amount AS NUMERIC)))::TEXT, $3 * COUNT(*)
+        FROM transfers t
+        LEFT JOIN entity_addresses fe ON fe.address = t.from_address
+        LEFT JOIN entity_addresses te ON te.address = t.to_address
+        CROSS JOIN LATERAL (
+            SELECT COALESCE(fe.entity, CASE WHEN t.from_address = '0x0000000000000000000000000000000000000000' THEN $5 ELSE $4 END) AS source,
+                   COALESCE(te.entity, CASE WHEN t.to_address = '0x0000000000000000000000000000000000000000' THEN $5 ELSE $4 END) AS target
+        ) e
+        WHERE t.chain_id = $6 AND t.block_number BETWEEN $1 AND $2
+          AND ($7::TEXT IS NULL OR t.token_address = $7)
+        GROUP BY 2, 3, 4, 5
+        ON CONFLICT (chain_id, day, token_address, source_entity, target_entity) DO UPDATE SET
+            volume = (CAST(entity_flows.volume AS NUMERIC) + CAST(EXCLUDED.volume AS NUMERIC))::TEXT,
+            transfer_count = entity_flows.transfer_count + EXCLUDED.transfer_count
+        "#,
+    )
+    .bind(from_block)
+    .bind(to_block)
+    .bind(sign)
+    .bind(UNKNOWN_ENTITY)
+    .bind(MINT_BURN_ENTITY)
+    .bind(chain_id)
+    .bind(token)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+
+    if sign < 0 {
+        sqlx::query("DELETE FROM entity_flows WHERE chain_id = $1 AND transfer_count = 0")
+            .bind(chain_id)
+            .traced()
+            .execute(&mut *conn)
+            .await
+            .record_rows()?;
+    }
+    Ok(())
+}
+
+/// Add the transfers of blocks `from_block..=to_block` to entity_flows.
+///
+/// Must run in the transaction that inserted those transfers, exactly once per range.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn apply_entity_flows(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<(), sqlx::Error> {
+    fold_entity_flows(conn, chain_id, from_block, to_block, 1, None).await
+}
+
+/// Subtract the transfers after `fork_block` from entity_flows.
+/// Must run before those transfers are deleted.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn revert_entity_flows(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    fold_entity_flows(conn, chain_id, fork_block + 1, i64::MAX, -1, None).await
+}
+
+/// Replace the entity membership and, if it changed, rebuild entity_flows from
+/// every stored transfer of every network. Returns whether anything changed.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn replace_entities(pool: &PgPool, entities: &[Entity]) -> Result<bool, sqlx::Error> {
+    let mut wanted: Vec<(String, String)> = entities
+        .iter()
+        .flat_map(|e| {
+            e.addresses
+                .iter()
+                .map(|a| (a.as_str().to_string(), e.name.clone()))
+        })
+        .collect();
+    wanted.sort();
+    wanted.dedup();
+
+    let mut tx = pool.begin().await?;
+    // Serializes with the indexer's batch transactions, which fold into entity_flows.
+    sqlx::query("LOCK TABLE entity_flows IN EXCLUSIVE MODE")
+        .traced()
+        .execute(&mut *tx)
+        .await
+        .record_rows()?;
+
+    let mut current: Vec<(String, String)> =
+        sqlx::query_as("SELECT address, entity FROM entity_addresses")
+            .traced()
+            .fetch_all(&mut *tx)
+            .await
+            .record_rows()?;
+    current.sort();
+    if current == wanted {
+        return Ok(false);
+    }
+
+    sqlx::query("DELETE FROM entity_addresses")
+        .traced()
+        .execute(&mut *tx)
+        .await
+        .record_rows()?;
+    for chunk in wanted.chunks(10_000) {
+        let mut qb: QueryBuilder<Postgres> =
+            QueryBuilder::new("INSERT INTO entity_addresses (address, entity) ");
+        qb.push_values(chunk, |mut b, (address, entity)| {
+            b.push_bind(address).push_bind(entity);
+        });
+        qb.build().traced().execute(&mut *tx).await.record_rows()?;
+    }
+
+    sqlx::query("DELETE FROM entity_flows")
+        .traced()
+        .execute(&mut *tx)
+        .await
+        .record_rows()?;
+    let chains: Vec<(i64,)> = sqlx::query_as("SELECT DISTINCT chain_id FROM tokens")
+        .traced()
+        .fetch_all(&mut *tx)
+        .await
+        .record_rows()?;
+    for (chain_id,) in chains {
+        fold_entity_flows(&mut tx, chain_id, 0, i64::MAX, 1, None).await?;
+    }
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Entity-to-entity flows on the days `since..=until`, optionally limited to
+/// some tokens, largest first. Amounts are scaled by each token's `decimals` before
+/// summing. Flows between two unknown addresses are left out.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_entity_flows(
+    pool: &PgPool,
+    chain_id: i64,
+    since: NaiveDate,
+    until: NaiveDate,
+    token_addresses: Option<&[String]>,
+    limit: i64,
+) -> Result<Vec<EntityFlow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT f.source_entity, f.target_entity,
+               TRIM_SCALE(SUM(CAST(f.volume AS NUMERIC) / POWER(10::NUMERIC, t.decimals)))::TEXT AS volume,
+               SUM(f.transfer_count)::BIGINT AS transfer_count
+        FROM entity_flows f
+        JOIN tokens t ON t.chain_id = f.chain_id AND t.address = f.token_address
+        WHERE f.chain_id = $5 AND f.day BETWEEN $1 AND $6
+          AND ($2::TEXT[] IS NULL OR f.token_address = ANY($2))
+          AND NOT (f.source_entity = $3 AND f.target_entity = $3)
+        GROUP BY f.source_entity, f.target_entity
+        ORDER BY SUM(CAST(f.volume AS NUMERIC) / POWER(10::NUMERIC, t.decimals)) DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(since)
+    .bind(token_addresses)
+    .bind(UNKNOWN_ENTITY)
+    .bind(limit)
+    .bind(chain_id)
+    .bind(until)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
 // ─── Account Queries ────────────────────────────────────────────────────────
 
 /// Upsert an account balance by adding a delta.
 /// For mints: address = recipient, delta > 0.
 /// For burns: address = sender, delta is subtracted.
 /// For transfers: called twice — subtract from sender, add to receiver.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn upsert_account_balance<'e, E>(
     executor: E,
+    chain_id: i64,
     address: &str,
     token_address: &str,
     amount: &str,
@@ -255,9 +1053,9 @@ where
     if is_add {
         sqlx::query(
             r#"
-            INSERT INTO accounts (address, token_address, balance, updated_at_block)
-            VALUES ($1, $2, $3, $4)
-            ON CONFLICT (address, token_address) DO UPDATE
+            INSERT INTO accounts (chain_id, address, token_address, balance, updated_at_block)
+            VALUES ($5, $1, $2, $3, $4)
+            ON CONFLICT (chain_id, address, token_address) DO UPDATE
             SET balance = (CAST(accounts.balance AS NUMERIC) + CAST($3 AS NUMERIC))::TEXT,
                 updated_at_block = $4
             "#,
@@ -266,14 +1064,17 @@ where
         .bind(token_address)
         .bind(amount)
         .bind(block_number)
+        .bind(chain_id)
+        .traced()
         .execute(executor)
-        .await?;
+        .await
+        .record_rows()?;
     } else {
         sqlx::query(
             r#"
-            INSERT INTO accounts (address, token_address, balance, updated_at_block)
-            VALUES ($1, $2, '0', $4)
-            ON CONFLICT (address, token_address) DO UPDATE
+            INSERT INTO accounts (chain_id, address, token_address, balance, updated_at_block)
+            VALUES ($5, $1, $2, '0', $4)
+            ON CONFLICT (chain_id, address, token_address) DO UPDATE
             SET balance = GREATEST(0, CAST(accounts.balance AS NUMERIC) - CAST($3 AS NUMERIC))::TEXT,
                 updated_at_block = $4
             "#,
@@ -282,274 +1083,2754 @@ where
         .bind(token_address)
         .bind(amount)
         .bind(block_number)
+        .bind(chain_id)
+        .traced()
         .execute(executor)
-        .await?;
+        .await
+        .record_rows()?;
     }
     Ok(())
 }
 
+/// Subtract the transfers after `fork_block` from account balances.
+/// Must run before those transfers are deleted.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn revert_account_balances(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE accounts a
+        SET balance = GREATEST(0, CAST(a.balance AS NUMERIC) - d.net)::TEXT
+        FROM (
+            SELECT address, token_address, SUM(amount) AS net
+            FROM (
+                SELECT to_address AS address, token_address, CAST(amount AS NUMERIC) AS amount
+                FROM transfers
+                WHERE chain_id = $2 AND block_number > $1 AND event_type IN ('transfer', 'mint')
+                UNION ALL
+                SELECT from_address, token_address, -CAST(amount AS NUMERIC)
+                FROM transfers
+                WHERE chain_id = $2 AND block_number > $1 AND event_type IN ('transfer', 'burn')
+            ) moves
+            GROUP BY address, token_address
+        ) d
+        WHERE a.chain_id = $2 AND a.address = d.address AND a.token_address = d.token_address
+        "#,
+    )
+    .bind(fork_block)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
 /// Get top holders for a token, ordered by balance descending.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn get_top_holders(
     pool: &PgPool,
-    token_address: &str,
+    chain_id: i64,
+    token_address: &TokenAddress,
     limit: i64,
 ) -> Result<Vec<Account>, sqlx::Error> {
     sqlx::query_as::<_, Account>(
         r#"
         SELECT * FROM accounts
-        WHERE token_address = $1 AND CAST(balance AS NUMERIC) > 0
+        WHERE chain_id = $3 AND token_address = $1 AND CAST(balance AS NUMERIC) > 0
         ORDER BY CAST(balance AS NUMERIC) DESC
         LIMIT $2
         "#,
     )
-    .bind(token_address)
+    .bind(token_address.as_str())
     .bind(limit)
+    .bind(chain_id)
+    .traced()
     .fetch_all(pool)
     .await
+    .record_rows()
 }
 
-// ─── Block Queries ──────────────────────────────────────────────────────────
-
-/// Insert a processed block.
-pub async fn insert_block<'e, E>(executor: E, block: &IndexedBlock) -> Result<(), sqlx::Error>
-where
-    E: Executor<'e, Database = Postgres>,
-{
-    sqlx::query(
+/// Get the top `limit` holders of each token in `token_addresses` in one query.
+///
+/// Rows come back grouped by token, each group ordered by balance descending.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_top_holders_for_tokens(
+    pool: &PgPool,
+    chain_id: i64,
+    token_addresses: &[String],
+    limit: i64,
+) -> Result<Vec<Account>, sqlx::Error> {
+    sqlx::query_as::<_, Account>(
         r#"
-        INSERT INTO indexed_blocks (block_number, block_hash, parent_hash, timestamp)
-        VALUES ($1, $2, $3, $4)
-        ON CONFLICT (block_number) DO UPDATE
-        SET block_hash = $2, parent_hash = $3, timestamp = $4
+        SELECT address, token_address, balance, updated_at_block FROM (
+            SELECT a.*, ROW_NUMBER() OVER (
+                PARTITION BY token_address ORDER BY CAST(balance AS NUMERIC) DESC
+            ) AS rank
+            FROM accounts a
+            WHERE chain_id = $3 AND token_address = ANY($1) AND CAST(balance AS NUMERIC) > 0
+        ) ranked
+        WHERE rank <= $2
+        ORDER BY token_address, rank
         "#,
     )
-    .bind(block.block_number)
-    .bind(&block.block_hash)
-    .bind(&block.parent_hash)
+    .bind(token_addresses)
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Get one page of transfers (newest first) for each `(token, address)` scope in one query.
+///
+/// A scope without an address covers every transfer of its token. Pages come
+/// back in the order of `scopes`, all starting `before` the same position.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_transfers_pages(
+    pool: &PgPool,
+    chain_id: i64,
+    scopes: &[(String, Option<String>)],
+    before: Option<(i64, i32)>,
+    limit: i64,
+) -> Result<Vec<Vec<Transfer>>, sqlx::Error> {
+    let (tokens, addresses): (Vec<String>, Vec<Option<String>>) = scopes.iter().cloned().unzip();
+    let (before_block, before_log) = before.unzip();
+    let rows = sqlx::query(
+        r#"
+        SELECT s.scope, t.*
+        FROM UNNEST($1::TEXT[], $2::TEXT[]) WITH ORDINALITY AS s(token_address, address, scope)
+        CROSS JOIN LATERAL (
+            SELECT * FROM transfers
+            WHERE chain_id = $6
+              AND token_address = s.token_address
+              AND (s.address IS NULL OR from_address = s.address OR to_address = s.address)
+              AND ($3::BIGINT IS NULL OR (block_number, log_index) < ($3, $4::INT))
+            ORDER BY block_number DESC, log_index DESC
+            LIMIT $5
+        ) t
+        ORDER BY s.scope, t.block_number DESC, t.log_index DESC
+        "#,
+    )
+    .bind(&tokens)
+    .bind(&addresses)
+    .bind(before_block)
+    .bind(before_log)
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()?;
+
+    let mut pages = vec![Vec::new(); scopes.len()];
+    for row in rows {
+        let scope: i64 = row.try_get("scope")?;
+        pages[scope as usize - 1].push(Transfer::from_row(&row)?);
+    }
+    Ok(pages)
+}
+
+// ─── Block Queries ──────────────────────────────────────────────────────────
+
+/// Insert a processed block.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn insert_block<'e, E>(
+    executor: E,
+    chain_id: i64,
+    block: &IndexedBlock,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO indexed_blocks (chain_id, block_number, block_hash, parent_hash, timestamp)
+        VALUES ($5, $1, $2, $3, $4)
+        ON CONFLICT (chain_id, block_number) DO UPDATE
+        SET block_hash = $2, parent_hash = $3, timestamp = $4
+        "#,
+    )
+    .bind(block.block_number)
+    .bind(&block.block_hash)
+    .bind(&block.parent_hash)
     .bind(block.timestamp)
+    .bind(chain_id)
+    .traced()
     .execute(executor)
-    .await?;
+    .await
+    .record_rows()?;
     Ok(())
 }
 
 /// Get the latest indexed block number.
-pub async fn get_latest_block(pool: &PgPool) -> Result<Option<i64>, sqlx::Error> {
-    let row: Option<(i64,)> = sqlx::query_as("SELECT MAX(block_number) FROM indexed_blocks")
-        .fetch_optional(pool)
-        .await?;
-    Ok(row.and_then(|r| Some(r.0)))
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_latest_block(pool: &PgPool, chain_id: i64) -> Result<Option<i64>, sqlx::Error> {
+    let row: (Option<i64>,) =
+        sqlx::query_as("SELECT MAX(block_number) FROM indexed_blocks WHERE chain_id = $1")
+            .bind(chain_id)
+            .traced()
+            .fetch_one(pool)
+            .await
+            .record_row()?;
+    Ok(row.0)
 }
 
-/// Delete all indexed data after a given block number (for reorg handling).
-/// This runs inside a single transaction to ensure consistency.
-pub async fn reorg_rollback(pool: &PgPool, fork_block: i64) -> Result<(), sqlx::Error> {
-    let mut tx = pool.begin().await?;
+/// Get the indexed blocks numbered `from_block..=to_block`, oldest first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_indexed_blocks(
+    pool: &PgPool,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<IndexedBlock>, sqlx::Error> {
+    sqlx::query_as::<_, IndexedBlock>(
+        r#"
+        SELECT block_number, block_hash, parent_hash, timestamp FROM indexed_blocks
+        WHERE chain_id = $1 AND block_number BETWEEN $2 AND $3
+        ORDER BY block_number
+        "#,
+    )
+    .bind(chain_id)
+    .bind(from_block)
+    .bind(to_block)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
 
-    // Delete hourly_stats entries that may have been affected
-    // (conservative: delete stats for hours containing blocks after fork)
+/// Record a reorg at `fork_block`, its depth measured from the ingestion
+/// cursor. Must run before the rollback moves that cursor.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn record_reorg(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        DELETE FROM hourly_stats
-        WHERE (token_address, hour) IN (
-            SELECT DISTINCT token_address, DATE_TRUNC('hour', created_at) AS hour
-            FROM transfers WHERE block_number > $1
-        )
+        INSERT INTO reorgs (chain_id, fork_block, depth)
+        SELECT $2, $1, GREATEST(value::BIGINT - $1, 0)
+        FROM indexer_state WHERE chain_id = $2 AND key = 'last_ingested_block'
         "#,
     )
     .bind(fork_block)
-    .execute(&mut *tx)
-    .await?;
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Undo the transfers, rollups, global counters and entity flows derived from
+/// the blocks after `fork_block` and move the processing cursor back to it (if
+/// it is past it). Balances, supplies and hourly stats are kept by the event
+/// handlers, whose roll-backs must run first, while the transfers still exist.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn roll_back_derived(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    // Earliest day whose daily/monthly rollups include orphaned transfers
+    let (rollup_cutoff,): (Option<NaiveDate>,) = sqlx::query_as(
+        "SELECT MIN(DATE(block_time)) FROM transfers WHERE chain_id = $2 AND block_number > $1",
+    )
+    .bind(fork_block)
+    .bind(chain_id)
+    .traced()
+    .fetch_one(&mut *conn)
+    .await
+    .record_row()?;
+
+    // Take orphaned transfers back out of the global counters and entity flows
+    revert_global_counters(conn, chain_id, fork_block).await?;
+    revert_entity_flows(conn, chain_id, fork_block).await?;
 
     // Delete transfers after the fork point
-    sqlx::query("DELETE FROM transfers WHERE block_number > $1")
+    sqlx::query("DELETE FROM transfers WHERE chain_id = $2 AND block_number > $1")
         .bind(fork_block)
-        .execute(&mut *tx)
-        .await?;
+        .bind(chain_id)
+        .traced()
+        .execute(&mut *conn)
+        .await
+        .record_rows()?;
 
-    // Delete indexed blocks after the fork point
-    sqlx::query("DELETE FROM indexed_blocks WHERE block_number > $1")
-        .bind(fork_block)
-        .execute(&mut *tx)
-        .await?;
+    // Rebuild the affected rollups from the surviving transfers
+    if let Some(day) = rollup_cutoff {
+        rebuild_rollups_since(conn, chain_id, day).await?;
+    }
 
-    // Reset the indexer cursor to the fork point
-    sqlx::query("UPDATE indexer_state SET value = $1 WHERE key = 'last_indexed_block'")
-        .bind(fork_block.to_string())
-        .execute(&mut *tx)
-        .await?;
+    rewind_cursor(conn, chain_id, "last_indexed_block", fork_block).await
+}
 
-    tx.commit().await?;
+/// Delete the raw logs and indexed blocks after `fork_block` and move the
+/// ingestion cursor back to it (if it is past it).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn roll_back_raw(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    fork_block: i64,
+) -> Result<(), sqlx::Error> {
+    for table in ["logs", "indexed_blocks"] {
+        sqlx::query(&format!(
+            "DELETE FROM {table} WHERE chain_id = $2 AND block_number > $1"
+        ))
+        .bind(fork_block)
+        .bind(chain_id)
+        .traced()
+        .execute(&mut *conn)
+        .await
+        .record_rows()?;
+    }
 
-    tracing::warn!(
-        fork_block = fork_block,
-        "Reorg rollback complete — deleted data after block"
-    );
+    // The blocks after the fork are fetched again, raw logs included
+    rewind_cursor(conn, chain_id, "raw_logs_from_block", fork_block + 1).await?;
+    rewind_cursor(conn, chain_id, "last_ingested_block", fork_block).await
+}
+
+/// Move cursor `key` back to `block` unless it is already at or before it.
+async fn rewind_cursor(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    key: &str,
+    block: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE indexer_state SET value = $1, updated_at = NOW()
+        WHERE chain_id = $2 AND key = $3 AND value::BIGINT > $4
+        "#,
+    )
+    .bind(block.to_string())
+    .bind(chain_id)
+    .bind(key)
+    .bind(block)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
     Ok(())
 }
 
 /// Get the stored block hash for a specific block number.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
 pub async fn get_block_hash(
     pool: &PgPool,
+    chain_id: i64,
     block_number: i64,
 ) -> Result<Option<String>, sqlx::Error> {
-    let row: Option<(String,)> =
-        sqlx::query_as("SELECT block_hash FROM indexed_blocks WHERE block_number = $1")
-            .bind(block_number)
-            .fetch_optional(pool)
-            .await?;
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT block_hash FROM indexed_blocks WHERE chain_id = $1 AND block_number = $2",
+    )
+    .bind(chain_id)
+    .bind(block_number)
+    .traced()
+    .fetch_optional(pool)
+    .await
+    .record_rows()?;
     Ok(row.map(|r| r.0))
 }
 
-// ─── Indexer State ──────────────────────────────────────────────────────────
+// ─── Raw Logs ───────────────────────────────────────────────────────────────
 
-/// Get the last indexed block from persistent state.
-pub async fn get_last_indexed_block(pool: &PgPool) -> Result<i64, sqlx::Error> {
-    let row: (String,) =
-        sqlx::query_as("SELECT value FROM indexer_state WHERE key = 'last_indexed_block'")
-            .fetch_one(pool)
-            .await?;
-    Ok(row.0.parse::<i64>().unwrap_or(0))
+/// Store fetched logs. Logs already stored are left as they are.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn insert_logs(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    logs: &[RawLog],
+) -> Result<(), sqlx::Error> {
+    for chunk in logs.chunks(5_000) {
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO logs (chain_id, block_number, log_index, transaction_hash, address, topics, data, block_timestamp) ",
+        );
+        qb.push_values(chunk, |mut b, l| {
+            b.push_bind(chain_id)
+                .push_bind(l.block_number)
+                .push_bind(l.log_index)
+                .push_bind(&l.transaction_hash)
+                .push_bind(&l.address)
+                .push_bind(&l.topics)
+                .push_bind(&l.data)
+                .push_bind(l.block_timestamp);
+        });
+        qb.push(" ON CONFLICT (chain_id, block_number, log_index) DO NOTHING");
+        qb.build()
+            .traced()
+            .execute(&mut *conn)
+            .await
+            .record_rows()?;
+    }
+    Ok(())
 }
 
-/// Set the last indexed block in persistent state.
-pub async fn set_last_indexed_block<'e, E>(
-    executor: E,
-    block_number: i64,
-) -> Result<(), sqlx::Error>
-where
-    E: Executor<'e, Database = Postgres>,
-{
-    sqlx::query("UPDATE indexer_state SET value = $1 WHERE key = 'last_indexed_block'")
-        .bind(block_number.to_string())
-        .execute(executor)
-        .await?;
+/// Replace the stored logs and block records of blocks `from_block..=to_block`
+/// with `logs` and `blocks` (fetched again from the chain), leaving the cursors
+/// alone. If the raw logs kept so far start within or right after the range,
+/// they now start at `from_block`.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn replace_logs(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+    logs: &[RawLog],
+    blocks: &[IndexedBlock],
+) -> Result<(), sqlx::Error> {
+    for table in ["logs", "indexed_blocks"] {
+        sqlx::query(&format!(
+            "DELETE FROM {table} WHERE chain_id = $1 AND block_number BETWEEN $2 AND $3"
+        ))
+        .bind(chain_id)
+        .bind(from_block)
+        .bind(to_block)
+        .traced()
+        .execute(&mut *conn)
+        .await
+        .record_rows()?;
+    }
+    insert_logs(conn, chain_id, logs).await?;
+    for block in blocks {
+        insert_block(&mut *conn, chain_id, block).await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE indexer_state SET value = $1::TEXT, updated_at = NOW()
+        WHERE chain_id = $2 AND key = 'raw_logs_from_block'
+          AND value::BIGINT BETWEEN $1 + 1 AND $3 + 1
+        "#,
+    )
+    .bind(from_block)
+    .bind(chain_id)
+    .bind(to_block)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
     Ok(())
 }
 
-// ─── Analytics Queries ──────────────────────────────────────────────────────
-
-/// Per-token transfer volume — total value transferred per token.
-/// Returns (address, symbol, total_volume, transfer_count).
-pub async fn get_token_volumes(
+/// Get the stored logs of blocks `from_block..=to_block` whose event signature
+/// is one of `signatures` (hex), in chain order.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_logs(
     pool: &PgPool,
-) -> Result<Vec<(String, String, String, i64)>, sqlx::Error> {
-    let rows: Vec<(String, String, String, i64)> = sqlx::query_as(
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+    signatures: &[String],
+) -> Result<Vec<RawLog>, sqlx::Error> {
+    sqlx::query_as::<_, RawLog>(
         r#"
-        SELECT t.address, t.symbol,
-               COALESCE(SUM(CAST(tr.amount AS NUMERIC)), 0)::TEXT AS total_volume,
-               COUNT(tr.id) AS transfer_count
-        FROM tokens t
-        LEFT JOIN transfers tr ON t.address = tr.token_address
-        GROUP BY t.address, t.symbol
-        ORDER BY COALESCE(SUM(CAST(tr.amount AS NUMERIC)), 0) DESC
+        SELECT block_number, log_index, transaction_hash, address, topics, data, block_timestamp
+        FROM logs
+        WHERE chain_id = $1 AND block_number BETWEEN $2 AND $3 AND topics[1] = ANY($4)
+        ORDER BY block_number, log_index
         "#,
     )
+    .bind(chain_id)
+    .bind(from_block)
+    .bind(to_block)
+    .bind(signatures)
+    .traced()
     .fetch_all(pool)
-    .await?;
-    Ok(rows)
+    .await
+    .record_rows()
 }
 
-/// Global aggregate stats — total value transferred and total transactions across all tokens.
-/// Returns (total_volume, total_transfers).
-pub async fn get_global_stats(pool: &PgPool) -> Result<(String, i64), sqlx::Error> {
-    let row: (String, i64) = sqlx::query_as(
+// ─── Token Re-index ─────────────────────────────────────────────────────────
+
+/// Replace one token's raw logs and transfers in blocks `from_block..=to_block`
+/// with `logs` and `transfers` (re-fetched from the chain) and rebuild
+/// everything derived from them: balances, supply, hourly stats, rollups,
+/// global counters, known addresses and entity flows.
+///
+/// Rows still on chain keep their id and `created_at`, and webhooks fire only
+/// for transfers no subscription matched before. Bumps the chain's
+/// `reindex_count`, which the API's cache keys include. Runs in one
+/// transaction that serializes with the indexer's batches, so the indexer can
+/// keep running.
+/// Returns `false`, changing nothing, if the cursor is now before `to_block`
+/// (a reorg rolled the range back since it was fetched).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn reindex_token(
+    pool: &PgPool,
+    chain_id: i64,
+    token: &str,
+    from_block: i64,
+    to_block: i64,
+    logs: &[RawLog],
+    transfers: &[NewTransfer],
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    lock_batches(&mut tx, chain_id).await?;
+
+    let (cursor,): (i64,) = sqlx::query_as(
+        "SELECT value::BIGINT FROM indexer_state WHERE chain_id = $1 AND key = 'last_indexed_block'",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_one(&mut *tx)
+    .await
+    .record_row()?;
+    if cursor < to_block {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        "DELETE FROM logs WHERE chain_id = $1 AND address = $2 AND block_number BETWEEN $3 AND $4",
+    )
+    .bind(chain_id)
+    .bind(token)
+    .bind(from_block)
+    .bind(to_block)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+    insert_logs(&mut tx, chain_id, logs).await?;
+
+    sqlx::query(
         r#"
-        SELECT COALESCE(SUM(CAST(amount AS NUMERIC)), 0)::TEXT,
-               COUNT(*)
-        FROM transfers
+        CREATE TEMP TABLE reindexed (
+            from_address     TEXT NOT NULL,
+            to_address       TEXT NOT NULL,
+            amount           TEXT NOT NULL,
+            memo             TEXT,
+            event_type       TEXT NOT NULL,
+            transaction_hash TEXT NOT NULL,
+            block_number     BIGINT NOT NULL,
+            log_index        INT NOT NULL,
+            block_time       TIMESTAMP NOT NULL
+        ) ON COMMIT DROP
         "#,
     )
-    .fetch_one(pool)
-    .await?;
-    Ok(row)
-}
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+    for chunk in transfers.chunks(5_000) {
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO reindexed (from_address, to_address, amount, memo, event_type, transaction_hash, block_number, log_index, block_time) ",
+        );
+        qb.push_values(chunk, |mut b, t| {
+            b.push_bind(&t.from_address)
+                .push_bind(&t.to_address)
+                .push_bind(&t.amount)
+                .push_bind(&t.memo)
+                .push_bind(&t.event_type)
+                .push_bind(&t.transaction_hash)
+                .push_bind(t.block_number)
+                .push_bind(t.log_index)
+                .push_bind(t.block_time);
+        });
+        qb.build().traced().execute(&mut *tx).await.record_rows()?;
+    }
 
-/// Total unique active addresses (senders + receivers).
-pub async fn get_active_address_count(pool: &PgPool) -> Result<i64, sqlx::Error> {
-    let row: (i64,) = sqlx::query_as(
+    // Addresses of the stored and the re-fetched rows, whose first-seen block may move
+    sqlx::query(
         r#"
-        SELECT COUNT(DISTINCT addr) FROM (
-            SELECT from_address AS addr FROM transfers WHERE from_address != '0x0000000000000000000000000000000000000000'
-            UNION
-            SELECT to_address AS addr FROM transfers WHERE to_address != '0x0000000000000000000000000000000000000000'
-        ) sub
+        CREATE TEMP TABLE reindexed_addresses ON COMMIT DROP AS
+        SELECT a.address
+        FROM transfers t
+        CROSS JOIN LATERAL (VALUES (t.from_address), (t.to_address)) AS a(address)
+        WHERE t.chain_id = $1 AND t.token_address = $2 AND t.block_number BETWEEN $3 AND $4
+        UNION
+        SELECT a.address
+        FROM reindexed r
+        CROSS JOIN LATERAL (VALUES (r.from_address), (r.to_address)) AS a(address)
         "#,
     )
-    .fetch_one(pool)
+    .bind(chain_id)
+    .bind(token)
+    .bind(from_block)
+    .bind(to_block)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+
+    // Take the stored rows out of everything derived from them
+    let old_cutoff = token_rollup_cutoff(&mut tx, chain_id, token, from_block, to_block).await?;
+    fold_totals(&mut tx, chain_id, from_block, to_block, -1, Some(token)).await?;
+    fold_entity_flows(&mut tx, chain_id, from_block, to_block, -1, Some(token)).await?;
+    fold_hourly_stats(
+        &mut tx,
+        chain_id,
+        -1,
+        HourlyEvents::StoredToken(token, from_block, to_block),
+    )
     .await?;
-    Ok(row.0)
-}
 
-// ─── Time-Series Queries ────────────────────────────────────────────────────
+    // Swap in the re-fetched rows
+    sqlx::query(
+        r#"
+        DELETE FROM transfers t
+        WHERE t.chain_id = $1 AND t.token_address = $2 AND t.block_number BETWEEN $3 AND $4
+          AND NOT EXISTS (
+              SELECT 1 FROM reindexed r
+              WHERE r.transaction_hash = t.transaction_hash AND r.log_index = t.log_index
+          )
+        "#,
+    )
+    .bind(chain_id)
+    .bind(token)
+    .bind(from_block)
+    .bind(to_block)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+    sqlx::query(
+        r#"
+        INSERT INTO transfers (chain_id, token_address, from_address, to_address, amount, memo, event_type, transaction_hash, block_number, log_index, block_time)
+        SELECT $1, $2, from_address, to_address, amount, memo, event_type, transaction_hash, block_number, log_index, block_time
+        FROM reindexed
+        ON CONFLICT (chain_id, transaction_hash, log_index) DO UPDATE SET
+            token_address = EXCLUDED.token_address,
+            from_address = EXCLUDED.from_address,
+            to_address = EXCLUDED.to_address,
+            amount = EXCLUDED.amount,
+            memo = EXCLUDED.memo,
+            event_type = EXCLUDED.event_type,
+            block_number = EXCLUDED.block_number,
+            block_time = EXCLUDED.block_time
+        "#,
+    )
+    .bind(chain_id)
+    .bind(token)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
 
-/// Daily transfer volume aggregated across all tokens.
-/// Returns rows of (date, total_volume, transfer_count).
-pub async fn get_daily_volume(
-    pool: &PgPool,
-    limit: i64,
-) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
-    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+    // Fold the re-fetched rows back in
+    fold_totals(&mut tx, chain_id, from_block, to_block, 1, Some(token)).await?;
+    fold_entity_flows(&mut tx, chain_id, from_block, to_block, 1, Some(token)).await?;
+    refresh_known_addresses(&mut tx, chain_id).await?;
+    fold_hourly_stats(&mut tx, chain_id, 1, HourlyEvents::Reindexed(token)).await?;
+    enqueue_webhook_deliveries(&mut tx, chain_id, from_block, to_block, Some(token)).await?;
+
+    let new_cutoff = token_rollup_cutoff(&mut tx, chain_id, token, from_block, to_block).await?;
+    if let Some(day) = old_cutoff.into_iter().chain(new_cutoff).min() {
+        rebuild_token_rollups_since(&mut tx, chain_id, token, day).await?;
+    }
+
+    // Balances and supply are recomputed from the token's whole history
+    sqlx::query("DELETE FROM accounts WHERE chain_id = $1 AND token_address = $2")
+        .bind(chain_id)
+        .bind(token)
+        .traced()
+        .execute(&mut *tx)
+        .await
+        .record_rows()?;
+    sqlx::query(
         r#"
-        SELECT DATE(created_at)::TEXT AS day,
-               COALESCE(SUM(CAST(amount AS NUMERIC)), 0)::TEXT AS volume,
-               COUNT(*) AS tx_count
-        FROM transfers
-        GROUP BY DATE(created_at)
-        ORDER BY day DESC
-        LIMIT $1
+        INSERT INTO accounts (chain_id, address, token_address, balance, updated_at_block)
+        SELECT $1, address, $2, GREATEST(0, SUM(amount))::TEXT, MAX(block_number)
+        FROM (
+            SELECT to_address AS address, CAST(amount AS NUMERIC) AS amount, block_number
+            FROM transfers
+            WHERE chain_id = $1 AND token_address = $2 AND event_type IN ('transfer', 'mint')
+            UNION ALL
+            SELECT from_address, -CAST(amount AS NUMERIC), block_number
+            FROM transfers
+            WHERE chain_id = $1 AND token_address = $2 AND event_type IN ('transfer', 'burn')
+        ) moves
+        GROUP BY address
         "#,
     )
-    .bind(limit)
-    .fetch_all(pool)
-    .await?;
-    Ok(rows)
+    .bind(chain_id)
+    .bind(token)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+    sqlx::query(
+        r#"
+        UPDATE tokens SET total_supply = (
+            SELECT GREATEST(0, COALESCE(SUM(
+                CASE WHEN event_type = 'mint' THEN CAST(amount AS NUMERIC) ELSE -CAST(amount AS NUMERIC) END
+            ), 0))::TEXT
+            FROM transfers
+            WHERE chain_id = $1 AND token_address = $2 AND event_type IN ('mint', 'burn')
+        )
+        WHERE chain_id = $1 AND address = $2
+        "#,
+    )
+    .bind(chain_id)
+    .bind(token)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+
+    // The cursor did not move, so change the API's cache keys another way
+    sqlx::query(
+        r#"
+        INSERT INTO indexer_state (chain_id, key, value) VALUES ($1, 'reindex_count', '1')
+        ON CONFLICT (chain_id, key) DO UPDATE
+        SET value = (indexer_state.value::BIGINT + 1)::TEXT, updated_at = NOW()
+        "#,
+    )
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+
+    tx.commit().await?;
+    Ok(true)
 }
 
-/// Monthly transfer volume aggregated across all tokens.
-/// Returns rows of (month, total_volume, transfer_count).
-pub async fn get_monthly_volume(
-    pool: &PgPool,
-    limit: i64,
-) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
-    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+/// Recompute the first-seen block of every address in `reindexed_addresses`
+/// from the stored transfers: addresses no transfer mentions any more are
+/// forgotten, new ones recorded, and global_totals counts the difference.
+async fn refresh_known_addresses(
+    conn: &mut PgConnection,
+    chain_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
         r#"
-        SELECT TO_CHAR(DATE_TRUNC('month', created_at), 'YYYY-MM') AS month,
-               COALESCE(SUM(CAST(amount AS NUMERIC)), 0)::TEXT AS volume,
-               COUNT(*) AS tx_count
-        FROM transfers
-        GROUP BY DATE_TRUNC('month', created_at)
-        ORDER BY month DESC
-        LIMIT $1
+        WITH seen AS (
+            SELECT r.address, LEAST(
+                (SELECT MIN(t.block_number) FROM transfers t
+                 WHERE t.chain_id = $1 AND t.from_address = r.address),
+                (SELECT MIN(t.block_number) FROM transfers t
+                 WHERE t.chain_id = $1 AND t.to_address = r.address)
+            ) AS first_seen_block
+            FROM reindexed_addresses r
+            WHERE r.address <> '0x0000000000000000000000000000000000000000'
+        ),
+        removed AS (
+            DELETE FROM known_addresses k
+            USING seen s
+            WHERE k.chain_id = $1 AND k.address = s.address AND s.first_seen_block IS NULL
+            RETURNING 1
+        ),
+        upserted AS (
+            INSERT INTO known_addresses (chain_id, address, first_seen_block)
+            SELECT $1, address, first_seen_block FROM seen WHERE first_seen_block IS NOT NULL
+            ON CONFLICT (chain_id, address) DO UPDATE SET first_seen_block = EXCLUDED.first_seen_block
+            RETURNING xmax = 0 AS inserted
+        )
+        UPDATE global_totals SET active_addresses = active_addresses
+            + (SELECT COUNT(*) FROM upserted WHERE inserted)
+            - (SELECT COUNT(*) FROM removed)
+        WHERE chain_id = $1
         "#,
     )
-    .bind(limit)
-    .fetch_all(pool)
-    .await?;
-    Ok(rows)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?;
+    Ok(())
 }
 
-/// Daily volume + activity for a specific token.
-/// Returns rows of (date, volume, transfer_count).
-pub async fn get_token_daily_volume(
-    pool: &PgPool,
-    token_address: &str,
-    limit: i64,
-) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
-    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+/// Earliest day of one token's transfers in blocks `from_block..=to_block`.
+async fn token_rollup_cutoff(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    token: &str,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Option<NaiveDate>, sqlx::Error> {
+    let (day,): (Option<NaiveDate>,) = sqlx::query_as(
         r#"
-        SELECT DATE(created_at)::TEXT AS day,
-               COALESCE(SUM(CAST(amount AS NUMERIC)), 0)::TEXT AS volume,
-               COUNT(*) AS tx_count
-        FROM transfers
-        WHERE token_address = $1
-        GROUP BY DATE(created_at)
-        ORDER BY day DESC
-        LIMIT $2
+        SELECT MIN(DATE(block_time)) FROM transfers
+        WHERE chain_id = $1 AND token_address = $2 AND block_number BETWEEN $3 AND $4
         "#,
     )
-    .bind(token_address)
-    .bind(limit)
-    .fetch_all(pool)
-    .await?;
-    Ok(rows)
+    .bind(chain_id)
+    .bind(token)
+    .bind(from_block)
+    .bind(to_block)
+    .traced()
+    .fetch_one(&mut *conn)
+    .await
+    .record_row()?;
+    Ok(day)
+}
+
+/// Events an hourly_stats fold reads, as `token_address, hour, event_type,
+/// amount, from_address, to_address` rows.
+#[derive(Clone, Copy)]
+enum HourlyEvents<'a> {
+    /// Stored transfers after block `$3`.
+    StoredAfter(i64),
+    /// Stored transfers of token `$3` in blocks `$4..=$5`.
+    StoredToken(&'a str, i64, i64),
+    /// Re-fetched transfers of token `$3` in the `reindexed` temp table.
+    Reindexed(&'a str),
+}
+
+impl HourlyEvents<'_> {
+    fn source(&self) -> &'static str {
+        match self {
+            Self::StoredAfter(_) => {
+                r#"
+                SELECT token_address, DATE_TRUNC('hour', block_time) AS hour,
+                       event_type, amount, from_address, to_address
+                FROM transfers
+                WHERE chain_id = $1 AND block_number > $3
+                "#
+            }
+            Self::StoredToken(..) => {
+                r#"
+                SELECT token_address, DATE_TRUNC('hour', block_time) AS hour,
+                       event_type, amount, from_address, to_address
+                FROM transfers
+                WHERE chain_id = $1 AND token_address = $3 AND block_number BETWEEN $4 AND $5
+                "#
+            }
+            Self::Reindexed(_) => {
+                r#"
+                SELECT $3 AS token_address, DATE_TRUNC('hour', block_time) AS hour,
+                       event_type, amount, from_address, to_address
+                FROM reindexed
+                "#
+            }
+        }
+    }
+}
+
+/// Add (`sign = 1`) or subtract (`sign = -1`) `events` to/from hourly_stats,
+/// counting senders and receivers like [`upsert_hourly_stats`].
+async fn fold_hourly_stats(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    sign: i64,
+    events: HourlyEvents<'_>,
+) -> Result<(), sqlx::Error> {
+    let source = events.source();
+    let sql = format!(
+        r#"
+        INSERT INTO hourly_stats (chain_id, token_address, hour, transfer_count, transfer_volume, mint_count, mint_volume, burn_count, burn_volume, unique_senders, unique_receivers)
+        SELECT $1, e.token_address, e.hour,
+               $2 * COUNT(*) FILTER (WHERE e.event_type = 'transfer'),
+               ($2 * COALESCE(SUM(CAST(e.amount AS NUMERIC)) FILTER (WHERE e.event_type = 'transfer'), 0))::TEXT,
+               $2 * COUNT(*) FILTER (WHERE e.event_type = 'mint'),
+               ($2 * COALESCE(SUM(CAST(e.amount AS NUMERIC)) FILTER (WHERE e.event_type = 'mint'), 0))::TEXT,
+               $2 * COUNT(*) FILTER (WHERE e.event_type = 'burn'),
+               ($2 * COALESCE(SUM(CAST(e.amount AS NUMERIC)) FILTER (WHERE e.event_type = 'burn'), 0))::TEXT,
+               $2 * COUNT(*) FILTER (WHERE e.from_address <> '0x0000000000000000000000000000000000000000'),
+               $2 * COUNT(*) FILTER (WHERE e.to_address <> '0x0000000000000000000000000000000000000000')
+        FROM ({source}) e
+        GROUP BY e.token_address, e.hour
+        ON CONFLICT (chain_id, token_address, hour) DO UPDATE SET
+            transfer_count = hourly_stats.transfer_count + EXCLUDED.transfer_count,
+            transfer_volume = (CAST(hourly_stats.transfer_volume AS NUMERIC) + CAST(EXCLUDED.transfer_volume AS NUMERIC))::TEXT,
+            mint_count = hourly_stats.mint_count + EXCLUDED.mint_count,
+            mint_volume = (CAST(hourly_stats.mint_volume AS NUMERIC) + CAST(EXCLUDED.mint_volume AS NUMERIC))::TEXT,
+            burn_count = hourly_stats.burn_count + EXCLUDED.burn_count,
+            burn_volume = (CAST(hourly_stats.burn_volume AS NUMERIC) + CAST(EXCLUDED.burn_volume AS NUMERIC))::TEXT,
+            unique_senders = hourly_stats.unique_senders + EXCLUDED.unique_senders,
+            unique_receivers = hourly_stats.unique_receivers + EXCLUDED.unique_receivers
+        "#
+    );
+    let query = sqlx::query(&sql).bind(chain_id).bind(sign);
+    let query = match events {
+        HourlyEvents::StoredAfter(block) => query.bind(block),
+        HourlyEvents::StoredToken(token, from, to) => query.bind(token).bind(from).bind(to),
+        HourlyEvents::Reindexed(token) => query.bind(token),
+    };
+    query.traced().execute(&mut *conn).await.record_rows()?;
+
+    if sign < 0 {
+        sqlx::query(
+            r#"
+            DELETE FROM hourly_stats
+            WHERE chain_id = $1 AND transfer_count = 0 AND mint_count = 0 AND burn_count = 0
+            "#,
+        )
+        .bind(chain_id)
+        .traced()
+        .execute(&mut *conn)
+        .await
+        .record_rows()?;
+    }
+    Ok(())
+}
+
+// ─── Networks ───────────────────────────────────────────────────────────────
+
+/// Register the network an indexer writes, seeding its cursors on first use.
+///
+/// The first network registered in a database also adopts the rows indexed
+/// before networks existed (stored under chain id 0). Fails if `name` already
+/// belongs to another chain id.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn register_network(pool: &PgPool, chain_id: i64, name: &str) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let unassigned: Option<(i64,)> =
+        sqlx::query_as("DELETE FROM networks WHERE chain_id = 0 RETURNING chain_id")
+            .traced()
+            .fetch_optional(&mut *tx)
+            .await
+            .record_rows()?;
+    if unassigned.is_some() && chain_id != 0 {
+        // The database held no other chain until now, so no key can collide.
+        for table in CHAIN_TABLES {
+            sqlx::query(&format!(
+                "UPDATE {table} SET chain_id = $1 WHERE chain_id = 0"
+            ))
+            .bind(chain_id)
+            .traced()
+            .execute(&mut *tx)
+            .await
+            .record_rows()?;
+        }
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO networks (chain_id, name) VALUES ($1, $2)
+        ON CONFLICT (chain_id) DO UPDATE SET name = EXCLUDED.name
+        "#,
+    )
+    .bind(chain_id)
+    .bind(name)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+
+    // A new chain starts at block 0.
+    sqlx::query(
+        r#"
+        INSERT INTO indexer_state (chain_id, key, value)
+        VALUES ($1, 'last_indexed_block', '0'),
+               ($1, 'last_ingested_block', '0')
+        ON CONFLICT (chain_id, key) DO NOTHING
+        "#,
+    )
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+
+    tx.commit().await
+}
+
+/// Tables holding indexed chain data, keyed by `chain_id`. Updating `tokens`
+/// cascades to the tables referencing it.
+const CHAIN_TABLES: [&str; 22] = [
+    "tokens",
+    "transfers",
+    "accounts",
+    "hourly_stats",
+    "daily_stats",
+    "daily_active_addresses",
+    "monthly_stats",
+    "monthly_active_addresses",
+    "token_totals",
+    "global_totals",
+    "known_addresses",
+    "holder_snapshots",
+    "holder_sets",
+    "entity_flows",
+    "indexed_blocks",
+    "logs",
+    "indexer_state",
+    "reorgs",
+    "webhook_subscriptions",
+    "webhook_deliveries",
+    "webhook_dead_letters",
+    "webhook_matches",
+];
+
+/// Every registered network, by name.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_networks(pool: &PgPool) -> Result<Vec<Network>, sqlx::Error> {
+    sqlx::query_as::<_, Network>("SELECT * FROM networks WHERE chain_id <> 0 ORDER BY name")
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+// ─── Indexer State ──────────────────────────────────────────────────────────
+
+/// Get the last indexed block from persistent state.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_last_indexed_block(pool: &PgPool, chain_id: i64) -> Result<i64, sqlx::Error> {
+    let row: (String,) = sqlx::query_as(
+        "SELECT value FROM indexer_state WHERE chain_id = $1 AND key = 'last_indexed_block'",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_one(pool)
+    .await
+    .record_row()?;
+    Ok(row.0.parse::<i64>().unwrap_or(0))
+}
+
+/// Get the last indexed block of every network as (chain_id, block) pairs.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_last_indexed_blocks(pool: &PgPool) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT chain_id, value::BIGINT FROM indexer_state WHERE key = 'last_indexed_block' ORDER BY chain_id",
+    )
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Get the last indexed block of every registered network, with the number of
+/// token re-indexes run on it and of label writes seen by it (which change data
+/// below the cursor), as (name, block, re-indexes, label writes) rows.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_network_cursors(
+    pool: &PgPool,
+) -> Result<Vec<(String, i64, i64, i64)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT n.name, s.value::BIGINT, COALESCE(r.value::BIGINT, 0), COALESCE(l.value::BIGINT, 0)
+        FROM networks n
+        JOIN indexer_state s ON s.chain_id = n.chain_id AND s.key = 'last_indexed_block'
+        LEFT JOIN indexer_state r ON r.chain_id = n.chain_id AND r.key = 'reindex_count'
+        LEFT JOIN indexer_state l ON l.chain_id = n.chain_id AND l.key = 'label_count'
+        WHERE n.chain_id <> 0
+        ORDER BY n.name
+        "#,
+    )
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Set the last indexed block in persistent state.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn set_last_indexed_block<'e, E>(
+    executor: E,
+    chain_id: i64,
+    block_number: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "UPDATE indexer_state SET value = $1, updated_at = NOW() WHERE chain_id = $2 AND key = 'last_indexed_block'",
+    )
+    .bind(block_number.to_string())
+    .bind(chain_id)
+    .traced()
+    .execute(executor)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Get the last block whose logs are in the `logs` table.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_last_ingested_block(pool: &PgPool, chain_id: i64) -> Result<i64, sqlx::Error> {
+    let row: (String,) = sqlx::query_as(
+        "SELECT value FROM indexer_state WHERE chain_id = $1 AND key = 'last_ingested_block'",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_one(pool)
+    .await
+    .record_row()?;
+    Ok(row.0.parse::<i64>().unwrap_or(0))
+}
+
+/// Set the last block whose logs are in the `logs` table.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn set_last_ingested_block<'e, E>(
+    executor: E,
+    chain_id: i64,
+    block_number: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "UPDATE indexer_state SET value = $1, updated_at = NOW() WHERE chain_id = $2 AND key = 'last_ingested_block'",
+    )
+    .bind(block_number.to_string())
+    .bind(chain_id)
+    .traced()
+    .execute(executor)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// First block whose raw logs were kept: blocks indexed before the `logs`
+/// table existed cannot be reprocessed from the database.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_raw_logs_from_block(pool: &PgPool, chain_id: i64) -> Result<i64, sqlx::Error> {
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT value FROM indexer_state WHERE chain_id = $1 AND key = 'raw_logs_from_block'",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_optional(pool)
+    .await
+    .record_rows()?;
+    Ok(row.and_then(|r| r.0.parse().ok()).unwrap_or(0))
+}
+
+/// Record the chain head the indexer just read from the RPC.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn set_chain_head(
+    pool: &PgPool,
+    chain_id: i64,
+    block_number: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO indexer_state (chain_id, key, value, updated_at)
+        VALUES ($2, 'chain_head', $1, NOW())
+        ON CONFLICT (chain_id, key) DO UPDATE SET value = $1, updated_at = NOW()
+        "#,
+    )
+    .bind(block_number.to_string())
+    .bind(chain_id)
+    .traced()
+    .execute(pool)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Take the writer lock of a network. It is held until the returned
+/// connection is dropped; `None` means another process holds it.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn try_lock_network(
+    pool: &PgPool,
+    chain_id: i64,
+) -> Result<Option<PgConnection>, sqlx::Error> {
+    // Detached, so dropping it closes the session (and the lock) instead of
+    // parking both in the pool.
+    let mut conn = pool.acquire().await?.detach();
+    let (locked,): (bool,) =
+        sqlx::query_as("SELECT pg_try_advisory_lock(hashtextextended('tempulse:indexer', $1))")
+            .bind(chain_id)
+            .traced()
+            .fetch_one(&mut conn)
+            .await
+            .record_row()?;
+    Ok(locked.then_some(conn))
+}
+
+/// Serialize with every other writer of a network's indexed data until the
+/// transaction ends. Batches, rollbacks and token re-indexes take it first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn lock_batches(conn: &mut PgConnection, chain_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended('tempulse:batch', $1))")
+        .bind(chain_id)
+        .traced()
+        .execute(&mut *conn)
+        .await
+        .record_rows()?;
+    Ok(())
+}
+
+/// Indexer progress: cursor, chain head as last seen, and the latest reorg.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_indexer_status(
+    pool: &PgPool,
+    chain_id: i64,
+) -> Result<IndexerStatus, sqlx::Error> {
+    let (last_indexed_block, last_indexed_timestamp, chain_head, chain_head_seen_at): (
+        String,
+        Option<i64>,
+        Option<String>,
+        Option<NaiveDateTime>,
+    ) = sqlx::query_as(
+        r#"
+        SELECT c.value,
+               (SELECT timestamp FROM indexed_blocks
+                WHERE chain_id = c.chain_id AND block_number <= c.value::BIGINT
+                ORDER BY block_number DESC LIMIT 1),
+               h.value, h.updated_at
+        FROM indexer_state c
+        LEFT JOIN indexer_state h ON h.chain_id = c.chain_id AND h.key = 'chain_head'
+        WHERE c.chain_id = $1 AND c.key = 'last_indexed_block'
+        "#,
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_one(pool)
+    .await
+    .record_row()?;
+
+    let last_reorg: Option<Reorg> = sqlx::query_as(
+        "SELECT fork_block, depth, detected_at FROM reorgs WHERE chain_id = $1 ORDER BY id DESC LIMIT 1",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_optional(pool)
+    .await
+    .record_rows()?;
+
+    Ok(IndexerStatus {
+        last_indexed_block: last_indexed_block.parse().unwrap_or(0),
+        last_indexed_timestamp,
+        chain_head: chain_head.and_then(|h| h.parse().ok()),
+        chain_head_seen_at,
+        last_reorg,
+    })
+}
+
+// ─── Notifications ──────────────────────────────────────────────────────────
+
+/// `NOTIFY` channel announcing newly committed transfers.
+pub const TRANSFERS_CHANNEL: &str = "tempulse_transfers";
+
+/// Announce that the blocks in `batch` were indexed.
+///
+/// Run this inside the batch transaction: Postgres delivers the notification
+/// only when (and if) the transaction commits.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn notify_transfers_committed<'e, E>(
+    executor: E,
+    batch: &TransfersCommitted,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let payload = serde_json::to_string(batch).expect("notification payload serializes");
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(TRANSFERS_CHANNEL)
+        .bind(payload)
+        .traced()
+        .execute(executor)
+        .await
+        .record_rows()?;
+    Ok(())
+}
+
+/// Get all transfers in blocks `from_block..=to_block`, in chain order.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_transfers_in_blocks(
+    pool: &PgPool,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+) -> Result<Vec<Transfer>, sqlx::Error> {
+    sqlx::query_as::<_, Transfer>(
+        r#"
+        SELECT * FROM transfers
+        WHERE chain_id = $3 AND block_number BETWEEN $1 AND $2
+        ORDER BY block_number, log_index
+        "#,
+    )
+    .bind(from_block)
+    .bind(to_block)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+// ─── Analytics Queries ──────────────────────────────────────────────────────
+
+/// Per-token transfer volume — total value transferred per token, read from `token_totals`.
+/// Returns (address, symbol, total_volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token_volumes(
+    pool: &PgPool,
+    chain_id: i64,
+) -> Result<Vec<(String, String, String, i64)>, sqlx::Error> {
+    let rows: Vec<(String, String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT t.address, t.symbol,
+               COALESCE(tt.total_volume, '0') AS total_volume,
+               COALESCE(tt.transfer_count, 0) AS transfer_count
+        FROM tokens t
+        LEFT JOIN token_totals tt ON tt.chain_id = t.chain_id AND tt.token_address = t.address
+        WHERE t.chain_id = $1
+        ORDER BY COALESCE(CAST(tt.total_volume AS NUMERIC), 0) DESC
+        "#,
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()?;
+    Ok(rows)
+}
+
+/// Global aggregate stats — total value transferred and total transactions across all tokens.
+/// Returns (total_volume, total_transfers).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_global_stats(pool: &PgPool, chain_id: i64) -> Result<(String, i64), sqlx::Error> {
+    let row: Option<(String, i64)> = sqlx::query_as(
+        "SELECT total_volume, total_transfers FROM global_totals WHERE chain_id = $1",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_optional(pool)
+    .await
+    .record_rows()?;
+    Ok(row.unwrap_or_else(|| ("0".to_string(), 0)))
+}
+
+/// Total unique active addresses (senders + receivers).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_active_address_count(pool: &PgPool, chain_id: i64) -> Result<i64, sqlx::Error> {
+    let row: Option<(i64,)> =
+        sqlx::query_as("SELECT active_addresses FROM global_totals WHERE chain_id = $1")
+            .bind(chain_id)
+            .traced()
+            .fetch_optional(pool)
+            .await
+            .record_rows()?;
+    Ok(row.map_or(0, |r| r.0))
+}
+
+// ─── Time-Series Queries ────────────────────────────────────────────────────
+
+/// Daily transfer volume aggregated across all tokens, read from `daily_stats`.
+/// Returns rows of (date, total_volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_daily_volume(
+    pool: &PgPool,
+    chain_id: i64,
+    limit: i64,
+) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
+    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT day::TEXT AS day,
+               COALESCE(SUM(CAST(transfer_volume AS NUMERIC) + CAST(mint_volume AS NUMERIC) + CAST(burn_volume AS NUMERIC)), 0)::TEXT AS volume,
+               COALESCE(SUM(transfer_count + mint_count + burn_count), 0)::BIGINT AS tx_count
+        FROM daily_stats
+        WHERE chain_id = $2
+        GROUP BY day
+        ORDER BY day DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()?;
+    Ok(rows)
+}
+
+/// Monthly transfer volume aggregated across all tokens, read from `monthly_stats`.
+/// Returns rows of (month, total_volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_monthly_volume(
+    pool: &PgPool,
+    chain_id: i64,
+    limit: i64,
+) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
+    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT TO_CHAR(month, 'YYYY-MM') AS month,
+               COALESCE(SUM(CAST(transfer_volume AS NUMERIC) + CAST(mint_volume AS NUMERIC) + CAST(burn_volume AS NUMERIC)), 0)::TEXT AS volume,
+               COALESCE(SUM(transfer_count + mint_count + burn_count), 0)::BIGINT AS tx_count
+        FROM monthly_stats
+        WHERE chain_id = $2
+        GROUP BY month
+        ORDER BY month DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()?;
+    Ok(rows)
+}
+
+/// Daily volume + activity for a specific token.
+/// Returns rows of (date, volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token_daily_volume(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: &TokenAddress,
+    limit: i64,
+) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
+    let rows: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT day::TEXT AS day,
+               (CAST(transfer_volume AS NUMERIC) + CAST(mint_volume AS NUMERIC) + CAST(burn_volume AS NUMERIC))::TEXT AS volume,
+               (transfer_count + mint_count + burn_count)::BIGINT AS tx_count
+        FROM daily_stats
+        WHERE chain_id = $3 AND token_address = $1
+        ORDER BY day DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(token_address.as_str())
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()?;
+    Ok(rows)
+}
+
+// ─── Holder Distribution ────────────────────────────────────────────────────
+
+/// Number of balance buckets in a [`HolderSnapshot`]. Bucket 0 counts holders
+/// of less than one whole token, bucket `i` those in `[10^(i-1), 10^i)`, and
+/// the last bucket everything from `10^(HOLDER_BUCKETS - 2)` up.
+pub const HOLDER_BUCKETS: usize = 8;
+
+/// Compute and store `token_address`'s holder distribution for `day` from the
+/// current `accounts`, replacing any earlier snapshot of the same day.
+///
+/// New and churned holders are counted against the holder set of the latest
+/// snapshot before `day`; the first snapshot of a token counts everyone as new.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn snapshot_holder_distribution(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: &str,
+    day: NaiveDate,
+) -> Result<HolderSnapshot, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Metrics and the stored holder set must see the same balances.
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .traced()
+        .execute(&mut *tx)
+        .await
+        .record_rows()?;
+
+    let (prev_day,): (Option<NaiveDate>,) = sqlx::query_as(
+        "SELECT MAX(day) FROM holder_sets WHERE chain_id = $3 AND token_address = $1 AND day < $2",
+    )
+    .bind(token_address)
+    .bind(day)
+    .bind(chain_id)
+    .traced()
+    .fetch_one(&mut *tx)
+    .await
+    .record_row()?;
+
+    let snapshot: HolderSnapshot = sqlx::query_as(
+        r#"
+        WITH holders AS (
+            SELECT address, CAST(balance AS NUMERIC) AS balance
+            FROM accounts
+            WHERE chain_id = $5 AND token_address = $1 AND CAST(balance AS NUMERIC) > 0
+        ),
+        ranked AS (
+            SELECT balance,
+                   ROW_NUMBER() OVER (ORDER BY balance) AS asc_rank,
+                   ROW_NUMBER() OVER (ORDER BY balance DESC) AS desc_rank,
+                   SUM(balance) OVER (ORDER BY balance DESC ROWS UNBOUNDED PRECEDING) AS cumulative
+            FROM holders
+        ),
+        totals AS (
+            SELECT COUNT(*) AS n, COALESCE(SUM(balance), 0) AS total FROM holders
+        ),
+        metrics AS (
+            SELECT t.n, t.total,
+                   CASE WHEN t.total = 0 THEN 0
+                        ELSE 2 * SUM(r.asc_rank * r.balance) / (t.n * t.total) - (t.n + 1)::NUMERIC / t.n
+                   END AS gini,
+                   COUNT(r.balance) FILTER (WHERE r.cumulative - r.balance <= t.total / 2) AS nakamoto,
+                   COALESCE(SUM(r.balance) FILTER (WHERE r.desc_rank <= 10), 0) AS top10,
+                   COALESCE(SUM(r.balance) FILTER (WHERE r.desc_rank <= 100), 0) AS top100
+            FROM totals t
+            LEFT JOIN ranked r ON TRUE
+            GROUP BY t.n, t.total
+        ),
+        buckets AS (
+            SELECT ARRAY(
+                SELECT COUNT(h.address)
+                FROM generate_series(0, $4 - 1) AS g(bucket)
+                LEFT JOIN holders h
+                  ON LEAST(GREATEST(FLOOR(LOG(h.balance / POWER(10::NUMERIC, tk.decimals))) + 1, 0), $4 - 1) = g.bucket
+                GROUP BY g.bucket
+                ORDER BY g.bucket
+            ) AS counts
+            FROM tokens tk
+            WHERE tk.chain_id = $5 AND tk.address = $1
+        ),
+        prev AS (
+            SELECT address FROM holder_sets WHERE chain_id = $5 AND token_address = $1 AND day = $3
+        ),
+        churn AS (
+            SELECT (SELECT COUNT(*) FROM holders h
+                    WHERE NOT EXISTS (SELECT 1 FROM prev p WHERE p.address = h.address)) AS new_holders,
+                   (SELECT COUNT(*) FROM prev p
+                    WHERE NOT EXISTS (SELECT 1 FROM holders h WHERE h.address = p.address)) AS churned_holders
+        )
+        INSERT INTO holder_snapshots (chain_id, token_address, day, holder_count, total_balance, gini, nakamoto,
+                                      top10_share, top100_share, new_holders, churned_holders, balance_buckets)
+        SELECT $5, $1, $2, m.n, m.total::TEXT,
+               TRIM_SCALE(ROUND(m.gini, 6))::TEXT,
+               m.nakamoto,
+               COALESCE(TRIM_SCALE(ROUND(m.top10 / NULLIF(m.total, 0), 6)), 0)::TEXT,
+               COALESCE(TRIM_SCALE(ROUND(m.top100 / NULLIF(m.total, 0), 6)), 0)::TEXT,
+               c.new_holders, c.churned_holders, b.counts
+        FROM metrics m, buckets b, churn c
+        ON CONFLICT (chain_id, token_address, day) DO UPDATE SET
+            holder_count = EXCLUDED.holder_count,
+            total_balance = EXCLUDED.total_balance,
+            gini = EXCLUDED.gini,
+            nakamoto = EXCLUDED.nakamoto,
+            top10_share = EXCLUDED.top10_share,
+            top100_share = EXCLUDED.top100_share,
+            new_holders = EXCLUDED.new_holders,
+            churned_holders = EXCLUDED.churned_holders,
+            balance_buckets = EXCLUDED.balance_buckets,
+            taken_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(token_address)
+    .bind(day)
+    .bind(prev_day)
+    .bind(HOLDER_BUCKETS as i32)
+    .bind(chain_id)
+    .traced()
+    .fetch_one(&mut *tx)
+    .await
+    .record_row()?;
+
+    // Keep only the previous day's set and today's.
+    sqlx::query(
+        "DELETE FROM holder_sets WHERE chain_id = $4 AND token_address = $1 AND (day >= $2 OR day < $3)",
+    )
+    .bind(token_address)
+    .bind(day)
+    .bind(prev_day)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+    sqlx::query(
+        r#"
+        INSERT INTO holder_sets (chain_id, token_address, day, address)
+        SELECT chain_id, token_address, $2, address
+        FROM accounts
+        WHERE chain_id = $3 AND token_address = $1 AND CAST(balance AS NUMERIC) > 0
+        "#,
+    )
+    .bind(token_address)
+    .bind(day)
+    .bind(chain_id)
+    .traced()
+    .execute(&mut *tx)
+    .await
+    .record_rows()?;
+
+    tx.commit().await?;
+    Ok(snapshot)
+}
+
+/// Stored holder distribution snapshots for a token, newest first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_holder_snapshots(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: &TokenAddress,
+    limit: i64,
+) -> Result<Vec<HolderSnapshot>, sqlx::Error> {
+    sqlx::query_as::<_, HolderSnapshot>(
+        "SELECT * FROM holder_snapshots WHERE chain_id = $3 AND token_address = $1 ORDER BY day DESC LIMIT $2",
+    )
+    .bind(token_address.as_str())
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+// ─── Velocity ───────────────────────────────────────────────────────────────
+
+/// CTE with one `velocity` row per currency and calendar day of the last `$2`
+/// days on chain `$3`, for the token `$1` (in base units) or, when `$1` is NULL,
+/// across all tokens of each currency (in decimal units). Supply is anchored on
+/// `tokens.total_supply` and walked back through `daily_stats.net_issuance`, so
+/// issuance from before indexing started still counts; days without activity
+/// carry the supply of the last day before them.
+const VELOCITY_DAYS: &str = r#"
+    WITH scoped AS (
+        SELECT address, currency, CAST(total_supply AS NUMERIC) AS total_supply,
+               CASE WHEN $1::TEXT IS NULL THEN POWER(10::NUMERIC, decimals) ELSE 1 END AS unit
+        FROM tokens
+        WHERE chain_id = $3 AND ($1::TEXT IS NULL OR address = $1)
+    ),
+    token_days AS (
+        SELECT t.currency, s.day,
+               SUM(CAST(s.transfer_volume AS NUMERIC) / t.unit) AS volume,
+               SUM(CAST(s.net_issuance AS NUMERIC) / t.unit) AS net
+        FROM daily_stats s
+        JOIN scoped t ON t.address = s.token_address
+        WHERE s.chain_id = $3
+        GROUP BY t.currency, s.day
+    ),
+    anchor AS (
+        SELECT t.currency, SUM(t.total_supply / t.unit) AS supply_now,
+               SUM(t.total_supply / t.unit)
+                   - COALESCE((SELECT SUM(d.net) FROM token_days d WHERE d.currency = t.currency), 0)
+                   AS supply_before
+        FROM scoped t
+        GROUP BY t.currency
+    ),
+    supply AS (
+        -- Supply at the end of a day: today's supply less what was issued after it.
+        SELECT d.currency, d.day, d.volume, d.net,
+               a.supply_now - COALESCE(SUM(d.net) OVER (
+                   PARTITION BY d.currency ORDER BY d.day DESC
+                   ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+               ), 0) AS supply_end
+        FROM token_days d
+        JOIN anchor a ON a.currency = d.currency
+    ),
+    calendar AS (
+        SELECT a.currency, a.supply_before, g::DATE AS day
+        FROM anchor a
+        CROSS JOIN generate_series(CURRENT_DATE - ($2::INT - 1), CURRENT_DATE, INTERVAL '1 day') AS g
+    ),
+    velocity AS (
+        SELECT c.currency, c.day,
+               COALESCE(s.volume, 0) AS volume,
+               COALESCE(
+                   s.supply_end - s.net / 2,
+                   (SELECT p.supply_end FROM supply p
+                    WHERE p.currency = c.currency AND p.day < c.day
+                    ORDER BY p.day DESC LIMIT 1),
+                   c.supply_before
+               ) AS average_supply
+        FROM calendar c
+        LEFT JOIN supply s ON s.currency = c.currency AND s.day = c.day
+    )
+"#;
+
+/// Amount precision of [`VELOCITY_DAYS`]: whole base units for a single token,
+/// 6 decimal places across tokens.
+const VELOCITY_SCALE: &str = "CASE WHEN $1::TEXT IS NULL THEN 6 ELSE 0 END";
+
+/// Daily velocity over the last `days` days for one token or (with `None`)
+/// for each currency across its tokens in decimal units, ordered by currency
+/// and then newest day first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_velocity_days(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: Option<&TokenAddress>,
+    days: i32,
+) -> Result<Vec<VelocityDay>, sqlx::Error> {
+    let sql = format!(
+        r#"{VELOCITY_DAYS}
+        SELECT currency, day,
+               TRIM_SCALE(ROUND(volume, {VELOCITY_SCALE}))::TEXT AS transfer_volume,
+               TRIM_SCALE(ROUND(average_supply, {VELOCITY_SCALE}))::TEXT AS average_supply,
+               TRIM_SCALE(ROUND(volume / NULLIF(average_supply, 0), 6))::TEXT AS velocity
+        FROM velocity
+        ORDER BY currency, day DESC
+        "#
+    );
+    sqlx::query_as(&sql)
+        .bind(token_address.map(TokenAddress::as_str))
+        .bind(days)
+        .bind(chain_id)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+/// Velocity and average holding time over the last `days` days for one token
+/// or (with `None`) for each currency across its tokens in decimal units.
+/// A token's summary is a single row; an unknown token has none.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_velocity_summaries(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: Option<&TokenAddress>,
+    days: i32,
+) -> Result<Vec<VelocitySummary>, sqlx::Error> {
+    let sql = format!(
+        r#"{VELOCITY_DAYS}
+        SELECT currency,
+               TRIM_SCALE(ROUND(SUM(volume), {VELOCITY_SCALE}))::TEXT AS transfer_volume,
+               TRIM_SCALE(ROUND(AVG(average_supply), {VELOCITY_SCALE}))::TEXT AS average_supply,
+               TRIM_SCALE(ROUND(SUM(volume) / NULLIF(AVG(average_supply), 0), 6))::TEXT AS velocity,
+               TRIM_SCALE(ROUND($2 * AVG(average_supply) / NULLIF(SUM(volume), 0), 2))::TEXT
+                   AS average_holding_days
+        FROM velocity
+        GROUP BY currency
+        ORDER BY currency
+        "#
+    );
+    sqlx::query_as(&sql)
+        .bind(token_address.map(TokenAddress::as_str))
+        .bind(days)
+        .bind(chain_id)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+/// Turnover of a token's holders over the last `days` days, grouped by their
+/// current balance rank (`top_10`, `top_11_100`, `rest`) plus `exited` senders.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_cohort_turnover(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: &TokenAddress,
+    days: i32,
+) -> Result<Vec<CohortTurnover>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH ranked AS (
+            SELECT address, CAST(balance AS NUMERIC) AS balance,
+                   ROW_NUMBER() OVER (ORDER BY CAST(balance AS NUMERIC) DESC) AS rank
+            FROM accounts
+            WHERE chain_id = $3 AND token_address = $1 AND CAST(balance AS NUMERIC) > 0
+        ),
+        sent AS (
+            SELECT from_address AS address, SUM(CAST(amount AS NUMERIC)) AS volume
+            FROM transfers
+            WHERE chain_id = $3 AND token_address = $1
+              AND event_type = 'transfer'
+              AND block_time >= CURRENT_DATE - ($2::INT - 1)
+            GROUP BY from_address
+        ),
+        members AS (
+            SELECT address, balance, rank,
+                   CASE WHEN rank <= 10 THEN 'top_10'
+                        WHEN rank <= 100 THEN 'top_11_100'
+                        ELSE 'rest'
+                   END AS cohort
+            FROM ranked
+            UNION ALL
+            SELECT s.address, 0, NULL, 'exited'
+            FROM sent s
+            WHERE NOT EXISTS (SELECT 1 FROM ranked r WHERE r.address = s.address)
+        )
+        SELECT m.cohort,
+               COUNT(*) AS holders,
+               SUM(m.balance)::TEXT AS balance,
+               COALESCE(SUM(s.volume), 0)::TEXT AS outgoing_volume,
+               TRIM_SCALE(ROUND(COALESCE(SUM(s.volume), 0) / NULLIF(SUM(m.balance), 0), 6))::TEXT
+                   AS turnover
+        FROM members m
+        LEFT JOIN sent s ON s.address = m.address
+        GROUP BY m.cohort
+        ORDER BY MIN(m.rank) NULLS LAST
+        "#,
+    )
+    .bind(token_address.as_str())
+    .bind(days)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+// ─── Issuance ───────────────────────────────────────────────────────────────
+
+/// Mint/burn totals per `interval` from `since` onwards, newest first, read from
+/// `daily_stats` — for one token in base units or (with `None`) across all
+/// tokens in decimal units. Periods without activity are omitted.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_issuance_periods(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: Option<&TokenAddress>,
+    interval: IssuanceInterval,
+    since: NaiveDate,
+) -> Result<Vec<IssuancePeriod>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT DATE_TRUNC($2, s.day)::DATE AS period,
+               SUM(s.mint_count)::BIGINT AS mint_count,
+               TRIM_SCALE(SUM(CAST(s.mint_volume AS NUMERIC) / t.unit))::TEXT AS mint_volume,
+               SUM(s.burn_count)::BIGINT AS burn_count,
+               TRIM_SCALE(SUM(CAST(s.burn_volume AS NUMERIC) / t.unit))::TEXT AS burn_volume,
+               TRIM_SCALE(SUM(CAST(s.net_issuance AS NUMERIC) / t.unit))::TEXT AS net_issuance
+        FROM daily_stats s
+        JOIN (
+            SELECT address,
+                   CASE WHEN $1::TEXT IS NULL THEN POWER(10::NUMERIC, decimals) ELSE 1 END AS unit
+            FROM tokens
+            WHERE chain_id = $4
+        ) t ON t.address = s.token_address
+        WHERE s.chain_id = $4
+          AND ($1::TEXT IS NULL OR s.token_address = $1)
+          AND s.day >= $3
+          AND (s.mint_count > 0 OR s.burn_count > 0)
+        GROUP BY DATE_TRUNC($2, s.day)
+        ORDER BY period DESC
+        "#,
+    )
+    .bind(token_address.map(TokenAddress::as_str))
+    .bind(interval.as_str())
+    .bind(since)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Addresses with the largest `event_type` (`"mint"` or `"burn"`) volume for a
+/// token since `since`: mint recipients, or the holders whose tokens were burned.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_top_issuance_parties(
+    pool: &PgPool,
+    chain_id: i64,
+    token_address: &TokenAddress,
+    event_type: &str,
+    since: NaiveDate,
+    limit: i64,
+) -> Result<Vec<IssuanceParty>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT CASE WHEN $2 = 'mint' THEN to_address ELSE from_address END AS address,
+               SUM(CAST(amount AS NUMERIC))::TEXT AS volume,
+               COUNT(*) AS count
+        FROM transfers
+        WHERE chain_id = $5 AND token_address = $1 AND event_type = $2 AND block_time >= $3
+        GROUP BY 1
+        ORDER BY SUM(CAST(amount AS NUMERIC)) DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(token_address.as_str())
+    .bind(event_type)
+    .bind(since)
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Per-token mint/burn totals since `since` in decimal units, largest net issuance first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token_issuance_totals(
+    pool: &PgPool,
+    chain_id: i64,
+    since: NaiveDate,
+) -> Result<Vec<TokenIssuance>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT t.address AS token_address, t.symbol,
+               TRIM_SCALE(SUM(CAST(s.mint_volume AS NUMERIC)) / POWER(10::NUMERIC, t.decimals))::TEXT AS mint_volume,
+               TRIM_SCALE(SUM(CAST(s.burn_volume AS NUMERIC)) / POWER(10::NUMERIC, t.decimals))::TEXT AS burn_volume,
+               TRIM_SCALE(SUM(CAST(s.net_issuance AS NUMERIC)) / POWER(10::NUMERIC, t.decimals))::TEXT AS net_issuance
+        FROM daily_stats s
+        JOIN tokens t ON t.chain_id = s.chain_id AND t.address = s.token_address
+        WHERE s.chain_id = $2 AND s.day >= $1 AND (s.mint_count > 0 OR s.burn_count > 0)
+        GROUP BY t.address, t.symbol, t.decimals
+        ORDER BY SUM(CAST(s.net_issuance AS NUMERIC)) / POWER(10::NUMERIC, t.decimals) DESC
+        "#,
+    )
+    .bind(since)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+// ─── FX Rates ───────────────────────────────────────────────────────────────
+
+/// Upsert a batch of daily FX rates (latest write wins per base/quote/day).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn upsert_fx_rates(pool: &PgPool, rates: &[FxRate]) -> Result<(), sqlx::Error> {
+    if rates.is_empty() {
+        return Ok(());
+    }
+
+    let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(
+        "INSERT INTO fx_rates (base_currency, quote_currency, day, rate, source) ",
+    );
+
+    qb.push_values(rates, |mut b, r| {
+        b.push_bind(&r.base_currency)
+            .push_bind(&r.quote_currency)
+            .push_bind(r.day)
+            .push_bind(&r.rate)
+            .push_bind(&r.source);
+    });
+
+    qb.push(
+        " ON CONFLICT (base_currency, quote_currency, day) DO UPDATE SET rate = EXCLUDED.rate, source = EXCLUDED.source",
+    );
+    qb.build().traced().execute(pool).await.record_rows()?;
+    Ok(())
+}
+
+/// Get stored FX rates, newest first, optionally filtered by base and/or quote currency.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_fx_rates(
+    pool: &PgPool,
+    base_currency: Option<&str>,
+    quote_currency: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FxRate>, sqlx::Error> {
+    sqlx::query_as::<_, FxRate>(
+        r#"
+        SELECT * FROM fx_rates
+        WHERE ($1::TEXT IS NULL OR base_currency = $1)
+          AND ($2::TEXT IS NULL OR quote_currency = $2)
+        ORDER BY day DESC, base_currency, quote_currency
+        LIMIT $3
+        "#,
+    )
+    .bind(base_currency)
+    .bind(quote_currency)
+    .bind(limit)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// CTE pricing every (token, day) of chain `$2`'s `daily_stats` volume in the
+/// quote currency `$1`.
+///
+/// Raw amounts are scaled by the token's `decimals` and multiplied by the rate
+/// closest to that day (a stored inverse pair is used if the direct pair is
+/// missing). Tokens already denominated in `$1` use a rate of 1; tokens with no
+/// usable rate get a NULL `value` and are left out of the sums. When `$1` is
+/// NULL every rate is 1, so `value` is just the decimal-scaled volume.
+const PRICED_TOKEN_DAYS: &str = r#"
+    WITH token_days AS (
+        SELECT token_address, day,
+               CAST(transfer_volume AS NUMERIC) + CAST(mint_volume AS NUMERIC)
+                   + CAST(burn_volume AS NUMERIC) AS volume,
+               transfer_count + mint_count + burn_count AS tx_count
+        FROM daily_stats
+        WHERE chain_id = $2
+    ),
+    priced AS (
+        SELECT d.token_address, d.day, d.tx_count,
+               d.volume / POWER(10::NUMERIC, t.decimals) * fx.rate AS value
+        FROM token_days d
+        JOIN tokens t ON t.chain_id = $2 AND t.address = d.token_address
+        LEFT JOIN LATERAL (
+            SELECT CASE
+                WHEN $1::TEXT IS NULL OR UPPER(t.currency) = $1 THEN 1::NUMERIC
+                ELSE (
+                    SELECT CASE WHEN r.base_currency = UPPER(t.currency)
+                                THEN CAST(r.rate AS NUMERIC)
+                                ELSE 1 / CAST(r.rate AS NUMERIC)
+                           END
+                    FROM fx_rates r
+                    WHERE (r.base_currency = UPPER(t.currency) AND r.quote_currency = $1)
+                       OR (r.base_currency = $1 AND r.quote_currency = UPPER(t.currency))
+                    ORDER BY ABS(r.day - d.day), r.day DESC, r.base_currency = UPPER(t.currency) DESC
+                    LIMIT 1
+                )
+            END AS rate
+        ) fx ON TRUE
+    )
+"#;
+
+/// Sum of `priced.value` — 6 decimal places once converted, otherwise exact
+/// up to 18 — with trailing zeros trimmed.
+const PRICED_SUM: &str =
+    "TRIM_SCALE(ROUND(SUM(value), CASE WHEN $1::TEXT IS NULL THEN 18 ELSE 6 END))";
+
+/// Per-token transfer volume in decimal units, converted into `quote_currency` if given.
+/// Returns (address, symbol, total_volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_token_volumes_normalized(
+    pool: &PgPool,
+    chain_id: i64,
+    quote_currency: Option<&str>,
+) -> Result<Vec<(String, String, String, i64)>, sqlx::Error> {
+    let sql = format!(
+        r#"{PRICED_TOKEN_DAYS}
+        SELECT t.address, t.symbol,
+               COALESCE({PRICED_SUM}, 0)::TEXT AS total_volume,
+               COALESCE(SUM(p.tx_count), 0)::BIGINT AS transfer_count
+        FROM tokens t
+        LEFT JOIN priced p ON p.token_address = t.address
+        WHERE t.chain_id = $2
+        GROUP BY t.address, t.symbol
+        ORDER BY COALESCE(SUM(p.value), 0) DESC
+        "#
+    );
+    sqlx::query_as(&sql)
+        .bind(quote_currency)
+        .bind(chain_id)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+/// Global aggregate stats in decimal units, converted into `quote_currency` if given.
+/// Returns (total_volume, total_transfers).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_global_stats_normalized(
+    pool: &PgPool,
+    chain_id: i64,
+    quote_currency: Option<&str>,
+) -> Result<(String, i64), sqlx::Error> {
+    let sql = format!(
+        r#"{PRICED_TOKEN_DAYS}
+        SELECT COALESCE({PRICED_SUM}, 0)::TEXT,
+               COALESCE(SUM(tx_count), 0)::BIGINT
+        FROM priced
+        "#
+    );
+    sqlx::query_as(&sql)
+        .bind(quote_currency)
+        .bind(chain_id)
+        .traced()
+        .fetch_one(pool)
+        .await
+        .record_row()
+}
+
+/// Daily transfer volume across all tokens in decimal units, converted into `quote_currency` if given.
+/// Returns rows of (date, total_volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_daily_volume_normalized(
+    pool: &PgPool,
+    chain_id: i64,
+    quote_currency: Option<&str>,
+    limit: i64,
+) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
+    let sql = format!(
+        r#"{PRICED_TOKEN_DAYS}
+        SELECT day::TEXT AS day,
+               COALESCE({PRICED_SUM}, 0)::TEXT AS volume,
+               SUM(tx_count)::BIGINT AS tx_count
+        FROM priced
+        GROUP BY day
+        ORDER BY day DESC
+        LIMIT $3
+        "#
+    );
+    sqlx::query_as(&sql)
+        .bind(quote_currency)
+        .bind(chain_id)
+        .bind(limit)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+/// Monthly transfer volume across all tokens in decimal units, converted into `quote_currency` if given.
+/// Returns rows of (month, total_volume, transfer_count).
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_monthly_volume_normalized(
+    pool: &PgPool,
+    chain_id: i64,
+    quote_currency: Option<&str>,
+    limit: i64,
+) -> Result<Vec<(String, String, i64)>, sqlx::Error> {
+    let sql = format!(
+        r#"{PRICED_TOKEN_DAYS}
+        SELECT TO_CHAR(DATE_TRUNC('month', day), 'YYYY-MM') AS month,
+               COALESCE({PRICED_SUM}, 0)::TEXT AS volume,
+               SUM(tx_count)::BIGINT AS tx_count
+        FROM priced
+        GROUP BY DATE_TRUNC('month', day)
+        ORDER BY month DESC
+        LIMIT $3
+        "#
+    );
+    sqlx::query_as(&sql)
+        .bind(quote_currency)
+        .bind(chain_id)
+        .bind(limit)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+// ─── Address Labels ─────────────────────────────────────────────────────────
+
+/// Insert or replace labels, tagging them with `source` (`import`, `admin`, …).
+///
+/// When an address appears more than once, its last entry wins. Rows are
+/// written in chunks to stay under PostgreSQL's bind-parameter limit, all in
+/// one transaction.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn upsert_address_labels(
+    pool: &PgPool,
+    labels: &[LabelEntry],
+    source: &str,
+) -> Result<(), sqlx::Error> {
+    let labels = last_label_per_address(labels);
+    if labels.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    for chunk in labels.chunks(10_000) {
+        let mut qb: QueryBuilder<Postgres> =
+            QueryBuilder::new("INSERT INTO address_labels (address, name, category, source) ");
+        qb.push_values(chunk, |mut b, l| {
+            b.push_bind(l.address.as_str())
+                .push_bind(&l.name)
+                .push_bind(l.category.as_str())
+                .push_bind(source);
+        });
+        qb.push(
+            r#" ON CONFLICT (address) DO UPDATE SET
+                name = EXCLUDED.name,
+                category = EXCLUDED.category,
+                source = EXCLUDED.source,
+                updated_at = NOW()"#,
+        );
+        qb.build().traced().execute(&mut *tx).await.record_rows()?;
+    }
+    bump_label_count(&mut tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Count a label write on every registered network. Labels are shared, so
+/// this changes the API's cache keys everywhere, as `reindex_count` does for
+/// one network.
+async fn bump_label_count(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO indexer_state (chain_id, key, value)
+        SELECT chain_id, 'label_count', '1' FROM networks
+        ON CONFLICT (chain_id, key) DO UPDATE
+        SET value = (indexer_state.value::BIGINT + 1)::TEXT, updated_at = NOW()
+        "#,
+    )
+    .traced()
+    .execute(conn)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Keep only the last entry for each address, in first-seen order.
+///
+/// A single `INSERT … ON CONFLICT DO UPDATE` cannot touch the same row twice.
+fn last_label_per_address(labels: &[LabelEntry]) -> Vec<&LabelEntry> {
+    let mut position = std::collections::HashMap::new();
+    let mut out: Vec<&LabelEntry> = Vec::with_capacity(labels.len());
+    for label in labels {
+        match position.get(&label.address) {
+            Some(&i) => out[i] = label,
+            None => {
+                position.insert(&label.address, out.len());
+                out.push(label);
+            }
+        }
+    }
+    out
+}
+
+/// Labels ordered by name, optionally limited to one category.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_address_labels(
+    pool: &PgPool,
+    category: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AddressLabel>, sqlx::Error> {
+    sqlx::query_as::<_, AddressLabel>(
+        r#"
+        SELECT * FROM address_labels
+        WHERE $1::TEXT IS NULL OR category = $1
+        ORDER BY name, address
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(category)
+    .bind(limit)
+    .bind(offset)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Labels for the given addresses; unlabeled addresses are simply absent.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_labels_for_addresses(
+    pool: &PgPool,
+    addresses: &[String],
+) -> Result<Vec<AddressLabel>, sqlx::Error> {
+    sqlx::query_as::<_, AddressLabel>("SELECT * FROM address_labels WHERE address = ANY($1)")
+        .bind(addresses)
+        .traced()
+        .fetch_all(pool)
+        .await
+        .record_rows()
+}
+
+/// Delete a label. Returns whether it existed.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn delete_address_label(pool: &PgPool, address: &str) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("DELETE FROM address_labels WHERE address = $1")
+        .bind(address)
+        .traced()
+        .execute(&mut *tx)
+        .await
+        .record_rows()?;
+    let deleted = result.rows_affected() > 0;
+    if deleted {
+        bump_label_count(&mut tx).await?;
+    }
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+// ─── Webhooks ───────────────────────────────────────────────────────────────
+
+/// Create a subscription to chain `chain_id`'s transfers, signed with `secret`.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn create_webhook_subscription(
+    pool: &PgPool,
+    chain_id: i64,
+    spec: &WebhookSubscriptionSpec,
+    secret: &str,
+) -> Result<WebhookSubscription, sqlx::Error> {
+    sqlx::query_as::<_, WebhookSubscription>(
+        r#"
+        INSERT INTO webhook_subscriptions
+            (chain_id, url, secret, description, token_address, address, min_amount, event_type, active)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(chain_id)
+    .bind(&spec.url)
+    .bind(secret)
+    .bind(&spec.description)
+    .bind(&spec.token_address)
+    .bind(&spec.address)
+    .bind(&spec.min_amount)
+    .bind(&spec.event_type)
+    .bind(spec.active)
+    .traced()
+    .fetch_one(pool)
+    .await
+    .record_row()
+}
+
+/// Get chain `chain_id`'s subscriptions, oldest first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_webhook_subscriptions(
+    pool: &PgPool,
+    chain_id: i64,
+) -> Result<Vec<WebhookSubscription>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookSubscription>(
+        "SELECT * FROM webhook_subscriptions WHERE chain_id = $1 ORDER BY id",
+    )
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Get a single subscription by id.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_webhook_subscription(
+    pool: &PgPool,
+    id: i64,
+) -> Result<Option<WebhookSubscription>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookSubscription>("SELECT * FROM webhook_subscriptions WHERE id = $1")
+        .bind(id)
+        .traced()
+        .fetch_optional(pool)
+        .await
+        .record_rows()
+}
+
+/// Replace a subscription's editable fields. The secret is kept.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn update_webhook_subscription(
+    pool: &PgPool,
+    id: i64,
+    spec: &WebhookSubscriptionSpec,
+) -> Result<Option<WebhookSubscription>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookSubscription>(
+        r#"
+        UPDATE webhook_subscriptions
+        SET url = $2, description = $3, token_address = $4, address = $5,
+            min_amount = $6, event_type = $7, active = $8
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&spec.url)
+    .bind(&spec.description)
+    .bind(&spec.token_address)
+    .bind(&spec.address)
+    .bind(&spec.min_amount)
+    .bind(&spec.event_type)
+    .bind(spec.active)
+    .traced()
+    .fetch_optional(pool)
+    .await
+    .record_rows()
+}
+
+/// Delete a subscription with its pending deliveries and dead letters.
+/// Returns `false` if it did not exist.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn delete_webhook_subscription(pool: &PgPool, id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+        .bind(id)
+        .traced()
+        .execute(pool)
+        .await
+        .record_rows()?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Match the transfers of blocks `from_block..=to_block` (of one token, if
+/// given) against the chain's active subscriptions and queue a delivery for every match
+/// not made before.
+///
+/// Must run in the transaction that inserted those transfers, so a delivery
+/// is queued exactly when its transfer commits. Matches are remembered by
+/// `(chain_id, transaction_hash, log_index)` in `webhook_matches`, so
+/// transfers stored again by a reprocess or re-index do not fire twice. The
+/// payload is rendered here, in SQL, so it reflects the transfer as indexed.
+/// Returns the number of queued deliveries.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn enqueue_webhook_deliveries(
+    conn: &mut PgConnection,
+    chain_id: i64,
+    from_block: i64,
+    to_block: i64,
+    token: Option<&str>,
+) -> Result<u64, sqlx::Error> {
+    let queued = sqlx::query(
+        r#"
+        WITH matched AS (
+            INSERT INTO webhook_matches (subscription_id, chain_id, transaction_hash, log_index)
+            SELECT s.id, t.chain_id, t.transaction_hash, t.log_index
+            FROM transfers t
+            JOIN webhook_subscriptions s
+              ON s.chain_id = t.chain_id
+             AND s.active
+             AND (s.token_address IS NULL OR s.token_address = t.token_address)
+             AND (s.address IS NULL OR s.address = t.from_address OR s.address = t.to_address)
+             AND (s.event_type IS NULL OR s.event_type = t.event_type)
+             AND (s.min_amount IS NULL OR CAST(t.amount AS NUMERIC) >= CAST(s.min_amount AS NUMERIC))
+            WHERE t.chain_id = $1 AND t.block_number BETWEEN $2 AND $3
+              AND ($4::TEXT IS NULL OR t.token_address = $4)
+            ON CONFLICT DO NOTHING
+            RETURNING subscription_id, transaction_hash, log_index
+        )
+        INSERT INTO webhook_deliveries (chain_id, subscription_id, transfer_id, payload)
+        SELECT t.chain_id, m.subscription_id, t.id,
+               json_build_object('event', 'transfer', 'subscription_id', m.subscription_id, 'transfer', row_to_json(t))::TEXT
+        FROM matched m
+        JOIN transfers t
+          ON t.chain_id = $1 AND t.transaction_hash = m.transaction_hash AND t.log_index = m.log_index
+        ON CONFLICT (subscription_id, transfer_id) DO NOTHING
+        "#,
+    )
+    .bind(chain_id)
+    .bind(from_block)
+    .bind(to_block)
+    .bind(token)
+    .traced()
+    .execute(&mut *conn)
+    .await
+    .record_rows()?
+    .rows_affected();
+    Ok(queued)
+}
+
+/// Get up to `limit` of chain `chain_id`'s deliveries that are due, for active
+/// subscriptions only.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_due_webhook_deliveries(
+    pool: &PgPool,
+    chain_id: i64,
+    limit: i64,
+) -> Result<Vec<PendingWebhookDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, PendingWebhookDelivery>(
+        r#"
+        SELECT d.id, d.subscription_id, d.transfer_id, d.payload, d.attempts, s.url, s.secret
+        FROM webhook_deliveries d
+        JOIN webhook_subscriptions s ON s.id = d.subscription_id
+        WHERE d.chain_id = $2 AND s.active AND d.next_attempt_at <= NOW()
+        ORDER BY d.next_attempt_at, d.id
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .bind(chain_id)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Remove a delivery that succeeded.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn complete_webhook_delivery(pool: &PgPool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM webhook_deliveries WHERE id = $1")
+        .bind(id)
+        .traced()
+        .execute(pool)
+        .await
+        .record_rows()?;
+    Ok(())
+}
+
+/// Record a failed attempt and schedule the next one `retry_in_secs` from now.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn retry_webhook_delivery(
+    pool: &PgPool,
+    id: i64,
+    error: &str,
+    retry_in_secs: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE webhook_deliveries
+        SET attempts = attempts + 1,
+            last_error = $2,
+            next_attempt_at = NOW() + make_interval(secs => $3)
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(retry_in_secs as f64)
+    .traced()
+    .execute(pool)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Move a delivery that failed for the last time to the dead-letter table.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn dead_letter_webhook_delivery(
+    pool: &PgPool,
+    id: i64,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        WITH failed AS (
+            DELETE FROM webhook_deliveries WHERE id = $1 RETURNING *
+        )
+        INSERT INTO webhook_dead_letters (chain_id, subscription_id, transfer_id, payload, attempts, last_error)
+        SELECT chain_id, subscription_id, transfer_id, payload, attempts + 1, $2 FROM failed
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .traced()
+    .execute(pool)
+    .await
+    .record_rows()?;
+    Ok(())
+}
+
+/// Get a subscription's dead letters, newest first.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn get_webhook_dead_letters(
+    pool: &PgPool,
+    subscription_id: i64,
+    limit: i64,
+) -> Result<Vec<WebhookDeadLetter>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDeadLetter>(
+        r#"
+        SELECT * FROM webhook_dead_letters
+        WHERE subscription_id = $1
+        ORDER BY failed_at DESC, id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(limit)
+    .traced()
+    .fetch_all(pool)
+    .await
+    .record_rows()
+}
+
+/// Move a dead letter back onto the delivery queue with a fresh retry budget.
+/// Returns `false` if no such dead letter belongs to the subscription.
+#[tracing::instrument(skip_all, fields(db.statement, db.rows))]
+pub async fn requeue_webhook_dead_letter(
+    pool: &PgPool,
+    subscription_id: i64,
+    id: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        WITH revived AS (
+            DELETE FROM webhook_dead_letters WHERE id = $1 AND subscription_id = $2 RETURNING *
+        )
+        INSERT INTO webhook_deliveries (chain_id, subscription_id, transfer_id, payload)
+        SELECT chain_id, subscription_id, transfer_id, payload FROM revived
+        ON CONFLICT (subscription_id, transfer_id)
+        DO UPDATE SET attempts = 0, next_attempt_at = NOW(), last_error = NULL
+        "#,
+    )
+    .bind(id)
+    .bind(subscription_id)
+    .traced()
+    .execute(pool)
+    .await
+    .record_rows()?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempulse_core::labels::LabelCategory;
+
+    fn label(address: &str, name: &str) -> LabelEntry {
+        LabelEntry {
+            address: address.parse().unwrap(),
+            name: name.to_string(),
+            category: LabelCategory::Other,
+        }
+    }
+
+    #[test]
+    fn duplicate_labels_keep_the_last_entry() {
+        let a = "0x1111111111111111111111111111111111111111";
+        let b = "0x2222222222222222222222222222222222222222";
+        let labels = [label(a, "first"), label(b, "only"), label(a, "last")];
+
+        let names: Vec<_> = last_label_per_address(&labels)
+            .iter()
+            .map(|l| (l.address.as_str(), l.name.as_str()))
+            .collect();
+        assert_eq!(names, [(a, "last"), (b, "only")]);
+    }
+
+    // ─── Holder Distribution ────────────────────────────────────────────────
+
+    const TOKEN: &str = "0x20c0000000000000000000000000000000000001";
+
+    /// A token with one decimal place and the given holders (base units).
+    async fn seed_holders(pool: &PgPool, holders: &[(&str, &str)]) {
+        sqlx::query(
+            r#"
+            INSERT INTO tokens (chain_id, address, name, symbol, decimals, currency, total_supply,
+                                created_at_block, created_at_tx)
+            VALUES (1, $1, 'Test', 'TST', 1, 'USD', '0', 0, '0x')
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(TOKEN)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query("DELETE FROM accounts WHERE chain_id = 1 AND token_address = $1")
+            .bind(TOKEN)
+            .execute(pool)
+            .await
+            .unwrap();
+        for (address, balance) in holders {
+            sqlx::query(
+                "INSERT INTO accounts (chain_id, address, token_address, balance) VALUES (1, $1, $2, $3)",
+            )
+            .bind(address)
+            .bind(TOKEN)
+            .bind(balance)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, d).unwrap()
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn holder_snapshot_measures_a_fixed_distribution(pool: PgPool) {
+        // 0.5, 1.5, 2, 6 and 10 tokens, plus an emptied account.
+        seed_holders(
+            &pool,
+            &[
+                ("0xa", "5"),
+                ("0xb", "15"),
+                ("0xc", "20"),
+                ("0xd", "60"),
+                ("0xe", "100"),
+                ("0xf", "0"),
+            ],
+        )
+        .await;
+
+        let s = snapshot_holder_distribution(&pool, 1, TOKEN, day(1))
+            .await
+            .unwrap();
+        assert_eq!(s.holder_count, 5);
+        assert_eq!(s.total_balance, "200");
+        // 2 * (1*5 + 2*15 + 3*20 + 4*60 + 5*100) / (5 * 200) - 6/5
+        assert_eq!(s.gini, "0.47");
+        // 100 is exactly half, so it takes 60 more to hold a majority.
+        assert_eq!(s.nakamoto, 2);
+        assert_eq!(s.top10_share, "1");
+        assert_eq!(s.top100_share, "1");
+        // Under one token, [1, 10) and [10, 100).
+        assert_eq!(s.balance_buckets, [1, 3, 1, 0, 0, 0, 0, 0]);
+        // A token's first snapshot counts everyone as new.
+        assert_eq!((s.new_holders, s.churned_holders), (5, 0));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn holder_snapshot_counts_churn_against_the_previous_day(pool: PgPool) {
+        seed_holders(&pool, &[("0xa", "10"), ("0xb", "10"), ("0xc", "10")]).await;
+        snapshot_holder_distribution(&pool, 1, TOKEN, day(1))
+            .await
+            .unwrap();
+
+        // 0xa leaves, 0xd arrives.
+        seed_holders(&pool, &[("0xb", "10"), ("0xc", "10"), ("0xd", "10")]).await;
+        let s = snapshot_holder_distribution(&pool, 1, TOKEN, day(2))
+            .await
+            .unwrap();
+        assert_eq!((s.new_holders, s.churned_holders), (1, 1));
+        assert_eq!(s.gini, "0");
+        assert_eq!(s.nakamoto, 2);
+
+        // Retaking a day's snapshot still compares against the day before it.
+        seed_holders(&pool, &[("0xc", "10"), ("0xd", "10")]).await;
+        let s = snapshot_holder_distribution(&pool, 1, TOKEN, day(2))
+            .await
+            .unwrap();
+        assert_eq!((s.new_holders, s.churned_holders), (1, 2));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn holder_snapshot_of_a_token_without_holders_is_empty(pool: PgPool) {
+        seed_holders(&pool, &[("0xa", "0")]).await;
+
+        let s = snapshot_holder_distribution(&pool, 1, TOKEN, day(1))
+            .await
+            .unwrap();
+        assert_eq!((s.holder_count, s.total_balance.as_str()), (0, "0"));
+        assert_eq!((s.gini.as_str(), s.nakamoto), ("0", 0));
+        assert_eq!(s.top10_share, "0");
+        assert_eq!(s.balance_buckets, [0; HOLDER_BUCKETS]);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn label_writes_move_every_network_cursor(pool: PgPool) {
+        register_network(&pool, 1, "one").await.unwrap();
+        register_network(&pool, 2, "two").await.unwrap();
+        let label_counts = || async {
+            get_network_cursors(&pool)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(name, _, _, labels)| (name, labels))
+                .collect::<Vec<_>>()
+        };
+        let alice = "0x1111111111111111111111111111111111111111";
+
+        upsert_address_labels(&pool, &[label(alice, "Alice")], "admin")
+            .await
+            .unwrap();
+        assert_eq!(label_counts().await, [("one".into(), 1), ("two".into(), 1)]);
+
+        assert!(delete_address_label(&pool, alice).await.unwrap());
+        assert_eq!(label_counts().await, [("one".into(), 2), ("two".into(), 2)]);
+
+        // Nothing deleted, nothing to invalidate.
+        assert!(!delete_address_label(&pool, alice).await.unwrap());
+        assert_eq!(label_counts().await, [("one".into(), 2), ("two".into(), 2)]);
+    }
+
+    fn raw_log(block_number: i64) -> RawLog {
+        RawLog {
+            block_number,
+            log_index: 0,
+            transaction_hash: format!("0x{block_number:064x}"),
+            address: "0x20c0000000000000000000000000000000000001".to_string(),
+            topics: Vec::new(),
+            data: "0x".to_string(),
+            block_timestamp: Some(block_number),
+        }
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    #[ignore = "needs a Postgres server at DATABASE_URL"]
+    async fn replace_logs_swaps_one_range_and_extends_the_raw_logs_back(pool: PgPool) {
+        register_network(&pool, 1, "one").await.unwrap();
+        sqlx::query(
+            "INSERT INTO indexer_state (chain_id, key, value) VALUES (1, 'raw_logs_from_block', '11')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        insert_logs(&mut conn, 1, &[raw_log(5), raw_log(11), raw_log(12)])
+            .await
+            .unwrap();
+        let stored = || async {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT block_number FROM logs WHERE chain_id = 1 ORDER BY block_number",
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+
+        let block = IndexedBlock {
+            block_number: 10,
+            block_hash: "0x10".to_string(),
+            parent_hash: "0x09".to_string(),
+            timestamp: 10,
+        };
+        replace_logs(&mut conn, 1, 3, 10, &[raw_log(4), raw_log(7)], &[block])
+            .await
+            .unwrap();
+        assert_eq!(stored().await, [4, 7, 11, 12]);
+        assert_eq!(
+            get_block_hash(&pool, 1, 10).await.unwrap().as_deref(),
+            Some("0x10")
+        );
+        assert_eq!(get_raw_logs_from_block(&pool, 1).await.unwrap(), 3);
+
+        // Block 2 stays missing, so the raw logs still start at block 3.
+        replace_logs(&mut conn, 1, 1, 1, &[raw_log(1)], &[])
+            .await
+            .unwrap();
+        assert_eq!(stored().await, [1, 4, 7, 11, 12]);
+        assert_eq!(get_raw_logs_from_block(&pool, 1).await.unwrap(), 3);
+    }
 }
@@ -0,0 +1,69 @@
+//! Local webhook receiver for trying out subscriptions end to end.
+//!
+//! ```sh
+//! WEBHOOK_SECRET=<secret from POST /api/v1/webhooks> cargo run -p tempulse-webhooks --example receiver
+//! ```
+//!
+//! Listens on `RECEIVER_PORT` (default 4000), verifies each request's
+//! signature and prints the payload. Set `FAIL_EVERY=n` to answer every n-th
+//! request with a 500 and watch the dispatcher retry.
+
+use axum::{Router, body::Bytes, http::HeaderMap, http::StatusCode, routing::post};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+use tempulse_webhooks::{SIGNATURE_HEADER, verify};
+
+/// Signatures older than this are rejected as possible replays.
+const TOLERANCE_SECS: i64 = 300;
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let secret: Arc<str> = std::env::var("WEBHOOK_SECRET")
+        .expect("WEBHOOK_SECRET must be set")
+        .into();
+    let fail_every: u64 = std::env::var("FAIL_EVERY")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0);
+    let port: u16 = std::env::var("RECEIVER_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(4000);
+    let received = Arc::new(AtomicU64::new(0));
+
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: Bytes| {
+            let secret = secret.clone();
+            let received = received.clone();
+            async move {
+                let n = received.fetch_add(1, Ordering::Relaxed) + 1;
+                let signature = headers
+                    .get(SIGNATURE_HEADER)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                let now = chrono::Utc::now().timestamp();
+                if !verify(&secret, signature, &body, now, TOLERANCE_SECS) {
+                    tracing::warn!(request = n, "Rejected: bad signature");
+                    return StatusCode::UNAUTHORIZED;
+                }
+                if fail_every > 0 && n.is_multiple_of(fail_every) {
+                    tracing::info!(request = n, "Simulating failure");
+                    return StatusCode::INTERNAL_SERVER_ERROR;
+                }
+                tracing::info!(request = n, payload = %String::from_utf8_lossy(&body), "Delivered");
+                StatusCode::NO_CONTENT
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .expect("bind receiver port");
+    tracing::info!("Receiving webhooks on http://127.0.0.1:{port}/");
+    axum::serve(listener, app).await.expect("receiver server");
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use tempulse_storage::{self as storage, models::PendingWebhookDelivery};
+use tokio::task::JoinSet;
+
+use crate::signing::{SIGNATURE_HEADER, sign};
+
+/// Tuning knobs for the [`Dispatcher`].
+#[derive(Debug, Clone)]
+pub struct DispatcherConfig {
+    /// Pause between polls when there is nothing to do.
+    pub poll_interval: Duration,
+    /// Deliveries attempted (concurrently) per poll.
+    pub delivery_batch: i64,
+    /// Attempts before a delivery is moved to the dead-letter table.
+    pub max_attempts: i32,
+    /// Delay before the first retry; doubles on every further failure.
+    pub base_backoff: Duration,
+    /// Upper bound on the delay between retries.
+    pub max_backoff: Duration,
+    /// Per-request timeout.
+    pub request_timeout: Duration,
+}
+
+impl Default for DispatcherConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(2),
+            delivery_batch: 50,
+            max_attempts: 8,
+            base_backoff: Duration::from_secs(10),
+            max_backoff: Duration::from_secs(3600),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl DispatcherConfig {
+    /// Delay before the next attempt after `attempts` failures (1 = first failure).
+    pub fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+        self.base_backoff
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_backoff)
+    }
+}
+
+/// Delivers one chain's queued webhooks: the indexer matches transfers into
+/// `webhook_deliveries` as it stores them, and the dispatcher POSTs them to
+/// subscribers, retries with exponential backoff, and finally dead-letters them.
+pub struct Dispatcher {
+    pool: sqlx::PgPool,
+    chain_id: i64,
+    client: reqwest::Client,
+    config: DispatcherConfig,
+}
+
+impl Dispatcher {
+    pub fn new(pool: sqlx::PgPool, chain_id: i64, config: DispatcherConfig) -> eyre::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .user_agent(concat!("tempulse-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            pool,
+            chain_id,
+            client,
+            config,
+        })
+    }
+
+    /// Run forever. Errors are logged and the loop continues after a pause.
+    pub async fn run(self) {
+        tracing::info!(chain_id = self.chain_id, "Webhook dispatcher started");
+        loop {
+            match self.tick().await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => tracing::warn!(error = %e, "Webhook dispatch failed"),
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// Attempt due deliveries once.
+    /// Returns `true` if a full batch was processed and more work is likely waiting.
+    pub async fn tick(&self) -> eyre::Result<bool> {
+        let due = storage::repos::get_due_webhook_deliveries(
+            &self.pool,
+            self.chain_id,
+            self.config.delivery_batch,
+        )
+        .await?;
+        let full_batch = due.len() as i64 == self.config.delivery_batch;
+
+        let mut attempts = JoinSet::new();
+        for delivery in due {
+            let client = self.client.clone();
+            attempts.spawn(async move {
+                let result = post(&client, &delivery).await;
+                (delivery, result)
+            });
+        }
+
+        while let Some(joined) = attempts.join_next().await {
+            let (delivery, result) = joined?;
+            self.record(&delivery, result).await?;
+        }
+        Ok(full_batch)
+    }
+
+    async fn record(
+        &self,
+        delivery: &PendingWebhookDelivery,
+        result: Result<(), String>,
+    ) -> eyre::Result<()> {
+        let error = match result {
+            Ok(()) => {
+                storage::repos::complete_webhook_delivery(&self.pool, delivery.id).await?;
+                return Ok(());
+            }
+            Err(error) => error,
+        };
+
+        let attempts = delivery.attempts + 1;
+        if attempts >= self.config.max_attempts {
+            tracing::warn!(
+                delivery = delivery.id,
+                subscription = delivery.subscription_id,
+                attempts,
+                error = %error,
+                "Webhook delivery dead-lettered"
+            );
+            storage::repos::dead_letter_webhook_delivery(&self.pool, delivery.id, &error).await?;
+        } else {
+            let delay = self.config.backoff(attempts);
+            tracing::debug!(
+                delivery = delivery.id,
+                attempts,
+                retry_in = ?delay,
+                error = %error,
+                "Webhook delivery failed"
+            );
+            storage::repos::retry_webhook_delivery(
+                &self.pool,
+                delivery.id,
+                &error,
+                delay.as_secs() as i64,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// POST one signed payload. Any non-2xx response counts as a failure.
+async fn post(client: &reqwest::Client, delivery: &PendingWebhookDelivery) -> Result<(), String> {
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = sign(&delivery.secret, timestamp, delivery.payload.as_bytes());
+
+    let response = client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .header("X-Tempulse-Delivery", delivery.id.to_string())
+        .header("X-Tempulse-Event", "transfer")
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {status}"))
+    }
+}
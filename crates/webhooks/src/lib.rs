@@ -0,0 +1,8 @@
+//! Outbound webhooks: delivers the HMAC-signed payloads the indexer queues for
+//! transfers matching stored subscriptions, with retries.
+
+pub mod dispatcher;
+pub mod signing;
+
+pub use dispatcher::{Dispatcher, DispatcherConfig};
+pub use signing::{SIGNATURE_HEADER, sign, verify};
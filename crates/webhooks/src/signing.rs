@@ -0,0 +1,117 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header carrying the payload signature: `t=<unix seconds>,v1=<hex HMAC-SHA256>`.
+pub const SIGNATURE_HEADER: &str = "X-Tempulse-Signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(secret: &str, timestamp: i64, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Sign `body` as sent at `timestamp`, producing the [`SIGNATURE_HEADER`] value.
+///
+/// The MAC covers `"<timestamp>.<body>"`, so a captured request cannot be
+/// replayed with a fresh timestamp.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let signature = hex::encode(mac(secret, timestamp, body).finalize().into_bytes());
+    format!("t={timestamp},v1={signature}")
+}
+
+/// Check a [`SIGNATURE_HEADER`] value against `body`, rejecting signatures
+/// older than `tolerance_secs` relative to `now`. Comparison is constant-time.
+pub fn verify(secret: &str, header: &str, body: &[u8], now: i64, tolerance_secs: i64) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", t)) => timestamp = t.parse::<i64>().ok(),
+            Some(("v1", v)) => signature = hex::decode(v).ok(),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return false;
+    };
+
+    (now - timestamp).abs() <= tolerance_secs
+        && mac(secret, timestamp, body)
+            .verify_slice(&signature)
+            .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &[u8] = br#"{"event":"transfer"}"#;
+    const T: i64 = 1_700_000_000;
+
+    #[test]
+    fn signs_timestamp_dot_body() {
+        // HMAC-SHA256("whsec_test", "1700000000.{\"event\":\"transfer\"}")
+        assert_eq!(
+            sign(SECRET, T, BODY),
+            "t=1700000000,v1=415f0ef5a3857b5d951deb8a8d22fb2c20c104fa986071212289bcd085fd4e21"
+        );
+    }
+
+    #[test]
+    fn verifies_its_own_signature() {
+        let header = sign(SECRET, T, BODY);
+        assert!(verify(SECRET, &header, BODY, T, 300));
+        assert!(verify(SECRET, &header, BODY, T + 300, 300));
+        assert!(verify(SECRET, &header, BODY, T - 300, 300));
+    }
+
+    #[test]
+    fn accepts_fields_in_any_order_with_spaces() {
+        let header = sign(SECRET, T, BODY);
+        let (t, v1) = header.split_once(',').unwrap();
+        assert!(verify(SECRET, &format!("{v1}, {t}"), BODY, T, 300));
+    }
+
+    #[test]
+    fn rejects_tampering_and_wrong_secret() {
+        let header = sign(SECRET, T, BODY);
+        assert!(!verify(
+            SECRET,
+            &header,
+            br#"{"event":"transfer "}"#,
+            T,
+            300
+        ));
+        assert!(!verify("other", &header, BODY, T, 300));
+
+        // Moving the timestamp invalidates the MAC
+        let replayed = header.replace("t=1700000000", "t=1700000100");
+        assert!(!verify(SECRET, &replayed, BODY, T + 100, 300));
+    }
+
+    #[test]
+    fn rejects_stale_signatures() {
+        let header = sign(SECRET, T, BODY);
+        assert!(!verify(SECRET, &header, BODY, T + 301, 300));
+        assert!(!verify(SECRET, &header, BODY, T - 301, 300));
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        for header in [
+            "",
+            "t=1700000000",
+            "v1=415f0ef5a3857b5d951deb8a8d22fb2c20c104fa986071212289bcd085fd4e21",
+            "t=abc,v1=415f0ef5a3857b5d951deb8a8d22fb2c20c104fa986071212289bcd085fd4e21",
+            "t=1700000000,v1=not-hex",
+            "t=1700000000,v1=",
+        ] {
+            assert!(!verify(SECRET, header, BODY, T, 300), "{header:?}");
+        }
+    }
+}
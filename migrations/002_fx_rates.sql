@@ -0,0 +1,15 @@
+-- Tempulse: FX Rates
+-- Daily exchange rates used to normalize multi-currency volumes.
+
+-- ─── FX Rates ───────────────────────────────────────────────────────────────
+-- One unit of `base_currency` is worth `rate` units of `quote_currency`.
+CREATE TABLE IF NOT EXISTS fx_rates (
+    base_currency  TEXT NOT NULL,
+    quote_currency TEXT NOT NULL,
+    day            DATE NOT NULL,
+    rate           TEXT NOT NULL,
+    source         TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (base_currency, quote_currency, day)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fx_rates_quote ON fx_rates(quote_currency, day);
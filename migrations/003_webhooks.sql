@@ -0,0 +1,54 @@
+-- Tempulse: Outbound Webhooks
+-- Subscriptions, the pending delivery queue, and deliveries that exhausted their retries.
+
+-- ─── Subscriptions ──────────────────────────────────────────────────────────
+-- NULL filters match everything; `address` matches either side of a transfer.
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    id            BIGSERIAL PRIMARY KEY,
+    url           TEXT NOT NULL,
+    secret        TEXT NOT NULL,
+    description   TEXT NOT NULL DEFAULT '',
+    token_address TEXT,
+    address       TEXT,
+    min_amount    TEXT,
+    event_type    TEXT,
+    active        BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at    TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- ─── Pending Deliveries ─────────────────────────────────────────────────────
+-- The payload is rendered when the transfer is matched, so a delivery does not
+-- depend on the transfer row still existing (e.g. after a reorg rollback).
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id              BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    transfer_id     BIGINT NOT NULL,
+    payload         TEXT NOT NULL,
+    attempts        INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_error      TEXT,
+    created_at      TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (subscription_id, transfer_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at);
+
+-- ─── Dead Letters ───────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    id              BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    transfer_id     BIGINT NOT NULL,
+    payload         TEXT NOT NULL,
+    attempts        INT NOT NULL,
+    last_error      TEXT,
+    failed_at       TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_dead_letters_sub ON webhook_dead_letters(subscription_id);
+
+-- ─── Dispatcher Cursor ──────────────────────────────────────────────────────
+-- Highest `transfers.id` already matched against subscriptions. Seeded with the
+-- current maximum so existing history is not delivered.
+INSERT INTO indexer_state (key, value)
+SELECT 'webhook_last_transfer_id', COALESCE(MAX(id), 0)::TEXT FROM transfers
+ON CONFLICT (key) DO NOTHING;
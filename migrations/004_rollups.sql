@@ -0,0 +1,109 @@
+-- Tempulse: Daily / Monthly Rollups
+-- Per-token aggregates maintained incrementally by the indexer, so long-range
+-- time series never scan `transfers`. Volumes are base-unit integer strings.
+
+-- ─── Daily Stats ────────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS daily_stats (
+    token_address    TEXT NOT NULL REFERENCES tokens(address),
+    day              DATE NOT NULL,
+    transfer_count   BIGINT NOT NULL DEFAULT 0,
+    transfer_volume  TEXT NOT NULL DEFAULT '0',
+    mint_count       BIGINT NOT NULL DEFAULT 0,
+    mint_volume      TEXT NOT NULL DEFAULT '0',
+    burn_count       BIGINT NOT NULL DEFAULT 0,
+    burn_volume      TEXT NOT NULL DEFAULT '0',
+    net_issuance     TEXT NOT NULL DEFAULT '0',  -- mint_volume - burn_volume, may be negative
+    active_addresses BIGINT NOT NULL DEFAULT 0,  -- distinct non-zero senders/receivers
+    PRIMARY KEY (token_address, day)
+);
+
+CREATE INDEX IF NOT EXISTS idx_daily_stats_day ON daily_stats(day);
+
+-- Membership set behind daily_stats.active_addresses.
+CREATE TABLE IF NOT EXISTS daily_active_addresses (
+    token_address TEXT NOT NULL,
+    day           DATE NOT NULL,
+    address       TEXT NOT NULL,
+    PRIMARY KEY (token_address, day, address)
+);
+
+-- ─── Monthly Stats ──────────────────────────────────────────────────────────
+-- `month` is the first day of the calendar month.
+CREATE TABLE IF NOT EXISTS monthly_stats (
+    token_address    TEXT NOT NULL REFERENCES tokens(address),
+    month            DATE NOT NULL,
+    transfer_count   BIGINT NOT NULL DEFAULT 0,
+    transfer_volume  TEXT NOT NULL DEFAULT '0',
+    mint_count       BIGINT NOT NULL DEFAULT 0,
+    mint_volume      TEXT NOT NULL DEFAULT '0',
+    burn_count       BIGINT NOT NULL DEFAULT 0,
+    burn_volume      TEXT NOT NULL DEFAULT '0',
+    net_issuance     TEXT NOT NULL DEFAULT '0',
+    active_addresses BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (token_address, month)
+);
+
+CREATE INDEX IF NOT EXISTS idx_monthly_stats_month ON monthly_stats(month);
+
+CREATE TABLE IF NOT EXISTS monthly_active_addresses (
+    token_address TEXT NOT NULL,
+    month         DATE NOT NULL,
+    address       TEXT NOT NULL,
+    PRIMARY KEY (token_address, month, address)
+);
+
+-- Reorg repair rebuilds every period from a cutoff day onwards.
+CREATE INDEX IF NOT EXISTS idx_transfers_created_at ON transfers(created_at);
+
+-- ─── Backfill ───────────────────────────────────────────────────────────────
+INSERT INTO daily_active_addresses (token_address, day, address)
+SELECT DISTINCT t.token_address, DATE(t.created_at), a.address
+FROM transfers t
+CROSS JOIN LATERAL (VALUES (t.from_address), (t.to_address)) AS a(address)
+WHERE a.address <> '0x0000000000000000000000000000000000000000'
+ON CONFLICT DO NOTHING;
+
+INSERT INTO monthly_active_addresses (token_address, month, address)
+SELECT DISTINCT token_address, DATE_TRUNC('month', day)::DATE, address
+FROM daily_active_addresses
+ON CONFLICT DO NOTHING;
+
+INSERT INTO daily_stats (token_address, day, transfer_count, transfer_volume, mint_count, mint_volume,
+                         burn_count, burn_volume, net_issuance, active_addresses)
+SELECT s.token_address, s.day,
+       s.transfer_count, s.transfer_volume::TEXT, s.mint_count, s.mint_volume::TEXT,
+       s.burn_count, s.burn_volume::TEXT, (s.mint_volume - s.burn_volume)::TEXT,
+       (SELECT COUNT(*) FROM daily_active_addresses d
+        WHERE d.token_address = s.token_address AND d.day = s.day)
+FROM (
+    SELECT token_address, DATE(created_at) AS day,
+           COUNT(*) FILTER (WHERE event_type = 'transfer') AS transfer_count,
+           COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'transfer'), 0) AS transfer_volume,
+           COUNT(*) FILTER (WHERE event_type = 'mint') AS mint_count,
+           COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'mint'), 0) AS mint_volume,
+           COUNT(*) FILTER (WHERE event_type = 'burn') AS burn_count,
+           COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'burn'), 0) AS burn_volume
+    FROM transfers
+    GROUP BY token_address, DATE(created_at)
+) s
+ON CONFLICT DO NOTHING;
+
+INSERT INTO monthly_stats (token_address, month, transfer_count, transfer_volume, mint_count, mint_volume,
+                           burn_count, burn_volume, net_issuance, active_addresses)
+SELECT s.token_address, s.month,
+       s.transfer_count, s.transfer_volume::TEXT, s.mint_count, s.mint_volume::TEXT,
+       s.burn_count, s.burn_volume::TEXT, (s.mint_volume - s.burn_volume)::TEXT,
+       (SELECT COUNT(*) FROM monthly_active_addresses m
+        WHERE m.token_address = s.token_address AND m.month = s.month)
+FROM (
+    SELECT token_address, DATE_TRUNC('month', day)::DATE AS month,
+           SUM(transfer_count)::BIGINT AS transfer_count,
+           SUM(CAST(transfer_volume AS NUMERIC)) AS transfer_volume,
+           SUM(mint_count)::BIGINT AS mint_count,
+           SUM(CAST(mint_volume AS NUMERIC)) AS mint_volume,
+           SUM(burn_count)::BIGINT AS burn_count,
+           SUM(CAST(burn_volume AS NUMERIC)) AS burn_volume
+    FROM daily_stats
+    GROUP BY token_address, DATE_TRUNC('month', day)
+) s
+ON CONFLICT DO NOTHING;
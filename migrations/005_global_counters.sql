@@ -0,0 +1,50 @@
+-- Tempulse: Global Counters
+-- Running totals maintained by the indexer inside each batch transaction, so
+-- overview stats are single-row reads instead of scans over `transfers`.
+
+-- ─── Per-Token Totals ───────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS token_totals (
+    token_address  TEXT PRIMARY KEY REFERENCES tokens(address),
+    total_volume   TEXT NOT NULL DEFAULT '0',  -- transfers + mints + burns, base units
+    transfer_count BIGINT NOT NULL DEFAULT 0
+);
+
+-- ─── Global Totals (single row) ─────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS global_totals (
+    id               BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    total_volume     TEXT NOT NULL DEFAULT '0',
+    total_transfers  BIGINT NOT NULL DEFAULT 0,
+    active_addresses BIGINT NOT NULL DEFAULT 0
+);
+
+-- ─── Known Addresses ────────────────────────────────────────────────────────
+-- Every non-zero sender/receiver ever seen. `first_seen_block` lets a reorg
+-- drop exactly the addresses that only appeared in orphaned blocks.
+CREATE TABLE IF NOT EXISTS known_addresses (
+    address          TEXT PRIMARY KEY,
+    first_seen_block BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_known_addresses_block ON known_addresses(first_seen_block);
+
+-- ─── Backfill ───────────────────────────────────────────────────────────────
+INSERT INTO token_totals (token_address, total_volume, transfer_count)
+SELECT token_address, SUM(CAST(amount AS NUMERIC))::TEXT, COUNT(*)
+FROM transfers
+GROUP BY token_address
+ON CONFLICT DO NOTHING;
+
+INSERT INTO known_addresses (address, first_seen_block)
+SELECT a.address, MIN(t.block_number)
+FROM transfers t
+CROSS JOIN LATERAL (VALUES (t.from_address), (t.to_address)) AS a(address)
+WHERE a.address <> '0x0000000000000000000000000000000000000000'
+GROUP BY a.address
+ON CONFLICT DO NOTHING;
+
+INSERT INTO global_totals (id, total_volume, total_transfers, active_addresses)
+SELECT TRUE,
+       COALESCE((SELECT SUM(CAST(total_volume AS NUMERIC)) FROM token_totals), 0)::TEXT,
+       COALESCE((SELECT SUM(transfer_count) FROM token_totals), 0),
+       (SELECT COUNT(*) FROM known_addresses)
+ON CONFLICT DO NOTHING;
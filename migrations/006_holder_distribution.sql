@@ -0,0 +1,28 @@
+-- Tempulse: Holder Distribution
+-- Daily per-token snapshots of how balances in `accounts` are spread across holders.
+
+-- ─── Holder Snapshots ───────────────────────────────────────────────────────
+-- Refreshed periodically by the indexer; a past day keeps the last snapshot taken that day.
+CREATE TABLE IF NOT EXISTS holder_snapshots (
+    token_address   TEXT NOT NULL REFERENCES tokens(address),
+    day             DATE NOT NULL,
+    holder_count    BIGINT NOT NULL,
+    total_balance   TEXT NOT NULL,            -- sum of positive balances, base units
+    gini            TEXT NOT NULL,            -- 0 (equal) .. 1 (one holder owns everything)
+    nakamoto        BIGINT NOT NULL,          -- fewest holders owning more than half
+    top10_share     TEXT NOT NULL,            -- fraction of total_balance held by the top 10
+    top100_share    TEXT NOT NULL,
+    new_holders     BIGINT NOT NULL,          -- holders absent from the previous day's set
+    churned_holders BIGINT NOT NULL,          -- previous day's holders now at zero
+    balance_buckets BIGINT[] NOT NULL,        -- holder counts per power-of-ten balance bucket
+    taken_at        TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (token_address, day)
+);
+
+-- Holder sets of the latest two snapshot days, used to count new/churned holders.
+CREATE TABLE IF NOT EXISTS holder_sets (
+    token_address TEXT NOT NULL,
+    day           DATE NOT NULL,
+    address       TEXT NOT NULL,
+    PRIMARY KEY (token_address, day, address)
+);
@@ -0,0 +1,19 @@
+-- Tempulse: Address Labels
+-- Human-readable names for known addresses, so flows can be attributed to entities.
+
+-- ─── Address Labels ─────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS address_labels (
+    address    TEXT PRIMARY KEY,
+    name       TEXT NOT NULL,
+    category   TEXT NOT NULL CHECK (category IN
+                   ('exchange', 'issuer', 'treasury', 'bridge', 'contract', 'precompile', 'other')),
+    source     TEXT NOT NULL DEFAULT 'admin',  -- builtin | import | admin
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_labels_category ON address_labels(category);
+
+-- ─── Built-in Labels ────────────────────────────────────────────────────────
+INSERT INTO address_labels (address, name, category, source) VALUES
+    ('0x20fc000000000000000000000000000000000000', 'TIP-20 Factory', 'precompile', 'builtin')
+ON CONFLICT DO NOTHING;
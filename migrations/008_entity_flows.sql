@@ -0,0 +1,34 @@
+-- Tempulse: Entity Flows
+-- Token flows between entities (named groups of addresses) per token and day,
+-- maintained by the indexer inside each batch transaction.
+
+-- ─── Entity Membership ──────────────────────────────────────────────────────
+-- Loaded from ENTITIES_FILE by the indexer; entity_flows is rebuilt when it changes.
+CREATE TABLE IF NOT EXISTS entity_addresses (
+    address TEXT PRIMARY KEY,
+    entity  TEXT NOT NULL
+);
+
+-- ─── Entity Flows ───────────────────────────────────────────────────────────
+-- Addresses outside any entity count as '(unknown)'; the zero address of
+-- mints and burns counts as '(mint/burn)'.
+CREATE TABLE IF NOT EXISTS entity_flows (
+    token_address  TEXT NOT NULL REFERENCES tokens(address),
+    day            DATE NOT NULL,
+    source_entity  TEXT NOT NULL,
+    target_entity  TEXT NOT NULL,
+    volume         TEXT NOT NULL,   -- base units
+    transfer_count BIGINT NOT NULL,
+    PRIMARY KEY (day, token_address, source_entity, target_entity)
+);
+
+-- ─── Backfill ───────────────────────────────────────────────────────────────
+-- No entities exist yet, so every address is either unknown or the zero address.
+INSERT INTO entity_flows (token_address, day, source_entity, target_entity, volume, transfer_count)
+SELECT token_address, DATE(created_at),
+       CASE WHEN from_address = '0x0000000000000000000000000000000000000000' THEN '(mint/burn)' ELSE '(unknown)' END,
+       CASE WHEN to_address = '0x0000000000000000000000000000000000000000' THEN '(mint/burn)' ELSE '(unknown)' END,
+       SUM(CAST(amount AS NUMERIC))::TEXT, COUNT(*)
+FROM transfers
+GROUP BY 1, 2, 3, 4
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,14 @@
+-- Tempulse: Indexer Status
+-- Records the chain head the indexer last saw and every reorg it rolled back,
+-- so the API can report how fresh the indexed data is.
+
+-- ─── Indexer State ──────────────────────────────────────────────────────────
+ALTER TABLE indexer_state ADD COLUMN IF NOT EXISTS updated_at TIMESTAMP NOT NULL DEFAULT NOW();
+
+-- ─── Reorgs ─────────────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS reorgs (
+    id          BIGSERIAL PRIMARY KEY,
+    fork_block  BIGINT NOT NULL,           -- cursor was reset to this block
+    depth       BIGINT NOT NULL,           -- blocks rolled back
+    detected_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,123 @@
+-- Tempulse: Multiple Networks
+-- One database can hold several chains (testnet, mainnet, a local devnet),
+-- each written by its own indexer. Every table of indexed chain data gains a
+-- `chain_id` column that leads its keys. Off-chain data (fx_rates,
+-- address_labels, entity_addresses, webhook_subscriptions) stays shared.
+
+-- ─── Networks ───────────────────────────────────────────────────────────────
+-- Registered by each indexer at startup; the API resolves `?network=` here.
+CREATE TABLE IF NOT EXISTS networks (
+    chain_id      BIGINT PRIMARY KEY,
+    name          TEXT NOT NULL UNIQUE,
+    registered_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Rows indexed before this migration get chain_id 0. The first indexer to
+-- register a network adopts them and removes this placeholder.
+INSERT INTO networks (chain_id, name) VALUES (0, '(unassigned)');
+
+-- ─── Chain Columns ──────────────────────────────────────────────────────────
+ALTER TABLE indexed_blocks           ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE tokens                   ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE transfers                ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE accounts                 ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE hourly_stats             ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE indexer_state            ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE daily_stats              ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE daily_active_addresses   ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE monthly_stats            ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE monthly_active_addresses ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE token_totals             ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE global_totals            ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE known_addresses          ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE holder_snapshots         ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE holder_sets              ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE entity_flows             ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE reorgs                   ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE webhook_deliveries       ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE webhook_dead_letters     ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 0;
+
+-- ─── Keys ───────────────────────────────────────────────────────────────────
+-- Token foreign keys go first: they depend on tokens_pkey. They come back
+-- cascading updates so that relabelling a token's chain carries its rows along.
+ALTER TABLE transfers        DROP CONSTRAINT transfers_token_address_fkey;
+ALTER TABLE accounts         DROP CONSTRAINT accounts_token_address_fkey;
+ALTER TABLE hourly_stats     DROP CONSTRAINT hourly_stats_token_address_fkey;
+ALTER TABLE daily_stats      DROP CONSTRAINT daily_stats_token_address_fkey;
+ALTER TABLE monthly_stats    DROP CONSTRAINT monthly_stats_token_address_fkey;
+ALTER TABLE token_totals     DROP CONSTRAINT token_totals_token_address_fkey;
+ALTER TABLE holder_snapshots DROP CONSTRAINT holder_snapshots_token_address_fkey;
+ALTER TABLE entity_flows     DROP CONSTRAINT entity_flows_token_address_fkey;
+
+ALTER TABLE indexed_blocks DROP CONSTRAINT indexed_blocks_pkey,
+    ADD PRIMARY KEY (chain_id, block_number);
+ALTER TABLE tokens DROP CONSTRAINT tokens_pkey,
+    ADD PRIMARY KEY (chain_id, address);
+ALTER TABLE transfers DROP CONSTRAINT transfers_transaction_hash_log_index_key,
+    ADD UNIQUE (chain_id, transaction_hash, log_index);
+ALTER TABLE accounts DROP CONSTRAINT accounts_pkey,
+    ADD PRIMARY KEY (chain_id, address, token_address);
+ALTER TABLE hourly_stats DROP CONSTRAINT hourly_stats_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, hour);
+ALTER TABLE indexer_state DROP CONSTRAINT indexer_state_pkey,
+    ADD PRIMARY KEY (chain_id, key);
+ALTER TABLE daily_stats DROP CONSTRAINT daily_stats_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, day);
+ALTER TABLE daily_active_addresses DROP CONSTRAINT daily_active_addresses_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, day, address);
+ALTER TABLE monthly_stats DROP CONSTRAINT monthly_stats_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, month);
+ALTER TABLE monthly_active_addresses DROP CONSTRAINT monthly_active_addresses_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, month, address);
+ALTER TABLE token_totals DROP CONSTRAINT token_totals_pkey,
+    ADD PRIMARY KEY (chain_id, token_address);
+ALTER TABLE global_totals DROP CONSTRAINT global_totals_pkey,
+    DROP COLUMN id,
+    ADD PRIMARY KEY (chain_id);
+ALTER TABLE known_addresses DROP CONSTRAINT known_addresses_pkey,
+    ADD PRIMARY KEY (chain_id, address);
+ALTER TABLE holder_snapshots DROP CONSTRAINT holder_snapshots_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, day);
+ALTER TABLE holder_sets DROP CONSTRAINT holder_sets_pkey,
+    ADD PRIMARY KEY (chain_id, token_address, day, address);
+ALTER TABLE entity_flows DROP CONSTRAINT entity_flows_pkey,
+    ADD PRIMARY KEY (chain_id, day, token_address, source_entity, target_entity);
+
+ALTER TABLE transfers        ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE accounts         ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE hourly_stats     ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE daily_stats      ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE monthly_stats    ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE token_totals     ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE holder_snapshots ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+ALTER TABLE entity_flows     ADD FOREIGN KEY (chain_id, token_address) REFERENCES tokens(chain_id, address) ON UPDATE CASCADE;
+
+-- Writers always name the chain.
+ALTER TABLE indexed_blocks           ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE tokens                   ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE transfers                ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE accounts                 ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE hourly_stats             ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE indexer_state            ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE daily_stats              ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE daily_active_addresses   ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE monthly_stats            ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE monthly_active_addresses ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE token_totals             ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE global_totals            ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE known_addresses          ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE holder_snapshots         ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE holder_sets              ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE entity_flows             ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE reorgs                   ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE webhook_deliveries       ALTER COLUMN chain_id DROP DEFAULT;
+ALTER TABLE webhook_dead_letters     ALTER COLUMN chain_id DROP DEFAULT;
+
+-- ─── Indexes ────────────────────────────────────────────────────────────────
+DROP INDEX IF EXISTS idx_transfers_block;
+CREATE INDEX IF NOT EXISTS idx_transfers_chain_block ON transfers(chain_id, block_number);
+
+DROP INDEX IF EXISTS idx_known_addresses_block;
+CREATE INDEX IF NOT EXISTS idx_known_addresses_chain_block ON known_addresses(chain_id, first_seen_block);
+
+CREATE INDEX IF NOT EXISTS idx_reorgs_chain ON reorgs(chain_id, id);
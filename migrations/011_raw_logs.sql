@@ -0,0 +1,33 @@
+-- Tempulse: Raw Logs
+-- Every log emitted by a TIP-20 token or the factory, stored as fetched. The
+-- indexer ingests into this table, then derives transfers, balances and stats
+-- from it, so derived tables can be rebuilt without going back to the RPC.
+
+-- ─── Logs ───────────────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS logs (
+    chain_id         BIGINT NOT NULL,
+    block_number     BIGINT NOT NULL,
+    log_index        INT NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    address          TEXT NOT NULL,
+    topics           TEXT[] NOT NULL,      -- hex, event signature first
+    data             TEXT NOT NULL,        -- hex
+    block_timestamp  BIGINT,               -- unix seconds, when the RPC reports it
+    PRIMARY KEY (chain_id, block_number, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_logs_address ON logs(chain_id, address, block_number);
+
+-- ─── Cursors ────────────────────────────────────────────────────────────────
+-- `last_ingested_block` tracks the logs table; `last_indexed_block` keeps
+-- tracking the derived tables. Blocks indexed before this migration have no
+-- raw logs, so reprocessing may only start at `raw_logs_from_block`.
+INSERT INTO indexer_state (chain_id, key, value)
+SELECT chain_id, 'last_ingested_block', value
+FROM indexer_state WHERE key = 'last_indexed_block'
+ON CONFLICT (chain_id, key) DO NOTHING;
+
+INSERT INTO indexer_state (chain_id, key, value)
+SELECT chain_id, 'raw_logs_from_block', (value::BIGINT + 1)::TEXT
+FROM indexer_state WHERE key = 'last_indexed_block' AND value::BIGINT > 0
+ON CONFLICT (chain_id, key) DO NOTHING;
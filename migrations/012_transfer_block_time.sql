@@ -0,0 +1,98 @@
+-- Tempulse: Transfer Block Time
+-- Every transfer records the time of its block, and the time-bucketed tables
+-- (rollups, hourly stats) bucket on it instead of on `created_at`, which is
+-- when the row was inserted and moves whenever a range is reprocessed.
+
+-- ─── Column ─────────────────────────────────────────────────────────────────
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS block_time TIMESTAMP;
+
+-- Existing rows take the timestamp of their raw log, else of the nearest
+-- recorded block at or after theirs, else their insert time.
+UPDATE transfers t SET block_time = COALESCE(
+    (SELECT TO_TIMESTAMP(l.block_timestamp) AT TIME ZONE 'UTC'
+     FROM logs l
+     WHERE l.chain_id = t.chain_id AND l.block_number = t.block_number AND l.log_index = t.log_index),
+    (SELECT TO_TIMESTAMP(b.timestamp) AT TIME ZONE 'UTC'
+     FROM indexed_blocks b
+     WHERE b.chain_id = t.chain_id AND b.block_number >= t.block_number
+     ORDER BY b.block_number
+     LIMIT 1),
+    t.created_at
+);
+
+ALTER TABLE transfers ALTER COLUMN block_time SET NOT NULL;
+
+-- Rollback repair rebuilds every period from a cutoff day onwards.
+DROP INDEX IF EXISTS idx_transfers_created_at;
+CREATE INDEX IF NOT EXISTS idx_transfers_block_time ON transfers(chain_id, block_time);
+
+-- ─── Rebuild ────────────────────────────────────────────────────────────────
+-- Re-bucket what was bucketed on insert time.
+DELETE FROM daily_active_addresses;
+DELETE FROM monthly_active_addresses;
+DELETE FROM daily_stats;
+DELETE FROM monthly_stats;
+
+INSERT INTO daily_active_addresses (chain_id, token_address, day, address)
+SELECT DISTINCT t.chain_id, t.token_address, DATE(t.block_time), a.address
+FROM transfers t
+CROSS JOIN LATERAL (VALUES (t.from_address), (t.to_address)) AS a(address)
+WHERE a.address <> '0x0000000000000000000000000000000000000000';
+
+INSERT INTO monthly_active_addresses (chain_id, token_address, month, address)
+SELECT DISTINCT chain_id, token_address, DATE_TRUNC('month', day)::DATE, address
+FROM daily_active_addresses;
+
+INSERT INTO daily_stats (chain_id, token_address, day, transfer_count, transfer_volume, mint_count, mint_volume,
+                         burn_count, burn_volume, net_issuance, active_addresses)
+SELECT s.chain_id, s.token_address, s.day,
+       s.transfer_count, s.transfer_volume::TEXT, s.mint_count, s.mint_volume::TEXT,
+       s.burn_count, s.burn_volume::TEXT, (s.mint_volume - s.burn_volume)::TEXT,
+       (SELECT COUNT(*) FROM daily_active_addresses d
+        WHERE d.chain_id = s.chain_id AND d.token_address = s.token_address AND d.day = s.day)
+FROM (
+    SELECT chain_id, token_address, DATE(block_time) AS day,
+           COUNT(*) FILTER (WHERE event_type = 'transfer') AS transfer_count,
+           COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'transfer'), 0) AS transfer_volume,
+           COUNT(*) FILTER (WHERE event_type = 'mint') AS mint_count,
+           COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'mint'), 0) AS mint_volume,
+           COUNT(*) FILTER (WHERE event_type = 'burn') AS burn_count,
+           COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'burn'), 0) AS burn_volume
+    FROM transfers
+    GROUP BY chain_id, token_address, DATE(block_time)
+) s;
+
+INSERT INTO monthly_stats (chain_id, token_address, month, transfer_count, transfer_volume, mint_count, mint_volume,
+                           burn_count, burn_volume, net_issuance, active_addresses)
+SELECT s.chain_id, s.token_address, s.month,
+       s.transfer_count, s.transfer_volume::TEXT, s.mint_count, s.mint_volume::TEXT,
+       s.burn_count, s.burn_volume::TEXT, (s.mint_volume - s.burn_volume)::TEXT,
+       (SELECT COUNT(*) FROM monthly_active_addresses m
+        WHERE m.chain_id = s.chain_id AND m.token_address = s.token_address AND m.month = s.month)
+FROM (
+    SELECT chain_id, token_address, DATE_TRUNC('month', day)::DATE AS month,
+           SUM(transfer_count)::BIGINT AS transfer_count,
+           SUM(CAST(transfer_volume AS NUMERIC)) AS transfer_volume,
+           SUM(mint_count)::BIGINT AS mint_count,
+           SUM(CAST(mint_volume AS NUMERIC)) AS mint_volume,
+           SUM(burn_count)::BIGINT AS burn_count,
+           SUM(CAST(burn_volume AS NUMERIC)) AS burn_volume
+    FROM daily_stats
+    GROUP BY chain_id, token_address, DATE_TRUNC('month', day)
+) s;
+
+DELETE FROM hourly_stats;
+
+INSERT INTO hourly_stats (chain_id, token_address, hour, transfer_count, transfer_volume, mint_count, mint_volume,
+                          burn_count, burn_volume, unique_senders, unique_receivers)
+SELECT chain_id, token_address, DATE_TRUNC('hour', block_time),
+       COUNT(*) FILTER (WHERE event_type = 'transfer'),
+       COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'transfer'), 0)::TEXT,
+       COUNT(*) FILTER (WHERE event_type = 'mint'),
+       COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'mint'), 0)::TEXT,
+       COUNT(*) FILTER (WHERE event_type = 'burn'),
+       COALESCE(SUM(CAST(amount AS NUMERIC)) FILTER (WHERE event_type = 'burn'), 0)::TEXT,
+       COUNT(*) FILTER (WHERE from_address <> '0x0000000000000000000000000000000000000000'),
+       COUNT(*) FILTER (WHERE to_address <> '0x0000000000000000000000000000000000000000')
+FROM transfers
+GROUP BY chain_id, token_address, DATE_TRUNC('hour', block_time);
@@ -0,0 +1,17 @@
+-- Tempulse: Entity Flows by Block Time
+-- Entity flows bucket transfers on the day of their block (see 012) instead
+-- of the day they were stored.
+
+DELETE FROM entity_flows;
+
+INSERT INTO entity_flows (chain_id, token_address, day, source_entity, target_entity, volume, transfer_count)
+SELECT t.chain_id, t.token_address, DATE(t.block_time), e.source, e.target,
+       SUM(CAST(t.amount AS NUMERIC))::TEXT, COUNT(*)
+FROM transfers t
+LEFT JOIN entity_addresses fe ON fe.address = t.from_address
+LEFT JOIN entity_addresses te ON te.address = t.to_address
+CROSS JOIN LATERAL (
+    SELECT COALESCE(fe.entity, CASE WHEN t.from_address = '0x0000000000000000000000000000000000000000' THEN '(mint/burn)' ELSE '(unknown)' END) AS source,
+           COALESCE(te.entity, CASE WHEN t.to_address = '0x0000000000000000000000000000000000000000' THEN '(mint/burn)' ELSE '(unknown)' END) AS target
+) e
+GROUP BY 1, 2, 3, 4, 5;
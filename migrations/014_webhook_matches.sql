@@ -0,0 +1,46 @@
+-- Tempulse: Webhook Matches
+-- Deliveries are queued in the transaction that stores the transfers, instead
+-- of by a dispatcher scanning `transfers.id` past a high-water mark (ids commit
+-- out of order under concurrent writers, and re-derived rows get new ids).
+-- Every (subscription, transfer) pair ever matched is remembered by its
+-- position on chain, so a reprocessed or re-indexed transfer never fires twice.
+
+-- ─── Matches ────────────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS webhook_matches (
+    subscription_id  BIGINT NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    chain_id         BIGINT NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    log_index        INT NOT NULL,
+    matched_at       TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (subscription_id, chain_id, transaction_hash, log_index)
+);
+
+-- ─── Catch Up ───────────────────────────────────────────────────────────────
+-- Queue what the old dispatcher had not matched yet.
+INSERT INTO webhook_deliveries (chain_id, subscription_id, transfer_id, payload)
+SELECT t.chain_id, s.id, t.id,
+       json_build_object('event', 'transfer', 'subscription_id', s.id, 'transfer', row_to_json(t))::TEXT
+FROM transfers t
+JOIN indexer_state c ON c.chain_id = t.chain_id AND c.key = 'webhook_last_transfer_id'
+JOIN webhook_subscriptions s
+  ON s.active
+ AND (s.token_address IS NULL OR s.token_address = t.token_address)
+ AND (s.address IS NULL OR s.address = t.from_address OR s.address = t.to_address)
+ AND (s.event_type IS NULL OR s.event_type = t.event_type)
+ AND (s.min_amount IS NULL OR CAST(t.amount AS NUMERIC) >= CAST(s.min_amount AS NUMERIC))
+WHERE t.id > c.value::BIGINT
+ON CONFLICT (subscription_id, transfer_id) DO NOTHING;
+
+-- Every stored transfer a subscription's filters match counts as matched:
+-- it was delivered already, or predates the subscription.
+INSERT INTO webhook_matches (subscription_id, chain_id, transaction_hash, log_index)
+SELECT s.id, t.chain_id, t.transaction_hash, t.log_index
+FROM transfers t
+JOIN webhook_subscriptions s
+  ON (s.token_address IS NULL OR s.token_address = t.token_address)
+ AND (s.address IS NULL OR s.address = t.from_address OR s.address = t.to_address)
+ AND (s.event_type IS NULL OR s.event_type = t.event_type)
+ AND (s.min_amount IS NULL OR CAST(t.amount AS NUMERIC) >= CAST(s.min_amount AS NUMERIC))
+ON CONFLICT DO NOTHING;
+
+DELETE FROM indexer_state WHERE key = 'webhook_last_transfer_id';
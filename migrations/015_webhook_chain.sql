@@ -0,0 +1,49 @@
+-- Tempulse: Webhook Networks
+-- A subscription belongs to one network and only matches that chain's
+-- transfers. Before this migration subscriptions were shared, so each one
+-- keeps firing where it fired: it stays on the lowest registered chain and
+-- gets a copy (same URL, filters and secret) on every other registered chain,
+-- which takes over that chain's matches, queued deliveries and dead letters.
+
+-- ─── Column ─────────────────────────────────────────────────────────────────
+ALTER TABLE webhook_subscriptions ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+
+-- ─── Copies ─────────────────────────────────────────────────────────────────
+CREATE TEMPORARY TABLE webhook_copies AS
+SELECT s.id AS original_id, n.chain_id, nextval('webhook_subscriptions_id_seq') AS copy_id
+FROM webhook_subscriptions s
+CROSS JOIN networks n
+WHERE s.chain_id IS NULL
+  AND n.chain_id <> 0
+  AND n.chain_id > (SELECT MIN(chain_id) FROM networks WHERE chain_id <> 0);
+
+INSERT INTO webhook_subscriptions (id, chain_id, url, secret, description, token_address, address,
+                                   min_amount, event_type, active, created_at)
+SELECT c.copy_id, c.chain_id, s.url, s.secret, s.description, s.token_address, s.address,
+       s.min_amount, s.event_type, s.active, s.created_at
+FROM webhook_copies c
+JOIN webhook_subscriptions s ON s.id = c.original_id;
+
+UPDATE webhook_matches m SET subscription_id = c.copy_id
+FROM webhook_copies c
+WHERE m.subscription_id = c.original_id AND m.chain_id = c.chain_id;
+
+UPDATE webhook_deliveries d SET subscription_id = c.copy_id
+FROM webhook_copies c
+WHERE d.subscription_id = c.original_id AND d.chain_id = c.chain_id;
+
+UPDATE webhook_dead_letters d SET subscription_id = c.copy_id
+FROM webhook_copies c
+WHERE d.subscription_id = c.original_id AND d.chain_id = c.chain_id;
+
+DROP TABLE webhook_copies;
+
+-- Without a registered network, subscriptions wait under chain id 0 with the
+-- rest of the unassigned rows, and the first network adopts them.
+UPDATE webhook_subscriptions
+SET chain_id = COALESCE((SELECT MIN(chain_id) FROM networks WHERE chain_id <> 0), 0)
+WHERE chain_id IS NULL;
+
+ALTER TABLE webhook_subscriptions ALTER COLUMN chain_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_chain ON webhook_subscriptions(chain_id);
@@ -22,6 +22,7 @@ export interface Transfer {
   transaction_hash: string;
   block_number: number;
   log_index: number;
+  block_time: string;
   created_at: string;
 }
 